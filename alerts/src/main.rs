@@ -57,16 +57,30 @@ async fn eod_check(client: &ApiClient) -> bool {
 
     let current_day = Day::get_current_day();
 
+    // no end of day on holidays and PTO
+    if full_state.get_day_off(current_day).is_some() {
+        return false;
+    }
+
     let todays_entries = match full_state.get_vms_for_day(current_day) {
         Some(vms) => vms,
         None => return false,
     };
 
+    let target_minutes = full_state.targets.minutes_for_day(current_day);
+    if target_minutes <= 0 {
+        return false;
+    }
+
     let total_minutes = sum_to_nearest_quarter_hour(todays_entries.as_slice());
 
-    // 7.5 hours
-    if total_minutes >= 450 {
-        if let Err(e) = send_notification("You are close to 8 hours worked today!!") {
+    // half an hour before the day's target
+    if total_minutes as i64 >= target_minutes - 30 {
+        let message = format!(
+            "You are close to {} worked today!!",
+            format_target(target_minutes)
+        );
+        if let Err(e) = send_notification(&message) {
             log::error!("Failed to send notification: {:?}", e);
             return false;
         }
@@ -76,6 +90,13 @@ async fn eod_check(client: &ApiClient) -> bool {
     }
 }
 
+fn format_target(target_minutes: i64) -> String {
+    match (target_minutes / 60, target_minutes % 60) {
+        (hours, 0) => format!("{} hours", hours),
+        (hours, minutes) => format!("{}h{:02}m", hours, minutes),
+    }
+}
+
 fn sum_to_nearest_quarter_hour(entries: &[TimeEntryVM]) -> u16 {
     let total_time_millis: i64 = entries.iter().map(|entry| entry.real_total_time()).sum();
     let total_minutes = total_time_millis / 1000 / 60; // Convert milliseconds to minutes
@@ -4,17 +4,33 @@ CREATE TABLE IF NOT EXISTS time_tracking.charge_codes (
     id SERIAL PRIMARY KEY,
     alias TEXT,
     code TEXT,
-    is_nc BOOLEAN
+    is_nc BOOLEAN,
+    -- optional allotment, period is 0 week, 1 pay period, 2 month, 3 contract
+    budget_minutes INTEGER NULL,
+    budget_period SMALLINT NULL,
+    budget_start DATE NULL,
+    -- pinned to the top of the picker
+    is_favorite BOOLEAN NOT NULL DEFAULT FALSE,
+    -- last time an entry was given this code, for the picker's recents
+    last_used_at TIMESTAMPTZ NULL,
+    -- gets an entry with no time at the start of each day
+    is_pinned BOOLEAN NOT NULL DEFAULT FALSE,
+    -- the last day it got one, so deleting the entry keeps it gone for the day
+    pinned_through DATE NULL
 );
 
 CREATE TABLE IF NOT EXISTS time_tracking.time_entries (
     id SERIAL PRIMARY KEY,
-    start_time TIMESTAMP NULL,
+    start_time TIMESTAMPTZ NULL,
     total_time BIGINT,
     note TEXT,
     day SMALLINT,
-    created_at TIMESTAMP NOT NULL DEFAULT NOW(),
-    charge_code_id INTEGER NULL REFERENCES time_tracking.charge_codes(id) ON DELETE SET NULL
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    charge_code_id INTEGER NULL REFERENCES time_tracking.charge_codes(id) ON DELETE SET NULL,
+    -- order within the day once an entry has been moved, the rest follow by id
+    position INTEGER NULL,
+    -- when it went in the trash, it's purged once this is older than the retention
+    deleted_at TIMESTAMPTZ NULL
 );
 
 -- if you ever want time_entries to have many charge codes
@@ -23,3 +39,86 @@ CREATE TABLE IF NOT EXISTS time_tracking.time_entries (
 --    charge_code_id INTEGER REFERENCES time_tracking.charge_codes(id) ON DELETE CASCADE,
 --    PRIMARY KEY (time_entry_id, charge_code_id)
 --);
+
+CREATE TABLE IF NOT EXISTS time_tracking.monitor_audit_log (
+    id SERIAL PRIMARY KEY,
+    rule_name TEXT NOT NULL,
+    action TEXT NOT NULL,
+    entry_id INTEGER NOT NULL,
+    entry_before JSONB NULL,
+    entry_after JSONB NULL,
+    applied_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- day is the same 0 (Monday) to 4 (Friday) numbering used by time_entries
+CREATE TABLE IF NOT EXISTS time_tracking.daily_targets (
+    day SMALLINT PRIMARY KEY,
+    target_minutes INTEGER NOT NULL
+);
+
+-- single row table, the check keeps it that way
+CREATE TABLE IF NOT EXISTS time_tracking.weekly_target (
+    id BOOLEAN PRIMARY KEY DEFAULT TRUE CHECK (id),
+    target_minutes INTEGER NOT NULL
+);
+
+-- time from entries removed by the weekly cleanup, kept so the over/under balance carries forward
+CREATE TABLE IF NOT EXISTS time_tracking.week_balances (
+    week_start DATE PRIMARY KEY,
+    worked_minutes BIGINT NOT NULL,
+    target_minutes INTEGER NOT NULL
+);
+
+INSERT INTO time_tracking.daily_targets (day, target_minutes)
+VALUES (0, 480), (1, 480), (2, 480), (3, 480), (4, 480)
+ON CONFLICT (day) DO NOTHING;
+
+INSERT INTO time_tracking.weekly_target (target_minutes)
+VALUES (2400)
+ON CONFLICT (id) DO NOTHING;
+
+-- time from entries removed by the weekly cleanup, kept so budgets longer than a week add up
+CREATE TABLE IF NOT EXISTS time_tracking.charge_code_usage (
+    charge_code_id INTEGER REFERENCES time_tracking.charge_codes(id) ON DELETE CASCADE,
+    work_date DATE NOT NULL,
+    total_time BIGINT NOT NULL,
+    PRIMARY KEY (charge_code_id, work_date)
+);
+
+-- holidays (kind 0) and PTO (kind 1), an entry is created for each once its week comes around
+CREATE TABLE IF NOT EXISTS time_tracking.days_off (
+    date DATE PRIMARY KEY,
+    kind SMALLINT NOT NULL,
+    name TEXT NOT NULL,
+    minutes INTEGER NOT NULL,
+    charge_code_id INTEGER NULL REFERENCES time_tracking.charge_codes(id) ON DELETE SET NULL,
+    entry_created BOOLEAN NOT NULL DEFAULT FALSE,
+    entry_id INTEGER NULL REFERENCES time_tracking.time_entries(id) ON DELETE SET NULL
+);
+
+-- when each entry was worked, written when a timer stops or a start and end time is typed in
+CREATE TABLE IF NOT EXISTS time_tracking.time_segments (
+    id SERIAL PRIMARY KEY,
+    time_entry_id INTEGER NOT NULL REFERENCES time_tracking.time_entries(id) ON DELETE CASCADE,
+    start_time TIMESTAMPTZ NOT NULL,
+    end_time TIMESTAMPTZ NOT NULL
+);
+
+-- the #tags and ticket references parsed out of each entry's note, rewritten whenever the note is
+CREATE TABLE IF NOT EXISTS time_tracking.note_tags (
+    time_entry_id INTEGER NOT NULL REFERENCES time_tracking.time_entries(id) ON DELETE CASCADE,
+    tag TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS note_tags_tag_idx ON time_tracking.note_tags (tag);
+CREATE INDEX IF NOT EXISTS note_tags_time_entry_id_idx ON time_tracking.note_tags (time_entry_id);
+
+-- every change made through the API, with the entries before and after it, for undo and redo.
+-- undone operations are dropped when a new change comes in, like any undo stack
+CREATE TABLE IF NOT EXISTS time_tracking.operation_log (
+    id SERIAL PRIMARY KEY,
+    description TEXT NOT NULL,
+    changes JSONB NOT NULL,
+    is_undone BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
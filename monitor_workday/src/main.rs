@@ -1,5 +1,9 @@
 use monitor_actions::{
-    actions::{long_running_timer_check::LongTimerCheck, midnight_check::MidnightTimerCheck},
+    actions::{
+        budget_check::{BudgetCheck, DEFAULT_THRESHOLDS},
+        long_running_timer_check::LongTimerCheck,
+        midnight_check::MidnightTimerCheck,
+    },
     monitor_orchistrator::MonitorOrchestrator,
 };
 use shared_lib::api_client::ApiClient;
@@ -16,9 +20,13 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         std::env::var("SERVER_URL").unwrap_or_else(|_| "http://localhost:3000".to_string());
     let api_client = ApiClient::new(api_base_url);
 
-    let mut orchestrator = MonitorOrchestrator::new(api_client);
+    // print what would be changed instead of calling the api
+    let dry_run = std::env::args().any(|arg| arg == "--dry-run");
+
+    let mut orchestrator = MonitorOrchestrator::new(api_client).dry_run(dry_run);
     orchestrator.add_action(LongTimerCheck {});
     orchestrator.add_action(MidnightTimerCheck {});
+    orchestrator.add_action(BudgetCheck::new(budget_thresholds()));
 
     let check_interval = Duration::from_secs(600); // 10 minutes
 
@@ -30,3 +38,17 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         sleep(check_interval).await;
     }
 }
+
+// percentages of a charge code budget to warn at, e.g. "75,90,100"
+fn budget_thresholds() -> Vec<i64> {
+    std::env::var("BUDGET_WARNING_THRESHOLDS")
+        .ok()
+        .map(|value| {
+            value
+                .split(',')
+                .filter_map(|threshold| threshold.trim().parse().ok())
+                .collect::<Vec<i64>>()
+        })
+        .filter(|thresholds| !thresholds.is_empty())
+        .unwrap_or_else(|| DEFAULT_THRESHOLDS.to_vec())
+}
@@ -0,0 +1,148 @@
+use crate::monitor_actions::{MonitorAction, MonitorActionResult};
+use chrono::NaiveDate;
+use shared_lib::models::{
+    budget::{BudgetPeriod, BudgetUsage},
+    full_state::FullState,
+};
+use std::{collections::HashSet, sync::Mutex};
+
+pub const DEFAULT_THRESHOLDS: [i64; 3] = [75, 90, 100];
+
+pub struct BudgetCheck {
+    // percentages of a budget that trigger a warning
+    thresholds: Vec<i64>,
+    // (charge code, period start, threshold) already warned about, so each fires once a period
+    warned: Mutex<HashSet<(i32, NaiveDate, i64)>>,
+}
+
+impl BudgetCheck {
+    pub fn new(mut thresholds: Vec<i64>) -> Self {
+        thresholds.sort_unstable();
+        Self {
+            thresholds,
+            warned: Mutex::new(HashSet::new()),
+        }
+    }
+}
+
+impl Default for BudgetCheck {
+    fn default() -> Self {
+        Self::new(DEFAULT_THRESHOLDS.to_vec())
+    }
+}
+
+impl MonitorAction for BudgetCheck {
+    fn name(&self) -> &'static str {
+        "BudgetCheck"
+    }
+
+    // warnings can wait until the next working day
+    fn runs_on_days_off(&self) -> bool {
+        false
+    }
+
+    fn execute(&self, full_state: &FullState) -> Option<MonitorActionResult> {
+        let mut warned = self.warned.lock().unwrap();
+        let mut results: Vec<MonitorActionResult> = Vec::new();
+
+        for budget in &full_state.budgets {
+            let used_percent = budget.used_percent();
+            let newly_crossed = self
+                .thresholds
+                .iter()
+                .filter(|threshold| used_percent >= **threshold)
+                .filter(|threshold| {
+                    warned.insert((budget.charge_code_id, budget.period_start, **threshold))
+                })
+                .count();
+
+            // crossing several thresholds at once still only needs one notification
+            if newly_crossed > 0 {
+                results.push(MonitorActionResult::Notify(budget_message(budget)));
+            }
+        }
+
+        if !results.is_empty() {
+            Some(MonitorActionResult::Multiple(results))
+        } else {
+            None
+        }
+    }
+}
+
+fn budget_message(budget: &BudgetUsage) -> String {
+    let period = match budget.period {
+        BudgetPeriod::Week => "weekly",
+        BudgetPeriod::PayPeriod => "pay period",
+        BudgetPeriod::Month => "monthly",
+        BudgetPeriod::Contract => "contract",
+    };
+    let remaining = budget.remaining_minutes();
+
+    if remaining < 0 {
+        format!(
+            "{} is {} over its {} budget",
+            budget.alias,
+            format_minutes(-remaining),
+            period
+        )
+    } else {
+        format!(
+            "{} has used {}% of its {} budget, {} left",
+            budget.alias,
+            budget.used_percent(),
+            period,
+            format_minutes(remaining)
+        )
+    }
+}
+
+fn format_minutes(minutes: i64) -> String {
+    format!("{}h{:02}m", minutes / 60, minutes % 60)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state_with_usage(used_minutes: i64) -> FullState {
+        FullState {
+            budgets: vec![BudgetUsage {
+                charge_code_id: 1,
+                alias: "contract work".to_string(),
+                period: BudgetPeriod::Month,
+                period_start: NaiveDate::from_ymd_opt(2024, 3, 1).unwrap(),
+                budget_minutes: 600,
+                used_minutes,
+            }],
+            ..Default::default()
+        }
+    }
+
+    fn notification_count(result: Option<MonitorActionResult>) -> usize {
+        match result {
+            Some(MonitorActionResult::Multiple(results)) => results.len(),
+            _ => 0,
+        }
+    }
+
+    #[test]
+    fn warns_once_per_threshold() {
+        let check = BudgetCheck::default();
+
+        assert_eq!(notification_count(check.execute(&state_with_usage(300))), 0);
+        assert_eq!(notification_count(check.execute(&state_with_usage(460))), 1);
+        assert_eq!(notification_count(check.execute(&state_with_usage(470))), 0);
+        assert_eq!(notification_count(check.execute(&state_with_usage(550))), 1);
+        assert_eq!(notification_count(check.execute(&state_with_usage(610))), 1);
+        assert_eq!(notification_count(check.execute(&state_with_usage(700))), 0);
+    }
+
+    #[test]
+    fn jumping_past_several_thresholds_warns_once() {
+        let check = BudgetCheck::default();
+
+        assert_eq!(notification_count(check.execute(&state_with_usage(620))), 1);
+        assert_eq!(notification_count(check.execute(&state_with_usage(630))), 0);
+    }
+}
@@ -5,6 +5,10 @@ use crate::monitor_actions::{MonitorAction, MonitorActionResult};
 pub struct LongTimerCheck {}
 
 impl MonitorAction for LongTimerCheck {
+    fn name(&self) -> &'static str {
+        "LongTimerCheck"
+    }
+
     fn execute(&self, full_state: &FullState) -> Option<MonitorActionResult> {
         let cutoff_millis = 10 * 60 * 60 * 1000; // 10 hours in milliseconds
 
@@ -1,18 +1,27 @@
 use crate::monitor_actions::{MonitorAction, MonitorActionResult};
-use chrono::{NaiveDateTime, Utc};
-use shared_lib::models::full_state::FullState;
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
+use shared_lib::{
+    models::full_state::FullState,
+    utils::time_zone::{local_date, user_time_zone},
+};
 
 pub struct MidnightTimerCheck {}
 
 impl MonitorAction for MidnightTimerCheck {
+    fn name(&self) -> &'static str {
+        "MidnightTimerCheck"
+    }
+
     fn execute(&self, full_state: &FullState) -> Option<MonitorActionResult> {
         let mut results: Vec<MonitorActionResult> = Vec::new();
-        let now = Utc::now().naive_utc();
+        let now = Utc::now();
+        let tz = user_time_zone();
 
         for entries in full_state.time_entries.values() {
             for entry in entries {
                 if let Some(start_time) = entry.start_time {
-                    if is_past_midnight(start_time, now) {
+                    if is_past_midnight(start_time, now, tz) {
                         results.push(MonitorActionResult::StopTimer(entry.id));
                     }
                 }
@@ -27,7 +36,48 @@ impl MonitorAction for MidnightTimerCheck {
     }
 }
 
-fn is_past_midnight(start_time: NaiveDateTime, current_time: NaiveDateTime) -> bool {
-    // Check if the start time and current time are on different days
-    start_time.date() < current_time.date()
+fn is_past_midnight(start_time: DateTime<Utc>, current_time: DateTime<Utc>, tz: Tz) -> bool {
+    // Check if the start time and current time are on different days for the user
+    local_date(start_time, tz) < local_date(current_time, tz)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono_tz::America::Chicago;
+
+    fn utc(s: &str) -> DateTime<Utc> {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn evening_timer_is_not_stopped_when_utc_rolls_over() {
+        // 5pm to 7pm CST crosses midnight UTC, but not local midnight
+        let start = utc("2024-03-08T23:00:00Z");
+        let now = utc("2024-03-09T01:00:00Z");
+        assert!(!is_past_midnight(start, now, Chicago));
+    }
+
+    #[test]
+    fn timer_left_running_past_local_midnight_is_stopped() {
+        let start = utc("2024-03-08T23:00:00Z");
+        let now = utc("2024-03-09T06:30:00Z");
+        assert!(is_past_midnight(start, now, Chicago));
+    }
+
+    #[test]
+    fn spring_forward_night_is_still_one_day() {
+        // 1:30am CST to 3:30am CDT on 2024-03-10, one real hour apart
+        let start = utc("2024-03-10T07:30:00Z");
+        let now = utc("2024-03-10T08:30:00Z");
+        assert!(!is_past_midnight(start, now, Chicago));
+    }
+
+    #[test]
+    fn fall_back_crossing_midnight_is_detected() {
+        // 11:30pm CDT on 11-02 to the second 1:30am (CST) on 11-03
+        let start = utc("2024-11-03T04:30:00Z");
+        let now = utc("2024-11-03T07:30:00Z");
+        assert!(is_past_midnight(start, now, Chicago));
+    }
 }
@@ -1,2 +1,3 @@
+pub mod budget_check;
 pub mod long_running_timer_check;
 pub mod midnight_check;
@@ -6,11 +6,18 @@ pub mod actions;
 pub enum MonitorActionResult {
     StopTimer(i32),
     DeleteEntry(i32),
+    // a warning for the user, nothing on the server changes
+    Notify(String),
     Multiple(Vec<MonitorActionResult>),
 }
 
 pub trait MonitorAction {
+    /// Name recorded in the audit log for every change this rule makes
+    fn name(&self) -> &'static str;
+    /// Rules that only matter on working days can opt out of holidays and PTO
+    fn runs_on_days_off(&self) -> bool {
+        true
+    }
     fn execute(&self, full_state: &FullState) -> Option<MonitorActionResult>;
 }
 
-
@@ -1,12 +1,20 @@
 use crate::utils::error::{MonitorError, Result};
 use async_recursion::async_recursion;
-use shared_lib::{api_client::ApiClient, models::full_state::FullState};
+use chrono::Utc;
+use notify_rust::Notification;
+use shared_lib::{
+    api_client::ApiClient,
+    models::{
+        audit_log::MonitorAuditEntry, day::Day, full_state::FullState, time_entry::TimeEntryVM,
+    },
+};
 
 use super::{MonitorAction, MonitorActionResult};
 
 pub struct MonitorOrchestrator {
     actions: Vec<Box<dyn MonitorAction>>,
     client: ApiClient,
+    dry_run: bool,
 }
 
 impl MonitorOrchestrator {
@@ -14,9 +22,15 @@ impl MonitorOrchestrator {
         Self {
             actions: Vec::new(),
             client,
+            dry_run: false,
         }
     }
 
+    pub fn dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
     pub fn add_action<A: MonitorAction + 'static>(&mut self, action: A) {
         self.actions.push(Box::new(action));
     }
@@ -27,16 +41,19 @@ impl MonitorOrchestrator {
 
         let full_state = self.get_full_state().await?;
         let results = self.run(&full_state);
-        for result in results {
-            handle_monitor_action_result(&self.client, result).await?;
+        for (rule_name, result) in results {
+            self.handle_monitor_action_result(rule_name, result, &full_state)
+                .await?;
         }
         Ok(())
     }
 
-    fn run(&self, full_state: &FullState) -> Vec<MonitorActionResult> {
+    fn run(&self, full_state: &FullState) -> Vec<(&'static str, MonitorActionResult)> {
+        let is_day_off = full_state.get_day_off(Day::get_current_day()).is_some();
         self.actions
             .iter()
-            .filter_map(|action| action.execute(full_state))
+            .filter(|action| !is_day_off || action.runs_on_days_off())
+            .filter_map(|action| action.execute(full_state).map(|x| (action.name(), x)))
             .collect()
     }
 
@@ -48,34 +65,99 @@ impl MonitorOrchestrator {
     }
 
     async fn cleanup_old_timers(&self) -> Result<()> {
+        if self.dry_run {
+            println!("[dry-run] would clean up entries older than a week");
+            return Ok(());
+        }
+
         match self.client.cleanup_entries().await {
             Ok(state) => Ok(state),
             Err(e) => Err(MonitorError::ReqwestError(e)),
         }
     }
-}
 
-#[async_recursion]
-async fn handle_monitor_action_result(
-    client: &ApiClient,
-    result: MonitorActionResult,
-) -> Result<()> {
-    match result {
-        MonitorActionResult::StopTimer(entry_id) => {
-            log::info!("stopping timer: {}", entry_id);
-            client.pause_entry(entry_id).await?;
-            Ok(())
-        }
-        MonitorActionResult::DeleteEntry(entry_id) => {
-            log::info!("deleting entry: {}", entry_id);
-            client.delete_entry(entry_id).await?;
-            Ok(())
-        }
-        MonitorActionResult::Multiple(results) => {
-            for sub_result in results {
-                handle_monitor_action_result(client, sub_result).await?
+    #[async_recursion(?Send)]
+    async fn handle_monitor_action_result(
+        &self,
+        rule_name: &'static str,
+        result: MonitorActionResult,
+        full_state: &FullState,
+    ) -> Result<()> {
+        match result {
+            MonitorActionResult::StopTimer(entry_id) => {
+                let before = full_state.get_entry_by_id(entry_id).cloned();
+                if self.dry_run {
+                    println!("[dry-run] {}: would stop timer {}", rule_name, entry_id);
+                    return Ok(());
+                }
+
+                log::info!("{}: stopping timer: {}", rule_name, entry_id);
+                let day_entries = self.client.pause_entry(entry_id).await?;
+                let after = day_entries.entries.into_iter().find(|e| e.id == entry_id);
+                self.record_audit(rule_name, "StopTimer", entry_id, before, after)
+                    .await;
+                Ok(())
             }
-            Ok(())
+            MonitorActionResult::DeleteEntry(entry_id) => {
+                let before = full_state.get_entry_by_id(entry_id).cloned();
+                if self.dry_run {
+                    println!("[dry-run] {}: would delete entry {}", rule_name, entry_id);
+                    return Ok(());
+                }
+
+                log::info!("{}: deleting entry: {}", rule_name, entry_id);
+                self.client.delete_entry(entry_id).await?;
+                self.record_audit(rule_name, "DeleteEntry", entry_id, before, None)
+                    .await;
+                Ok(())
+            }
+            MonitorActionResult::Notify(message) => {
+                if self.dry_run {
+                    println!("[dry-run] {}: would notify: {}", rule_name, message);
+                    return Ok(());
+                }
+
+                log::warn!("{}: {}", rule_name, message);
+                // a missed notification shouldn't stop the other results from being handled
+                if let Err(e) = Notification::new()
+                    .summary("Rustic Notification")
+                    .body(&message)
+                    .show()
+                {
+                    log::error!("Failed to send notification: {}", e);
+                }
+                Ok(())
+            }
+            MonitorActionResult::Multiple(results) => {
+                for sub_result in results {
+                    self.handle_monitor_action_result(rule_name, sub_result, full_state)
+                        .await?
+                }
+                Ok(())
+            }
+        }
+    }
+
+    async fn record_audit(
+        &self,
+        rule_name: &str,
+        action: &str,
+        entry_id: i32,
+        entry_before: Option<TimeEntryVM>,
+        entry_after: Option<TimeEntryVM>,
+    ) {
+        let audit_entry = MonitorAuditEntry {
+            rule_name: rule_name.to_string(),
+            action: action.to_string(),
+            entry_id,
+            entry_before,
+            entry_after,
+            applied_at: Utc::now(),
+        };
+
+        // the change has already been applied, so a failed audit write shouldn't stop the run
+        if let Err(e) = self.client.create_audit_entry(&audit_entry).await {
+            log::error!("Failed to record audit entry for {}: {}", entry_id, e);
         }
     }
 }
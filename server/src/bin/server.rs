@@ -3,7 +3,11 @@ use axum::{
     Router,
 };
 use rustic_server::{
-    routes::{charge_code_routes::*, time_entry_routes::*},
+    routes::{
+        audit_log_routes::*, charge_code_routes::*, day_off_routes::*, operation_routes::*,
+        target_routes::*, time_entry_routes::*,
+    },
+    services::time_entry_service::reindex_note_tags,
     utils,
 };
 use std::net::SocketAddr;
@@ -14,10 +18,28 @@ async fn main() {
     env_logger::init();
 
     let pool = utils::connections::get_connection().await;
+    // notes saved before tags were indexed can be searched by tag too
+    if let Err(error) = reindex_note_tags(&pool).await {
+        log::error!("Failed to index note tags: {error:?}");
+    }
 
     let app = Router::new()
         .route("/full_state", get(get_everything_request))
+        .route("/status", get(get_status_request))
         .route("/time_entries/day/:day", post(create_time_entry_request))
+        .route(
+            "/time_entries/day/:day/range",
+            post(create_time_entry_with_range_request),
+        )
+        .route(
+            "/time_entries/day/:day/copy",
+            post(copy_time_entries_request),
+        )
+        .route("/time_entries/quick", post(create_quick_entry_request))
+        .route(
+            "/time_entries/day/:day/last_week",
+            get(get_last_weeks_entries_request),
+        )
         .route(
             "/time_entries/:id/charge_code/:code_id",
             put(update_time_entry_charge_code_request),
@@ -34,13 +56,51 @@ async fn main() {
             "/time_entries/:id/note",
             put(update_time_entry_note_request),
         )
+        .route("/time_entries/:id/range", put(set_time_entry_range_request))
         .route("/time_entries/:id/play", put(play_time_entry_request))
         .route("/time_entries/:id/pause", put(pause_time_entry_request))
+        .route("/time_entries/:id/split", post(split_time_entry_request))
+        .route("/time_entries/:id/merge", post(merge_time_entries_request))
+        .route(
+            "/time_entries/:id/move/:offset",
+            put(move_time_entry_request),
+        )
         .route("/time_entries/:id", delete(delete_time_entry_request))
+        .route("/time_entries/trash", get(get_trash_request))
+        .route("/time_entries/:id/restore", put(restore_time_entry_request))
         .route("/time_entries/costpoint", get(get_costpoint_entries))
+        .route("/time_segments", get(get_time_segments_request))
+        .route("/search", get(search_time_entries_request))
         .route("/time_entries/update", put(update_time_entry_request))
         .route("/charge_codes", get(get_charge_codes))
+        .route("/charge_codes/budgets", get(get_budget_usage_request))
+        .route(
+            "/charge_codes/:id/budget",
+            put(update_charge_code_budget_request),
+        )
+        .route(
+            "/charge_codes/:id/favorite",
+            put(update_charge_code_favorite_request),
+        )
+        .route(
+            "/charge_codes/:id/pinned",
+            put(update_charge_code_pinned_request),
+        )
+        .route(
+            "/targets",
+            get(get_targets_request).put(update_targets_request),
+        )
+        .route("/days_off", get(get_days_off_request))
+        .route("/days_off/import", post(import_days_off_request))
+        .route("/days_off/:date", delete(delete_day_off_request))
+        .route("/operations", get(get_operations_request))
+        .route("/operations/undo", post(undo_operation_request))
+        .route("/operations/redo", post(redo_operation_request))
         .route("/admin/cleanup", post(delete_old_entries_request))
+        .route(
+            "/audit_log",
+            get(get_audit_log_request).post(create_audit_entry_request),
+        )
         .layer(axum::extract::Extension(pool))
         .layer(CorsLayer::permissive());
 
@@ -0,0 +1,85 @@
+use crate::models::audit_log::MonitorAuditRaw;
+use shared_lib::models::audit_log::MonitorAuditEntry;
+use sqlx::{types::Json, Executor, Postgres};
+
+pub async fn insert_audit_entry<'e, E>(
+    exec: E,
+    entry: &MonitorAuditEntry,
+) -> Result<MonitorAuditRaw, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_as::<_, MonitorAuditRaw>(
+        "INSERT INTO time_tracking.monitor_audit_log (rule_name, action, entry_id, entry_before, entry_after, applied_at)
+         VALUES ($1, $2, $3, $4, $5, $6)
+         RETURNING id, rule_name, action, entry_id, entry_before, entry_after, applied_at",
+    )
+    .bind(&entry.rule_name)
+    .bind(&entry.action)
+    .bind(entry.entry_id)
+    .bind(entry.entry_before.as_ref().map(Json))
+    .bind(entry.entry_after.as_ref().map(Json))
+    .bind(entry.applied_at)
+    .fetch_one(exec)
+    .await
+}
+
+pub async fn fetch_audit_entries<'e, E>(exec: E) -> Result<Vec<MonitorAuditRaw>, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_as::<_, MonitorAuditRaw>(
+        "SELECT id, rule_name, action, entry_id, entry_before, entry_after, applied_at
+         FROM time_tracking.monitor_audit_log
+         ORDER BY applied_at DESC, id DESC",
+    )
+    .fetch_all(exec)
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::db::audit_log_repo::*;
+    use crate::utils::connections::get_connection;
+    use chrono::Utc;
+    use shared_lib::models::{day::Day, time_entry::TimeEntryVM};
+
+    #[tokio::test]
+    async fn can_record_audit_entry() {
+        let pool = get_connection().await;
+        let mut tx = pool.begin().await.unwrap();
+
+        let before = TimeEntryVM {
+            id: 42,
+            start_time: Some(Utc::now()),
+            note: "left running".to_string(),
+            day: Day::Monday,
+            is_active: true,
+            ..Default::default()
+        };
+        let after = TimeEntryVM {
+            start_time: None,
+            is_active: false,
+            total_time: 600000,
+            ..before.clone()
+        };
+        let entry = MonitorAuditEntry {
+            rule_name: "MidnightTimerCheck".to_string(),
+            action: "StopTimer".to_string(),
+            entry_id: 42,
+            entry_before: Some(before),
+            entry_after: Some(after),
+            applied_at: Utc::now(),
+        };
+
+        insert_audit_entry(&mut *tx, &entry).await.unwrap();
+        let entries = fetch_audit_entries(&mut *tx).await.unwrap();
+        let recorded: MonitorAuditEntry = entries.into_iter().next().unwrap().into();
+
+        assert_eq!(recorded.rule_name, entry.rule_name);
+        assert_eq!(recorded.entry_before, entry.entry_before);
+        assert_eq!(recorded.entry_after, entry.entry_after);
+
+        tx.rollback().await.unwrap()
+    }
+}
@@ -1,8 +1,268 @@
-use shared_lib::models::charge_code::ChargeCode;
-use sqlx::PgPool;
+use chrono::NaiveDate;
+use shared_lib::models::{budget::ChargeCodeBudget, charge_code::ChargeCode};
+use sqlx::{Executor, PgPool, Postgres};
 
 pub async fn fetch_charge_codes(pool: &PgPool) -> Result<Vec<ChargeCode>, sqlx::Error> {
-    sqlx::query_as::<_, ChargeCode>("SELECT id, alias, code, is_nc FROM time_tracking.charge_codes")
-        .fetch_all(pool)
+    sqlx::query_as::<_, ChargeCode>(
+        "SELECT id, alias, code, is_nc, budget_minutes, budget_period, budget_start,
+         is_favorite, last_used_at, is_pinned
+         FROM time_tracking.charge_codes",
+    )
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn update_charge_code_budget<'e, E>(
+    exec: E,
+    id: i32,
+    budget: Option<&ChargeCodeBudget>,
+) -> Result<(), sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query(
+        "UPDATE time_tracking.charge_codes
+         SET budget_minutes = $2, budget_period = $3, budget_start = $4
+         WHERE id = $1",
+    )
+    .bind(id)
+    .bind(budget.map(|b| b.minutes as i32))
+    .bind(budget.map(|b| b.period))
+    .bind(budget.and_then(|b| b.start))
+    .execute(exec)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn update_charge_code_favorite<'e, E>(
+    exec: E,
+    id: i32,
+    is_favorite: bool,
+) -> Result<(), sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query("UPDATE time_tracking.charge_codes SET is_favorite = $2 WHERE id = $1")
+        .bind(id)
+        .bind(is_favorite)
+        .execute(exec)
+        .await?;
+
+    Ok(())
+}
+
+pub async fn update_charge_code_pinned<'e, E>(
+    exec: E,
+    id: i32,
+    is_pinned: bool,
+) -> Result<(), sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query("UPDATE time_tracking.charge_codes SET is_pinned = $2 WHERE id = $1")
+        .bind(id)
+        .bind(is_pinned)
+        .execute(exec)
+        .await?;
+
+    Ok(())
+}
+
+/// Time charged to a code since `since`, from both rolled up and current entries,
+/// including any timer that is still running.
+pub async fn fetch_used_millis_since<'e, E>(
+    exec: E,
+    charge_code_id: i32,
+    since: NaiveDate,
+    time_zone: &str,
+) -> Result<i64, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_scalar::<_, i64>(
+        "SELECT CAST(
+            COALESCE((
+                SELECT SUM(u.total_time)
+                FROM time_tracking.charge_code_usage u
+                WHERE u.charge_code_id = $1 AND u.work_date >= $2
+            ), 0)
+            + COALESCE((
+                SELECT SUM(te.total_time + CASE WHEN te.start_time IS NOT NULL THEN EXTRACT(EPOCH FROM (NOW() - te.start_time)) * 1000 ELSE 0 END)
+                FROM time_tracking.time_entries te
+                WHERE te.charge_code_id = $1 AND (te.created_at AT TIME ZONE $3)::DATE >= $2
+                AND te.deleted_at IS NULL
+            ), 0)
+         AS BIGINT)",
+    )
+    .bind(charge_code_id)
+    .bind(since)
+    .bind(time_zone)
+    .fetch_one(exec)
+    .await
+}
+
+/// Adds the time from entries that are about to be removed by the weekly cleanup
+/// to the usage for their charge code.
+pub async fn roll_up_charge_code_usage<'e, E>(exec: E, time_zone: &str) -> Result<(), sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query(
+        "INSERT INTO time_tracking.charge_code_usage (charge_code_id, work_date, total_time)
+         SELECT te.charge_code_id, (te.created_at AT TIME ZONE $1)::DATE, SUM(te.total_time)
+         FROM time_tracking.time_entries te
+         WHERE te.created_at < NOW() - INTERVAL '7 days' AND te.charge_code_id IS NOT NULL
+         AND te.deleted_at IS NULL
+         GROUP BY 1, 2
+         ON CONFLICT (charge_code_id, work_date) DO UPDATE
+         SET total_time = time_tracking.charge_code_usage.total_time + EXCLUDED.total_time",
+    )
+    .bind(time_zone)
+    .execute(exec)
+    .await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::db::charge_code_repo::*;
+    use crate::db::time_entry_repo::{
+        create_time_entry, delete_old_time_entries, update_charge_code_for_time_entry,
+        update_time_for_time_entry,
+    };
+    use crate::utils::connections::get_connection;
+    use chrono::{Duration, Utc};
+    use shared_lib::models::{budget::BudgetPeriod, day::Day};
+
+    #[tokio::test]
+    async fn can_set_and_clear_budget() {
+        let pool = get_connection().await;
+        let mut tx = pool.begin().await.unwrap();
+
+        let code_id: i32 = sqlx::query_scalar(
+            "INSERT INTO time_tracking.charge_codes (alias, code, is_nc)
+             VALUES ('budgeted', 'B-1', false) RETURNING id",
+        )
+        .fetch_one(&mut *tx)
+        .await
+        .unwrap();
+        let budget = ChargeCodeBudget {
+            minutes: 600,
+            period: BudgetPeriod::Month,
+            start: None,
+        };
+
+        update_charge_code_budget(&mut *tx, code_id, Some(&budget))
+            .await
+            .unwrap();
+        let budgeted: ChargeCode = sqlx::query_as(
+            "SELECT id, alias, code, is_nc, budget_minutes, budget_period, budget_start,
+             is_favorite, last_used_at
+             FROM time_tracking.charge_codes WHERE id = $1",
+        )
+        .bind(code_id)
+        .fetch_one(&mut *tx)
+        .await
+        .unwrap();
+        assert_eq!(budgeted.budget(), Some(budget));
+
+        update_charge_code_budget(&mut *tx, code_id, None)
+            .await
+            .unwrap();
+        let cleared: Option<i32> = sqlx::query_scalar(
+            "SELECT budget_minutes FROM time_tracking.charge_codes WHERE id = $1",
+        )
+        .bind(code_id)
+        .fetch_one(&mut *tx)
+        .await
+        .unwrap();
+        assert_eq!(cleared, None);
+
+        tx.rollback().await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn usage_survives_the_weekly_cleanup() {
+        let pool = get_connection().await;
+        let mut tx = pool.begin().await.unwrap();
+
+        let code_id: i32 = sqlx::query_scalar(
+            "INSERT INTO time_tracking.charge_codes (alias, code, is_nc)
+             VALUES ('budgeted', 'B-1', false) RETURNING id",
+        )
+        .fetch_one(&mut *tx)
+        .await
+        .unwrap();
+        let old_entry = create_time_entry(&mut *tx, Day::Monday).await.unwrap();
+        let new_entry = create_time_entry(&mut *tx, Day::Tuesday).await.unwrap();
+        for (entry_id, millis) in [(old_entry.id, 3_600_000), (new_entry.id, 1_800_000)] {
+            update_charge_code_for_time_entry(&mut *tx, entry_id, code_id)
+                .await
+                .unwrap();
+            update_time_for_time_entry(&mut *tx, entry_id, millis)
+                .await
+                .unwrap();
+        }
+        sqlx::query(
+            "UPDATE time_tracking.time_entries SET created_at = NOW() - INTERVAL '10 days' WHERE id = $1",
+        )
+        .bind(old_entry.id)
+        .execute(&mut *tx)
         .await
+        .unwrap();
+
+        roll_up_charge_code_usage(&mut *tx, "UTC").await.unwrap();
+        delete_old_time_entries(&mut *tx).await.unwrap();
+
+        let since = (Utc::now() - Duration::days(30)).date_naive();
+        let used = fetch_used_millis_since(&mut *tx, code_id, since, "UTC")
+            .await
+            .unwrap();
+
+        assert_eq!(used, 5_400_000);
+
+        tx.rollback().await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn remembers_favorites_and_last_use() {
+        let pool = get_connection().await;
+        let mut tx = pool.begin().await.unwrap();
+
+        let code_id: i32 = sqlx::query_scalar(
+            "INSERT INTO time_tracking.charge_codes (alias, code, is_nc)
+             VALUES ('favorite', 'F-1', false) RETURNING id",
+        )
+        .fetch_one(&mut *tx)
+        .await
+        .unwrap();
+        let fetch_code = |code_id: i32| {
+            sqlx::query_as::<_, ChargeCode>(
+                "SELECT id, alias, code, is_nc, budget_minutes, budget_period, budget_start,
+                 is_favorite, last_used_at
+                 FROM time_tracking.charge_codes WHERE id = $1",
+            )
+            .bind(code_id)
+        };
+
+        let code = fetch_code(code_id).fetch_one(&mut *tx).await.unwrap();
+        assert!(!code.is_favorite);
+        assert_eq!(code.last_used_at, None);
+
+        update_charge_code_favorite(&mut *tx, code_id, true)
+            .await
+            .unwrap();
+        let entry = create_time_entry(&mut *tx, Day::Monday).await.unwrap();
+        update_charge_code_for_time_entry(&mut *tx, entry.id, code_id)
+            .await
+            .unwrap();
+
+        let code = fetch_code(code_id).fetch_one(&mut *tx).await.unwrap();
+        assert!(code.is_favorite);
+        assert!(code.last_used_at.is_some());
+
+        tx.rollback().await.unwrap()
+    }
 }
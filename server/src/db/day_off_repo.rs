@@ -0,0 +1,157 @@
+use crate::models::{day_off::DayOffRaw, time_entry::TimeEntryRaw};
+use chrono::{DateTime, NaiveDate, Utc};
+use shared_lib::models::{day::Day, day_off::DayOff};
+use sqlx::{Executor, Postgres};
+
+pub async fn upsert_day_off<'e, E>(exec: E, day_off: &DayOff) -> Result<(), sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query(
+        "INSERT INTO time_tracking.days_off (date, kind, name, minutes, charge_code_id)
+         VALUES ($1, $2, $3, $4, $5)
+         ON CONFLICT (date) DO UPDATE
+         SET kind = EXCLUDED.kind, name = EXCLUDED.name, minutes = EXCLUDED.minutes,
+             charge_code_id = EXCLUDED.charge_code_id",
+    )
+    .bind(day_off.date)
+    .bind(day_off.kind)
+    .bind(&day_off.name)
+    .bind(day_off.minutes as i32)
+    .bind(day_off.charge_code_id)
+    .execute(exec)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn fetch_days_off_between<'e, E>(
+    exec: E,
+    from: NaiveDate,
+    to: NaiveDate,
+) -> Result<Vec<DayOffRaw>, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_as::<_, DayOffRaw>(
+        "SELECT date, kind, name, minutes, charge_code_id, entry_id
+         FROM time_tracking.days_off
+         WHERE date BETWEEN $1 AND $2
+         ORDER BY date",
+    )
+    .bind(from)
+    .bind(to)
+    .fetch_all(exec)
+    .await
+}
+
+pub async fn fetch_days_off_without_entries<'e, E>(
+    exec: E,
+    from: NaiveDate,
+    to: NaiveDate,
+) -> Result<Vec<DayOffRaw>, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_as::<_, DayOffRaw>(
+        "SELECT date, kind, name, minutes, charge_code_id, entry_id
+         FROM time_tracking.days_off
+         WHERE date BETWEEN $1 AND $2 AND NOT entry_created
+         ORDER BY date",
+    )
+    .bind(from)
+    .bind(to)
+    .fetch_all(exec)
+    .await
+}
+
+pub async fn delete_day_off<'e, E>(exec: E, date: NaiveDate) -> Result<(), sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query("DELETE FROM time_tracking.days_off WHERE date = $1")
+        .bind(date)
+        .execute(exec)
+        .await?;
+
+    Ok(())
+}
+
+/// Creates the filled in entry for a day off and marks it as created,
+/// so deleting the entry later doesn't bring it back.
+pub async fn create_day_off_entry<'e, E>(
+    exec: E,
+    day_off: &DayOffRaw,
+    day: Day,
+    created_at: DateTime<Utc>,
+) -> Result<TimeEntryRaw, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_as::<_, TimeEntryRaw>(
+        "WITH entry AS (
+            INSERT INTO time_tracking.time_entries (start_time, total_time, note, day, created_at, charge_code_id)
+            VALUES (NULL, $1, $2, $3, $4, $5)
+            RETURNING id, start_time, total_time, note, day, charge_code_id
+         ), marked AS (
+            UPDATE time_tracking.days_off
+            SET entry_created = TRUE, entry_id = (SELECT id FROM entry)
+            WHERE date = $6
+         )
+         SELECT entry.id, entry.start_time, entry.total_time, entry.note, entry.day, entry.charge_code_id, cc.alias
+         FROM entry
+         LEFT JOIN time_tracking.charge_codes cc ON entry.charge_code_id = cc.id",
+    )
+    .bind(day_off.minutes as i64 * 60_000)
+    .bind(&day_off.name)
+    .bind(day as i16)
+    .bind(created_at)
+    .bind(day_off.charge_code_id)
+    .bind(day_off.date)
+    .fetch_one(exec)
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::db::day_off_repo::*;
+    use crate::utils::connections::get_connection;
+    use shared_lib::models::day_off::DayOffKind;
+
+    #[tokio::test]
+    async fn day_off_entry_is_only_created_once() {
+        let pool = get_connection().await;
+        let mut tx = pool.begin().await.unwrap();
+
+        let date = NaiveDate::from_ymd_opt(2024, 12, 25).unwrap();
+        let day_off = DayOff {
+            date,
+            kind: DayOffKind::Holiday,
+            name: "Christmas".to_string(),
+            minutes: 480,
+            charge_code_id: None,
+            entry_id: None,
+        };
+        upsert_day_off(&mut *tx, &day_off).await.unwrap();
+
+        let pending = fetch_days_off_without_entries(&mut *tx, date, date)
+            .await
+            .unwrap();
+        assert_eq!(pending.len(), 1);
+
+        let entry = create_day_off_entry(&mut *tx, &pending[0], Day::Wednesday, Utc::now())
+            .await
+            .unwrap();
+        assert_eq!(entry.total_time, 480 * 60_000);
+        assert_eq!(entry.note, "Christmas");
+
+        let pending = fetch_days_off_without_entries(&mut *tx, date, date)
+            .await
+            .unwrap();
+        let recorded = fetch_days_off_between(&mut *tx, date, date).await.unwrap();
+        assert!(pending.is_empty());
+        assert_eq!(recorded[0].entry_id, Some(entry.id));
+
+        tx.rollback().await.unwrap()
+    }
+}
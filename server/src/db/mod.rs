@@ -1,2 +1,7 @@
+pub mod audit_log_repo;
 pub mod charge_code_repo;
+pub mod day_off_repo;
+pub mod operation_repo;
+pub mod target_repo;
 pub mod time_entry_repo;
+pub mod time_segment_repo;
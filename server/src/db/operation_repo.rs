@@ -0,0 +1,370 @@
+use crate::models::operation::OperationRaw;
+use shared_lib::models::operation::EntryChange;
+use sqlx::{types::Json, Executor, Postgres};
+
+pub async fn insert_operation<'e, E>(
+    exec: E,
+    description: &str,
+    changes: &[EntryChange],
+) -> Result<OperationRaw, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_as::<_, OperationRaw>(
+        "INSERT INTO time_tracking.operation_log (description, changes)
+         VALUES ($1, $2)
+         RETURNING id, description, changes, is_undone, created_at",
+    )
+    .bind(description)
+    .bind(Json(changes))
+    .fetch_one(exec)
+    .await
+}
+
+/// Newest first
+pub async fn fetch_operations<'e, E>(exec: E, limit: i64) -> Result<Vec<OperationRaw>, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_as::<_, OperationRaw>(
+        "SELECT id, description, changes, is_undone, created_at
+         FROM time_tracking.operation_log
+         ORDER BY id DESC
+         LIMIT $1",
+    )
+    .bind(limit)
+    .fetch_all(exec)
+    .await
+}
+
+/// The operation an undo would reverse
+pub async fn fetch_last_done_operation<'e, E>(exec: E) -> Result<Option<OperationRaw>, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_as::<_, OperationRaw>(
+        "SELECT id, description, changes, is_undone, created_at
+         FROM time_tracking.operation_log
+         WHERE NOT is_undone
+         ORDER BY id DESC
+         LIMIT 1",
+    )
+    .fetch_optional(exec)
+    .await
+}
+
+/// The operation a redo would apply again
+pub async fn fetch_first_undone_operation<'e, E>(
+    exec: E,
+) -> Result<Option<OperationRaw>, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_as::<_, OperationRaw>(
+        "SELECT id, description, changes, is_undone, created_at
+         FROM time_tracking.operation_log
+         WHERE is_undone
+         ORDER BY id ASC
+         LIMIT 1",
+    )
+    .fetch_optional(exec)
+    .await
+}
+
+pub async fn set_operation_undone<'e, E>(
+    exec: E,
+    id: i32,
+    is_undone: bool,
+) -> Result<(), sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query("UPDATE time_tracking.operation_log SET is_undone = $2 WHERE id = $1")
+        .bind(id)
+        .bind(is_undone)
+        .execute(exec)
+        .await?;
+
+    Ok(())
+}
+
+pub async fn delete_undone_operations<'e, E>(exec: E) -> Result<(), sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query("DELETE FROM time_tracking.operation_log WHERE is_undone")
+        .execute(exec)
+        .await?;
+
+    Ok(())
+}
+
+/// Drops all but the newest `keep` operations
+pub async fn prune_operations<'e, E>(exec: E, keep: i64) -> Result<(), sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query(
+        "DELETE FROM time_tracking.operation_log
+         WHERE id NOT IN (SELECT id FROM time_tracking.operation_log ORDER BY id DESC LIMIT $1)",
+    )
+    .bind(keep)
+    .execute(exec)
+    .await?;
+
+    Ok(())
+}
+
+/// Drops operations that touched any of `entry_ids`
+pub async fn delete_operations_for_entries<'e, E>(
+    exec: E,
+    entry_ids: &[i32],
+) -> Result<(), sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query(
+        "DELETE FROM time_tracking.operation_log
+         WHERE EXISTS (
+             SELECT 1 FROM jsonb_array_elements(changes) AS change
+             WHERE COALESCE(change->'before'->>'id', change->'after'->>'id')::INTEGER = ANY($1)
+         )",
+    )
+    .bind(entry_ids)
+    .execute(exec)
+    .await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::db::operation_repo::*;
+    use crate::db::time_entry_repo::{
+        create_time_entry, delete_time_entry, fetch_time_entry_by_id, fetch_trashed_time_entries,
+        pause_time_entry, play_time_entry_and_return_day, restore_time_entry,
+        update_time_for_time_entry,
+    };
+    use crate::db::time_segment_repo::{
+        clear_time_segments, fetch_time_segments_for_entry, set_time_entry_range,
+    };
+    use crate::services::operation_service::{
+        record_operation, redo_next_operation, undo_last_operation,
+    };
+    use crate::utils::connections::get_connection;
+    use chrono::{Duration, SubsecRound, Utc};
+    use shared_lib::models::{
+        day::Day, operation::Operation, time_entry::TimeEntryVM, time_segment::TimeRange,
+    };
+    use sqlx::PgConnection;
+
+    fn change(total_time: i64) -> EntryChange {
+        let entry = TimeEntryVM {
+            id: 42,
+            day: Day::Monday,
+            ..Default::default()
+        };
+        EntryChange::new(
+            Some(entry.clone()),
+            Some(TimeEntryVM {
+                total_time,
+                ..entry
+            }),
+        )
+    }
+
+    #[tokio::test]
+    async fn undo_and_redo_walk_the_log() {
+        let pool = get_connection().await;
+        let mut tx = pool.begin().await.unwrap();
+
+        let first = insert_operation(&mut *tx, "first", &[change(1)])
+            .await
+            .unwrap();
+        let second = insert_operation(&mut *tx, "second", &[change(2)])
+            .await
+            .unwrap();
+
+        let last_done = fetch_last_done_operation(&mut *tx).await.unwrap().unwrap();
+        assert_eq!(last_done.id, second.id);
+        assert!(fetch_first_undone_operation(&mut *tx)
+            .await
+            .unwrap()
+            .is_none());
+
+        set_operation_undone(&mut *tx, second.id, true)
+            .await
+            .unwrap();
+        set_operation_undone(&mut *tx, first.id, true)
+            .await
+            .unwrap();
+        let next_redo = fetch_first_undone_operation(&mut *tx)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(next_redo.id, first.id);
+
+        let operations: Vec<Operation> = fetch_operations(&mut *tx, 10)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(Operation::from)
+            .collect();
+        assert_eq!(operations[0].description, "second");
+        assert_eq!(operations[0].changes, vec![change(2)]);
+        assert!(operations[0].is_undone);
+
+        delete_undone_operations(&mut *tx).await.unwrap();
+        assert!(fetch_operations(&mut *tx, 10).await.unwrap().is_empty());
+
+        tx.rollback().await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn cleaned_up_entries_leave_the_log() {
+        let pool = get_connection().await;
+        let mut tx = pool.begin().await.unwrap();
+
+        insert_operation(&mut *tx, "change", &[change(1)])
+            .await
+            .unwrap();
+        let created = EntryChange::new(None, change(1).after);
+        insert_operation(&mut *tx, "create", &[created])
+            .await
+            .unwrap();
+        delete_operations_for_entries(&mut *tx, &[7]).await.unwrap();
+        assert_eq!(fetch_operations(&mut *tx, 10).await.unwrap().len(), 2);
+
+        delete_operations_for_entries(&mut *tx, &[42])
+            .await
+            .unwrap();
+        assert!(fetch_operations(&mut *tx, 10).await.unwrap().is_empty());
+
+        tx.rollback().await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn pruning_keeps_the_newest() {
+        let pool = get_connection().await;
+        let mut tx = pool.begin().await.unwrap();
+
+        for total_time in 0..5 {
+            insert_operation(&mut *tx, "change", &[change(total_time)])
+                .await
+                .unwrap();
+        }
+        prune_operations(&mut *tx, 2).await.unwrap();
+
+        let operations = fetch_operations(&mut *tx, 10).await.unwrap();
+        assert_eq!(operations.len(), 2);
+        assert_eq!(operations[0].changes.0, vec![change(4)]);
+
+        tx.rollback().await.unwrap()
+    }
+
+    async fn is_in_trash(conn: &mut PgConnection, id: i32) -> bool {
+        fetch_trashed_time_entries(conn)
+            .await
+            .unwrap()
+            .iter()
+            .any(|entry| entry.id == id)
+    }
+
+    #[tokio::test]
+    async fn trash_survives_undo_and_redo() {
+        let pool = get_connection().await;
+        let mut tx = pool.begin().await.unwrap();
+
+        let entry = create_time_entry(&mut *tx, Day::Monday).await.unwrap();
+        delete_time_entry(&mut *tx, entry.id).await.unwrap();
+        let deleted = EntryChange::new(Some((&entry).into()), None).trashed();
+        record_operation(&mut tx, vec![deleted]).await.unwrap();
+
+        undo_last_operation(&mut tx).await.unwrap();
+        assert!(!is_in_trash(&mut tx, entry.id).await);
+        redo_next_operation(&mut tx).await.unwrap();
+        assert!(is_in_trash(&mut tx, entry.id).await);
+
+        restore_time_entry(&mut *tx, entry.id).await.unwrap();
+        let restored = fetch_time_entry_by_id(&mut *tx, entry.id).await.unwrap();
+        let restored = EntryChange::new(None, Some((&restored).into())).trashed();
+        record_operation(&mut tx, vec![restored]).await.unwrap();
+
+        undo_last_operation(&mut tx).await.unwrap();
+        assert!(is_in_trash(&mut tx, entry.id).await);
+
+        tx.rollback().await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn undo_and_redo_bring_the_segments_along() {
+        let pool = get_connection().await;
+        let mut tx = pool.begin().await.unwrap();
+        let start = Utc::now().trunc_subsecs(0) - Duration::hours(3);
+
+        // a typed in total drops the segments, undo puts them back
+        let typed = create_time_entry(&mut *tx, Day::Monday).await.unwrap();
+        let range = TimeRange {
+            start,
+            end: start + Duration::hours(1),
+        };
+        set_time_entry_range(&mut *tx, typed.id, range)
+            .await
+            .unwrap();
+        let before = fetch_time_entry_by_id(&mut *tx, typed.id).await.unwrap();
+        let before_segments = fetch_time_segments_for_entry(&mut *tx, typed.id)
+            .await
+            .unwrap();
+        update_time_for_time_entry(&mut *tx, typed.id, 2 * 3_600_000)
+            .await
+            .unwrap();
+        clear_time_segments(&mut *tx, typed.id).await.unwrap();
+        let after = fetch_time_entry_by_id(&mut *tx, typed.id).await.unwrap();
+        let change = EntryChange::new(Some(before.into()), Some(after.into()))
+            .with_segments(before_segments.clone(), Vec::new());
+        record_operation(&mut tx, vec![change]).await.unwrap();
+
+        undo_last_operation(&mut tx).await.unwrap();
+        let undone = fetch_time_segments_for_entry(&mut *tx, typed.id)
+            .await
+            .unwrap();
+        assert_eq!(undone, before_segments);
+        redo_next_operation(&mut tx).await.unwrap();
+        let redone = fetch_time_segments_for_entry(&mut *tx, typed.id)
+            .await
+            .unwrap();
+        assert!(redone.is_empty());
+
+        // an undone pause takes back the segment it wrote
+        let timed = create_time_entry(&mut *tx, Day::Monday).await.unwrap();
+        play_time_entry_and_return_day(&mut *tx, timed.id, start)
+            .await
+            .unwrap();
+        let running = fetch_time_entry_by_id(&mut *tx, timed.id).await.unwrap();
+        pause_time_entry(&mut *tx, timed.id, 3_600_000)
+            .await
+            .unwrap();
+        let paused = fetch_time_entry_by_id(&mut *tx, timed.id).await.unwrap();
+        let paused_segments = fetch_time_segments_for_entry(&mut *tx, timed.id)
+            .await
+            .unwrap();
+        let change = EntryChange::new(Some(running.into()), Some(paused.into()))
+            .with_segments(Vec::new(), paused_segments.clone());
+        record_operation(&mut tx, vec![change]).await.unwrap();
+
+        undo_last_operation(&mut tx).await.unwrap();
+        let undone = fetch_time_segments_for_entry(&mut *tx, timed.id)
+            .await
+            .unwrap();
+        assert!(undone.is_empty());
+        let restarted = fetch_time_entry_by_id(&mut *tx, timed.id).await.unwrap();
+        assert!(restarted.start_time.is_some());
+        redo_next_operation(&mut tx).await.unwrap();
+        let redone = fetch_time_segments_for_entry(&mut *tx, timed.id)
+            .await
+            .unwrap();
+        assert_eq!(redone, paused_segments);
+
+        tx.rollback().await.unwrap()
+    }
+}
@@ -0,0 +1,241 @@
+use crate::models::targets::{DailyTargetRaw, WeekBalanceRaw};
+use chrono::NaiveDate;
+use shared_lib::models::day::Day;
+use sqlx::{Executor, Postgres};
+
+pub async fn fetch_daily_targets<'e, E>(exec: E) -> Result<Vec<DailyTargetRaw>, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_as::<_, DailyTargetRaw>(
+        "SELECT day, target_minutes FROM time_tracking.daily_targets ORDER BY day",
+    )
+    .fetch_all(exec)
+    .await
+}
+
+pub async fn fetch_weekly_target<'e, E>(exec: E) -> Result<i32, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_scalar::<_, i32>("SELECT target_minutes FROM time_tracking.weekly_target")
+        .fetch_one(exec)
+        .await
+}
+
+pub async fn upsert_daily_target<'e, E>(
+    exec: E,
+    day: Day,
+    target_minutes: i32,
+) -> Result<(), sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query(
+        "INSERT INTO time_tracking.daily_targets (day, target_minutes)
+         VALUES ($1, $2)
+         ON CONFLICT (day) DO UPDATE SET target_minutes = EXCLUDED.target_minutes",
+    )
+    .bind(day)
+    .bind(target_minutes)
+    .execute(exec)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn upsert_weekly_target<'e, E>(exec: E, target_minutes: i32) -> Result<(), sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query(
+        "INSERT INTO time_tracking.weekly_target (target_minutes)
+         VALUES ($1)
+         ON CONFLICT (id) DO UPDATE SET target_minutes = EXCLUDED.target_minutes",
+    )
+    .bind(target_minutes)
+    .execute(exec)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn fetch_week_balances_before<'e, E>(
+    exec: E,
+    week_start: NaiveDate,
+) -> Result<Vec<WeekBalanceRaw>, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_as::<_, WeekBalanceRaw>(
+        "SELECT week_start, worked_minutes, target_minutes
+         FROM time_tracking.week_balances
+         WHERE week_start < $1",
+    )
+    .bind(week_start)
+    .fetch_all(exec)
+    .await
+}
+
+/// Totals for entries from previous weeks that haven't been cleaned up yet,
+/// measured against the current weekly target.
+pub async fn fetch_unsettled_week_balances<'e, E>(
+    exec: E,
+    time_zone: &str,
+    week_start: NaiveDate,
+) -> Result<Vec<WeekBalanceRaw>, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_as::<_, WeekBalanceRaw>(
+        "SELECT
+            DATE_TRUNC('week', te.created_at AT TIME ZONE $1)::DATE AS week_start,
+            CAST(SUM(te.total_time) / 60000 AS BIGINT) AS worked_minutes,
+            (SELECT target_minutes FROM time_tracking.weekly_target) AS target_minutes
+         FROM time_tracking.time_entries te
+         WHERE (te.created_at AT TIME ZONE $1)::DATE < $2 AND te.deleted_at IS NULL
+         GROUP BY 1",
+    )
+    .bind(time_zone)
+    .bind(week_start)
+    .fetch_all(exec)
+    .await
+}
+
+/// Entries from previous weeks that haven't been cleaned up yet. Their time is already in the
+/// carried balance, so it isn't counted again for this week.
+pub async fn fetch_earlier_week_entry_ids<'e, E>(
+    exec: E,
+    time_zone: &str,
+    week_start: NaiveDate,
+) -> Result<Vec<i32>, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_scalar(
+        "SELECT id
+         FROM time_tracking.time_entries
+         WHERE (created_at AT TIME ZONE $1)::DATE < $2 AND deleted_at IS NULL",
+    )
+    .bind(time_zone)
+    .bind(week_start)
+    .fetch_all(exec)
+    .await
+}
+
+/// Adds the time from entries that are about to be removed by the weekly cleanup
+/// to the balance for the week they were worked in.
+pub async fn roll_up_old_time_entries<'e, E>(exec: E, time_zone: &str) -> Result<(), sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query(
+        "INSERT INTO time_tracking.week_balances (week_start, worked_minutes, target_minutes)
+         SELECT
+            DATE_TRUNC('week', te.created_at AT TIME ZONE $1)::DATE,
+            CAST(SUM(te.total_time) / 60000 AS BIGINT),
+            (SELECT target_minutes FROM time_tracking.weekly_target)
+         FROM time_tracking.time_entries te
+         WHERE te.created_at < NOW() - INTERVAL '7 days' AND te.deleted_at IS NULL
+         GROUP BY 1
+         ON CONFLICT (week_start) DO UPDATE
+         SET worked_minutes = time_tracking.week_balances.worked_minutes + EXCLUDED.worked_minutes",
+    )
+    .bind(time_zone)
+    .execute(exec)
+    .await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::db::target_repo::*;
+    use crate::db::time_entry_repo::{
+        create_time_entry, delete_old_time_entries, update_time_for_time_entry,
+    };
+    use crate::utils::connections::get_connection;
+    use chrono::{Duration, Utc};
+
+    #[tokio::test]
+    async fn can_update_targets() {
+        let pool = get_connection().await;
+        let mut tx = pool.begin().await.unwrap();
+
+        upsert_daily_target(&mut *tx, Day::Friday, 240)
+            .await
+            .unwrap();
+        upsert_weekly_target(&mut *tx, 2160).await.unwrap();
+
+        let friday = fetch_daily_targets(&mut *tx)
+            .await
+            .unwrap()
+            .into_iter()
+            .find(|target| target.day == Day::Friday)
+            .unwrap();
+        let weekly = fetch_weekly_target(&mut *tx).await.unwrap();
+
+        assert_eq!(friday.target_minutes, 240);
+        assert_eq!(weekly, 2160);
+
+        tx.rollback().await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn cleaned_up_entries_keep_their_week_balance() {
+        let pool = get_connection().await;
+        let mut tx = pool.begin().await.unwrap();
+
+        let entry = create_time_entry(&mut *tx, Day::Monday).await.unwrap();
+        let two_hours_millis = 7_200_000;
+        update_time_for_time_entry(&mut *tx, entry.id, two_hours_millis)
+            .await
+            .unwrap();
+        sqlx::query(
+            "UPDATE time_tracking.time_entries SET created_at = NOW() - INTERVAL '10 days' WHERE id = $1",
+        )
+        .bind(entry.id)
+        .execute(&mut *tx)
+        .await
+        .unwrap();
+
+        roll_up_old_time_entries(&mut *tx, "UTC").await.unwrap();
+        delete_old_time_entries(&mut *tx).await.unwrap();
+
+        let next_week = (Utc::now() + Duration::days(7)).date_naive();
+        let balances = fetch_week_balances_before(&mut *tx, next_week)
+            .await
+            .unwrap();
+
+        assert_eq!(balances.len(), 1);
+        assert_eq!(balances[0].worked_minutes, 120);
+
+        tx.rollback().await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn last_weeks_entries_are_left_out_of_this_week() {
+        let pool = get_connection().await;
+        let mut tx = pool.begin().await.unwrap();
+
+        let old = create_time_entry(&mut *tx, Day::Tuesday).await.unwrap();
+        let new = create_time_entry(&mut *tx, Day::Tuesday).await.unwrap();
+        sqlx::query(
+            "UPDATE time_tracking.time_entries SET created_at = NOW() - INTERVAL '3 days' WHERE id = $1",
+        )
+        .bind(old.id)
+        .execute(&mut *tx)
+        .await
+        .unwrap();
+
+        let today = Utc::now().date_naive();
+        let earlier = fetch_earlier_week_entry_ids(&mut *tx, "UTC", today - Duration::days(1))
+            .await
+            .unwrap();
+
+        assert!(earlier.contains(&old.id));
+        assert!(!earlier.contains(&new.id));
+
+        tx.rollback().await.unwrap()
+    }
+}
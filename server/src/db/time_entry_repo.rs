@@ -1,6 +1,14 @@
-use crate::models::{costpoint_entry::CostpointEntryRaw, time_entry::TimeEntryRaw};
-use chrono::NaiveDateTime;
-use shared_lib::models::{day::Day, time_entry::TimeEntryVM};
+use crate::models::{
+    copy_day::EntryCopyRaw, costpoint_entry::CostpointEntryRaw, time_entry::TimeEntryRaw,
+};
+use chrono::{DateTime, NaiveDate, Utc};
+use shared_lib::{
+    models::{day::Day, time_entry::TimeEntryVM},
+    utils::{
+        note_tags::parse_tags,
+        time_zone::{start_of_local_day, user_time_zone},
+    },
+};
 use sqlx::{Executor, Postgres};
 use std::collections::HashMap;
 
@@ -9,9 +17,10 @@ where
     E: Executor<'e, Database = Postgres>,
 {
     sqlx::query_as::<_, TimeEntryRaw>(
-        "SELECT te.id, te.start_time, te.total_time, te.note, te.day, cc.id as charge_code_id, cc.alias
+        "SELECT te.id, te.start_time, te.total_time, te.note, te.day, cc.id as charge_code_id, cc.alias, te.position
          FROM time_tracking.time_entries te
-         LEFT JOIN time_tracking.charge_codes cc ON te.charge_code_id = cc.id"
+         LEFT JOIN time_tracking.charge_codes cc ON te.charge_code_id = cc.id
+         WHERE te.deleted_at IS NULL"
     )
     .fetch_all(exec)
     .await
@@ -25,24 +34,25 @@ where
         "SELECT te.id, te.start_time, te.total_time, te.note, te.day, cc.id as charge_code_id, cc.alias
          FROM time_tracking.time_entries te
          LEFT JOIN time_tracking.charge_codes cc ON te.charge_code_id = cc.id
-         WHERE te.start_time IS NOT NULL",
+         WHERE te.start_time IS NOT NULL AND te.deleted_at IS NULL",
     )
     .fetch_all(exec)
     .await
 }
 
 pub fn organize_time_entries_by_day(entries: Vec<TimeEntryRaw>) -> HashMap<Day, Vec<TimeEntryVM>> {
-    let mut map: HashMap<Day, Vec<TimeEntryVM>> = HashMap::new();
+    let mut map: HashMap<Day, Vec<TimeEntryRaw>> = HashMap::new();
 
     for entry in entries {
-        map.entry(entry.day).or_default().push(entry.into());
-    }
-
-    for vms in map.values_mut() {
-        vms.sort_by(|a, b| a.id.cmp(&b.id));
+        map.entry(entry.day).or_default().push(entry);
     }
 
-    map
+    map.into_iter()
+        .map(|(day, mut entries)| {
+            entries.sort_by_key(|entry| entry.day_order());
+            (day, entries.into_iter().map(TimeEntryVM::from).collect())
+        })
+        .collect()
 }
 
 pub async fn fetch_time_entries_for_day<'e, E>(
@@ -53,10 +63,10 @@ where
     E: Executor<'e, Database = Postgres>,
 {
     sqlx::query_as::<_, TimeEntryRaw>(
-        "SELECT te.id, te.start_time, te.total_time, te.note, te.day, cc.id as charge_code_id, cc.alias
+        "SELECT te.id, te.start_time, te.total_time, te.note, te.day, cc.id as charge_code_id, cc.alias, te.position
          FROM time_tracking.time_entries te
          LEFT JOIN time_tracking.charge_codes cc ON te.charge_code_id = cc.id
-         WHERE te.day = $1"
+         WHERE te.day = $1 AND te.deleted_at IS NULL"
     )
     .bind(day)
     .fetch_all(exec)
@@ -67,15 +77,24 @@ pub async fn upsert_time_entry<'e, E>(exec: E, update: TimeEntryVM) -> Result<()
 where
     E: Executor<'e, Database = Postgres>,
 {
+    let tags = parse_tags(&update.note);
+    // the note's tags are reindexed in the same statement
     sqlx::query(
-        "INSERT INTO time_tracking.time_entries (id, start_time, total_time, note, day, charge_code_id)
+        "WITH cleared AS (
+             DELETE FROM time_tracking.note_tags WHERE time_entry_id = $1
+         ), tagged AS (
+             INSERT INTO time_tracking.note_tags (time_entry_id, tag)
+             SELECT $1, UNNEST($7::TEXT[])
+         )
+         INSERT INTO time_tracking.time_entries (id, start_time, total_time, note, day, charge_code_id)
          VALUES ($1, $2, $3, $4, $5, $6)
          ON CONFLICT (id) DO UPDATE SET
          start_time = EXCLUDED.start_time,
          total_time = EXCLUDED.total_time,
          note = EXCLUDED.note,
          day = EXCLUDED.day,
-         charge_code_id = EXCLUDED.charge_code_id
+         charge_code_id = EXCLUDED.charge_code_id,
+         deleted_at = NULL
          RETURNING id, start_time, total_time, note, day, charge_code_id"
     )
     .bind(update.id)
@@ -84,6 +103,7 @@ where
     .bind(update.note)
     .bind(update.day as i16)
     .bind(update.charge_code.as_ref().map(|x| x.id))
+    .bind(tags)
     .execute(exec)
     .await?;
 
@@ -114,17 +134,69 @@ where
          VALUES ($1, $2, $3, $4, $5)
          RETURNING id, start_time, total_time, note, day, null as charge_code_id, null as alias",
     )
-    .bind(None::<NaiveDateTime>)
+    .bind(None::<DateTime<Utc>>)
     .bind(0.0)
     .bind("")
     .bind(day as i16)
-    .bind(day.into_date())
+    .bind(start_of_local_day(day.into_date(), user_time_zone()))
     .fetch_one(exec)
     .await?;
 
     Ok(time_entry)
 }
 
+/// Gives each pinned charge code an entry with no time on `date`, unless it already has one
+/// there or got one earlier that day. Returns the ids of the new entries.
+pub async fn create_pinned_time_entries<'e, E>(
+    exec: E,
+    date: NaiveDate,
+    day: Day,
+    created_at: DateTime<Utc>,
+) -> Result<Vec<i32>, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_scalar(
+        "WITH due AS (
+             UPDATE time_tracking.charge_codes
+             SET pinned_through = $1
+             WHERE is_pinned AND (pinned_through IS NULL OR pinned_through < $1)
+             RETURNING id
+         )
+         INSERT INTO time_tracking.time_entries (start_time, total_time, note, day, created_at, charge_code_id)
+         SELECT NULL, 0, '', $2, $3, due.id
+         FROM due
+         WHERE NOT EXISTS (
+             SELECT 1 FROM time_tracking.time_entries te
+             WHERE te.charge_code_id = due.id AND te.day = $2 AND te.created_at >= $3
+         )
+         RETURNING id",
+    )
+    .bind(date)
+    .bind(day as i16)
+    .bind(created_at)
+    .fetch_all(exec)
+    .await
+}
+
+/// Numbers a day's entries in the order of `ids`
+pub async fn set_time_entry_positions<'e, E>(exec: E, ids: &[i32]) -> Result<(), sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query(
+        "UPDATE time_tracking.time_entries te
+         SET position = ordered.position
+         FROM UNNEST($1::INTEGER[]) WITH ORDINALITY AS ordered(id, position)
+         WHERE te.id = ordered.id",
+    )
+    .bind(ids)
+    .execute(exec)
+    .await?;
+
+    Ok(())
+}
+
 pub async fn update_charge_code_for_time_entry<'e, E>(
     exec: E,
     entry_id: i32,
@@ -133,9 +205,12 @@ pub async fn update_charge_code_for_time_entry<'e, E>(
 where
     E: Executor<'e, Database = Postgres>,
 {
-    // First, update the charge code for the time entry
+    // marks the code as recently used in the same statement
     sqlx::query(
-        "UPDATE time_tracking.time_entries
+        "WITH used AS (
+             UPDATE time_tracking.charge_codes SET last_used_at = NOW() WHERE id = $2
+         )
+         UPDATE time_tracking.time_entries
          SET charge_code_id = $2
          WHERE id = $1",
     )
@@ -197,11 +272,55 @@ pub async fn update_time_entry_note<'e, E>(
 where
     E: Executor<'e, Database = Postgres>,
 {
-    sqlx::query("UPDATE time_tracking.time_entries SET note = $1 WHERE id = $2")
-        .bind(new_note)
-        .bind(id)
-        .execute(exec)
-        .await?;
+    let tags = parse_tags(&new_note);
+    sqlx::query(
+        "WITH cleared AS (
+             DELETE FROM time_tracking.note_tags WHERE time_entry_id = $2
+         ), tagged AS (
+             INSERT INTO time_tracking.note_tags (time_entry_id, tag)
+             SELECT $2, UNNEST($3::TEXT[])
+         )
+         UPDATE time_tracking.time_entries SET note = $1 WHERE id = $2",
+    )
+    .bind(new_note)
+    .bind(id)
+    .bind(tags)
+    .execute(exec)
+    .await?;
+
+    Ok(())
+}
+
+/// Every entry's id and note, trashed ones included so they're found once restored
+pub async fn fetch_all_notes<'e, E>(exec: E) -> Result<Vec<(i32, String)>, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_as("SELECT id, COALESCE(note, '') FROM time_tracking.time_entries")
+        .fetch_all(exec)
+        .await
+}
+
+/// Swaps every tag for these, given as an entry id and tag pair at each index
+pub async fn replace_all_note_tags<'e, E>(
+    exec: E,
+    entry_ids: Vec<i32>,
+    tags: Vec<String>,
+) -> Result<(), sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query(
+        "WITH cleared AS (
+             DELETE FROM time_tracking.note_tags
+         )
+         INSERT INTO time_tracking.note_tags (time_entry_id, tag)
+         SELECT * FROM UNNEST($1::INTEGER[], $2::TEXT[])",
+    )
+    .bind(entry_ids)
+    .bind(tags)
+    .execute(exec)
+    .await?;
 
     Ok(())
 }
@@ -209,7 +328,7 @@ where
 pub async fn play_time_entry_and_return_day<'e, E>(
     exec: E,
     id: i32,
-    start_time: NaiveDateTime,
+    start_time: DateTime<Utc>,
 ) -> Result<i16, sqlx::Error>
 where
     E: Executor<'e, Database = Postgres>,
@@ -229,8 +348,15 @@ pub async fn pause_time_entry<'e, E>(exec: E, id: i32, elapsed_time: i64) -> Res
 where
     E: Executor<'e, Database = Postgres>,
 {
+    // the stretch that just ended goes on the timeline
     sqlx::query(
-        "UPDATE time_tracking.time_entries SET total_time = total_time + $1, start_time = NULL WHERE id = $2",
+        "WITH segment AS (
+             INSERT INTO time_tracking.time_segments (time_entry_id, start_time, end_time)
+             SELECT id, start_time, start_time + $1 * INTERVAL '1 millisecond'
+             FROM time_tracking.time_entries
+             WHERE id = $2 AND start_time IS NOT NULL
+         )
+         UPDATE time_tracking.time_entries SET total_time = total_time + $1, start_time = NULL WHERE id = $2",
     )
     .bind(elapsed_time)
     .bind(id)
@@ -240,7 +366,67 @@ where
     Ok(())
 }
 
+/// Entries tagged with every one of `tags` whose notes also contain every one of `words`
+pub async fn search_time_entries<'e, E>(
+    exec: E,
+    tags: &[String],
+    words: &[String],
+) -> Result<Vec<TimeEntryRaw>, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_as::<_, TimeEntryRaw>(
+        "SELECT te.id, te.start_time, te.total_time, te.note, te.day, cc.id as charge_code_id, cc.alias
+         FROM time_tracking.time_entries te
+         LEFT JOIN time_tracking.charge_codes cc ON te.charge_code_id = cc.id
+         WHERE te.deleted_at IS NULL
+         AND NOT EXISTS (
+             SELECT 1 FROM UNNEST($1::TEXT[]) AS wanted(tag)
+             WHERE NOT EXISTS (
+                 SELECT 1 FROM time_tracking.note_tags nt
+                 WHERE nt.time_entry_id = te.id AND nt.tag = wanted.tag
+             )
+         )
+         AND NOT EXISTS (
+             SELECT 1 FROM UNNEST($2::TEXT[]) AS wanted(word)
+             WHERE STRPOS(LOWER(te.note), wanted.word) = 0
+         )
+         ORDER BY te.day, te.id",
+    )
+    .bind(tags)
+    .bind(words)
+    .fetch_all(exec)
+    .await
+}
+
+/// Moves an entry to the trash. A running timer is stopped first so its time is kept.
 pub async fn delete_time_entry<'e, E>(exec: E, id: i32) -> Result<(), sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query(
+        "WITH segment AS (
+             INSERT INTO time_tracking.time_segments (time_entry_id, start_time, end_time)
+             SELECT id, start_time, NOW()
+             FROM time_tracking.time_entries
+             WHERE id = $1 AND start_time IS NOT NULL
+         )
+         UPDATE time_tracking.time_entries
+         SET total_time = total_time
+                 + COALESCE(CAST(EXTRACT(EPOCH FROM (NOW() - start_time)) * 1000 AS BIGINT), 0),
+             start_time = NULL,
+             deleted_at = NOW()
+         WHERE id = $1",
+    )
+    .bind(id)
+    .execute(exec)
+    .await?;
+
+    Ok(())
+}
+
+/// Removes an entry for good, without going through the trash
+pub async fn purge_time_entry<'e, E>(exec: E, id: i32) -> Result<(), sqlx::Error>
 where
     E: Executor<'e, Database = Postgres>,
 {
@@ -252,21 +438,101 @@ where
     Ok(())
 }
 
-pub async fn delete_old_time_entries<'e, E>(exec: E) -> Result<(), sqlx::Error>
+/// The entries in the trash, most recently deleted first
+pub async fn fetch_trashed_time_entries<'e, E>(exec: E) -> Result<Vec<TimeEntryRaw>, sqlx::Error>
 where
     E: Executor<'e, Database = Postgres>,
 {
-    sqlx::query(
-        "DELETE FROM time_tracking.time_entries
-         WHERE created_at < NOW() - INTERVAL '7 days'",
+    sqlx::query_as::<_, TimeEntryRaw>(
+        "SELECT te.id, te.start_time, te.total_time, te.note, te.day, cc.id as charge_code_id, cc.alias, te.position, te.deleted_at
+         FROM time_tracking.time_entries te
+         LEFT JOIN time_tracking.charge_codes cc ON te.charge_code_id = cc.id
+         WHERE te.deleted_at IS NOT NULL
+         ORDER BY te.deleted_at DESC, te.id DESC"
     )
-    .execute(exec)
-    .await?;
+    .fetch_all(exec)
+    .await
+}
+
+pub async fn restore_time_entry<'e, E>(exec: E, id: i32) -> Result<(), sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query("UPDATE time_tracking.time_entries SET deleted_at = NULL WHERE id = $1")
+        .bind(id)
+        .execute(exec)
+        .await?;
 
     Ok(())
 }
 
-pub async fn fetch_costpoint_entries<'e, E>(exec: E) -> Result<Vec<CostpointEntryRaw>, sqlx::Error>
+/// Removes entries that have been in the trash longer than `retention_days` for good.
+/// Returns their ids.
+pub async fn purge_trashed_time_entries<'e, E>(
+    exec: E,
+    retention_days: i64,
+) -> Result<Vec<i32>, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_scalar(
+        "DELETE FROM time_tracking.time_entries
+         WHERE deleted_at < NOW() - $1 * INTERVAL '1 day'
+         RETURNING id",
+    )
+    .bind(retention_days as f64)
+    .fetch_all(exec)
+    .await
+}
+
+/// Returns the ids of the deleted entries
+pub async fn delete_old_time_entries<'e, E>(exec: E) -> Result<Vec<i32>, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_scalar(
+        "DELETE FROM time_tracking.time_entries
+         WHERE created_at < NOW() - INTERVAL '7 days'
+         RETURNING id",
+    )
+    .fetch_all(exec)
+    .await
+}
+
+/// What was tracked on `date`, in the order it was listed. Time from entries the weekly
+/// cleanup already removed comes from the usage kept for each charge code, without notes.
+pub async fn fetch_entry_copies_for_date<'e, E>(
+    exec: E,
+    date: NaiveDate,
+    time_zone: &str,
+) -> Result<Vec<EntryCopyRaw>, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_as::<_, EntryCopyRaw>(
+        "SELECT charge_code_id, alias, total_time, note FROM (
+             SELECT cc.id AS charge_code_id, cc.alias, te.total_time, te.note, 0 AS source, te.position, te.id
+             FROM time_tracking.time_entries te
+             LEFT JOIN time_tracking.charge_codes cc ON te.charge_code_id = cc.id
+             WHERE (te.created_at AT TIME ZONE $2)::DATE = $1 AND te.deleted_at IS NULL
+             UNION ALL
+             SELECT cc.id, cc.alias, u.total_time, '', 1, NULL, cc.id
+             FROM time_tracking.charge_code_usage u
+             JOIN time_tracking.charge_codes cc ON u.charge_code_id = cc.id
+             WHERE u.work_date = $1
+         ) copies
+         ORDER BY source, position NULLS LAST, id",
+    )
+    .bind(date)
+    .bind(time_zone)
+    .fetch_all(exec)
+    .await
+}
+
+pub async fn fetch_costpoint_entries<'e, E>(
+    exec: E,
+    time_zone: &str,
+) -> Result<Vec<CostpointEntryRaw>, sqlx::Error>
 where
     E: Executor<'e, Database = Postgres>,
 {
@@ -282,16 +548,19 @@ SELECT
             )
         ) AS BIGINT
     ) AS total_time_milliseconds,
-    TO_CHAR(te.created_at, 'MM/DD/YY') AS entry_date,
+    TO_CHAR(te.created_at AT TIME ZONE $1, 'MM/DD/YY') AS entry_date,
     STRING_AGG(te.note, '\n') AS notes
 FROM
     time_tracking.time_entries te
 LEFT JOIN
     time_tracking.charge_codes cc ON te.charge_code_id = cc.id
+WHERE
+    te.deleted_at IS NULL
 GROUP BY
-    cc.code, TO_CHAR(te.created_at, 'MM/DD/YY');
+    cc.code, entry_date;
 ",
     )
+    .bind(time_zone)
     .fetch_all(exec)
     .await?;
 
@@ -379,7 +648,7 @@ mod tests {
         let mut tx = pool.begin().await.unwrap();
 
         let entry = create_time_entry(&mut *tx, Day::Monday).await.unwrap();
-        let start_time: NaiveDateTime = Utc::now().naive_utc();
+        let start_time: DateTime<Utc> = Utc::now();
         play_time_entry_and_return_day(&mut *tx, entry.id, start_time)
             .await
             .unwrap();
@@ -400,7 +669,7 @@ mod tests {
         let mut tx = pool.begin().await.unwrap();
 
         let entry = create_time_entry(&mut *tx, Day::Monday).await.unwrap();
-        let start_time: NaiveDateTime = Utc::now().naive_utc();
+        let start_time: DateTime<Utc> = Utc::now();
         play_time_entry_and_return_day(&mut *tx, entry.id, start_time)
             .await
             .unwrap();
@@ -413,7 +682,7 @@ mod tests {
         // gets first 10 millis
         assert_eq!(paused_entry.total_time, ten_min_millis);
 
-        let start_time: NaiveDateTime = Utc::now().naive_utc();
+        let start_time: DateTime<Utc> = Utc::now();
         play_time_entry_and_return_day(&mut *tx, entry.id, start_time)
             .await
             .unwrap();
@@ -468,7 +737,7 @@ mod tests {
         let _entry2 = create_time_entry(&mut *tx, Day::Monday).await.unwrap();
         let _entry3 = create_time_entry(&mut *tx, Day::Monday).await.unwrap();
 
-        let start_time: NaiveDateTime = Utc::now().naive_utc();
+        let start_time: DateTime<Utc> = Utc::now();
         play_time_entry_and_return_day(&mut *tx, entry.id, start_time)
             .await
             .unwrap();
@@ -481,4 +750,298 @@ mod tests {
 
         tx.rollback().await.unwrap()
     }
+
+    fn strings(items: &[&str]) -> Vec<String> {
+        items.iter().map(|item| item.to_string()).collect()
+    }
+
+    #[tokio::test]
+    async fn notes_are_searchable_by_tag_and_word() {
+        let pool = get_connection().await;
+        let mut tx = pool.begin().await.unwrap();
+
+        let entry = create_time_entry(&mut *tx, Day::Monday).await.unwrap();
+        update_time_entry_note(&mut *tx, entry.id, "Fixed ABC-12 #Deploy".to_string())
+            .await
+            .unwrap();
+
+        let found = search_time_entries(
+            &mut *tx,
+            &strings(&["ABC-12", "#deploy"]),
+            &strings(&["fixed"]),
+        )
+        .await
+        .unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].id, entry.id);
+        assert!(search_time_entries(&mut *tx, &strings(&["#review"]), &[])
+            .await
+            .unwrap()
+            .is_empty());
+
+        // a restored entry comes back with its tags
+        let mut restored: TimeEntryVM = fetch_time_entry_by_id(&mut *tx, entry.id)
+            .await
+            .unwrap()
+            .into();
+        delete_time_entry(&mut *tx, entry.id).await.unwrap();
+        restored.note = "#review".to_string();
+        upsert_time_entry(&mut *tx, restored).await.unwrap();
+        let found = search_time_entries(&mut *tx, &strings(&["#review"]), &[])
+            .await
+            .unwrap();
+        assert_eq!(found.len(), 1);
+        assert!(search_time_entries(&mut *tx, &strings(&["#deploy"]), &[])
+            .await
+            .unwrap()
+            .is_empty());
+
+        tx.rollback().await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn notes_from_before_tags_can_be_reindexed() {
+        let pool = get_connection().await;
+        let mut tx = pool.begin().await.unwrap();
+
+        // saved straight to the table, like a note from before tags were indexed
+        let entry = create_time_entry(&mut *tx, Day::Monday).await.unwrap();
+        sqlx::query("UPDATE time_tracking.time_entries SET note = '#legacy work' WHERE id = $1")
+            .bind(entry.id)
+            .execute(&mut *tx)
+            .await
+            .unwrap();
+        assert!(search_time_entries(&mut *tx, &strings(&["#legacy"]), &[])
+            .await
+            .unwrap()
+            .is_empty());
+
+        let (entry_ids, tags): (Vec<i32>, Vec<String>) = fetch_all_notes(&mut *tx)
+            .await
+            .unwrap()
+            .into_iter()
+            .flat_map(|(id, note)| parse_tags(&note).into_iter().map(move |tag| (id, tag)))
+            .unzip();
+        replace_all_note_tags(&mut *tx, entry_ids, tags)
+            .await
+            .unwrap();
+
+        let found = search_time_entries(&mut *tx, &strings(&["#legacy"]), &[])
+            .await
+            .unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].id, entry.id);
+
+        tx.rollback().await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn moved_entries_come_before_the_rest() {
+        let pool = get_connection().await;
+        let mut tx = pool.begin().await.unwrap();
+
+        let first = create_time_entry(&mut *tx, Day::Monday).await.unwrap();
+        let second = create_time_entry(&mut *tx, Day::Monday).await.unwrap();
+        let third = create_time_entry(&mut *tx, Day::Monday).await.unwrap();
+        set_time_entry_positions(&mut *tx, &[second.id, first.id])
+            .await
+            .unwrap();
+
+        let entries = fetch_time_entries_for_day(&mut *tx, Day::Monday.into())
+            .await
+            .unwrap();
+        let ids: Vec<i32> = organize_time_entries_by_day(entries)[&Day::Monday]
+            .iter()
+            .map(|entry| entry.id)
+            .collect();
+        assert_eq!(ids, vec![second.id, first.id, third.id]);
+
+        tx.rollback().await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn pinned_codes_get_one_entry_a_day() {
+        let pool = get_connection().await;
+        let mut tx = pool.begin().await.unwrap();
+
+        let code_id: i32 = sqlx::query_scalar(
+            "INSERT INTO time_tracking.charge_codes (alias, code, is_nc, is_pinned)
+             VALUES ('standup', 'S-1', false, true) RETURNING id",
+        )
+        .fetch_one(&mut *tx)
+        .await
+        .unwrap();
+        let monday = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        let created_at = start_of_local_day(monday, user_time_zone());
+
+        let created = create_pinned_time_entries(&mut *tx, monday, Day::Monday, created_at)
+            .await
+            .unwrap();
+        assert_eq!(created.len(), 1);
+        let entry = fetch_time_entry_by_id(&mut *tx, created[0]).await.unwrap();
+        assert_eq!(entry.charge_code_id, Some(code_id));
+        assert_eq!(entry.total_time, 0);
+
+        // deleting it keeps it gone until the next day
+        delete_time_entry(&mut *tx, entry.id).await.unwrap();
+        let created = create_pinned_time_entries(&mut *tx, monday, Day::Monday, created_at)
+            .await
+            .unwrap();
+        assert!(created.is_empty());
+
+        // a day that already has the code doesn't get another
+        let tuesday = monday.succ_opt().unwrap();
+        let existing = create_time_entry(&mut *tx, Day::Tuesday).await.unwrap();
+        update_charge_code_for_time_entry(&mut *tx, existing.id, code_id)
+            .await
+            .unwrap();
+        sqlx::query("UPDATE time_tracking.time_entries SET created_at = $2 WHERE id = $1")
+            .bind(existing.id)
+            .bind(start_of_local_day(tuesday, user_time_zone()))
+            .execute(&mut *tx)
+            .await
+            .unwrap();
+        let created = create_pinned_time_entries(
+            &mut *tx,
+            tuesday,
+            Day::Tuesday,
+            start_of_local_day(tuesday, user_time_zone()),
+        )
+        .await
+        .unwrap();
+        assert!(created.is_empty());
+
+        tx.rollback().await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn copies_come_from_entries_and_rolled_up_usage() {
+        let pool = get_connection().await;
+        let mut tx = pool.begin().await.unwrap();
+
+        let code_id: i32 = sqlx::query_scalar(
+            "INSERT INTO time_tracking.charge_codes (alias, code, is_nc)
+             VALUES ('design', 'D-1', false) RETURNING id",
+        )
+        .fetch_one(&mut *tx)
+        .await
+        .unwrap();
+        let date = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        let entry = create_time_entry(&mut *tx, Day::Monday).await.unwrap();
+        update_time_entry_note(&mut *tx, entry.id, "review".to_string())
+            .await
+            .unwrap();
+        sqlx::query("UPDATE time_tracking.time_entries SET created_at = $2 WHERE id = $1")
+            .bind(entry.id)
+            .bind(start_of_local_day(date, user_time_zone()))
+            .execute(&mut *tx)
+            .await
+            .unwrap();
+        sqlx::query(
+            "INSERT INTO time_tracking.charge_code_usage (charge_code_id, work_date, total_time)
+             VALUES ($1, $2, 3600000)",
+        )
+        .bind(code_id)
+        .bind(date)
+        .execute(&mut *tx)
+        .await
+        .unwrap();
+
+        let copies = fetch_entry_copies_for_date(&mut *tx, date, user_time_zone().name())
+            .await
+            .unwrap();
+        assert_eq!(copies.len(), 2);
+        assert_eq!(copies[0].note, "review");
+        assert_eq!(copies[0].charge_code_id, None);
+        assert_eq!(copies[1].charge_code_id, Some(code_id));
+        assert_eq!(copies[1].total_time, 3_600_000);
+        assert_eq!(copies[1].note, "");
+
+        tx.rollback().await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn deleted_entries_wait_in_the_trash() {
+        let pool = get_connection().await;
+        let mut tx = pool.begin().await.unwrap();
+
+        let entry = create_time_entry(&mut *tx, Day::Monday).await.unwrap();
+        update_time_for_time_entry(&mut *tx, entry.id, 60_000)
+            .await
+            .unwrap();
+        play_time_entry_and_return_day(
+            &mut *tx,
+            entry.id,
+            Utc::now() - chrono::Duration::minutes(5),
+        )
+        .await
+        .unwrap();
+        delete_time_entry(&mut *tx, entry.id).await.unwrap();
+
+        let day = fetch_time_entries_for_day(&mut *tx, Day::Monday.into())
+            .await
+            .unwrap();
+        assert!(day.is_empty());
+        let trash = fetch_trashed_time_entries(&mut *tx).await.unwrap();
+        assert_eq!(trash.len(), 1);
+        assert!(trash[0].deleted_at.is_some());
+        // the running timer's time was kept
+        assert_eq!(trash[0].start_time, None);
+        assert!(trash[0].total_time > 5 * 60_000);
+
+        restore_time_entry(&mut *tx, entry.id).await.unwrap();
+        let day = fetch_time_entries_for_day(&mut *tx, Day::Monday.into())
+            .await
+            .unwrap();
+        assert_eq!(day.len(), 1);
+        assert!(fetch_trashed_time_entries(&mut *tx)
+            .await
+            .unwrap()
+            .is_empty());
+
+        // a purge skips the trash
+        purge_time_entry(&mut *tx, entry.id).await.unwrap();
+        assert!(fetch_time_entries_for_day(&mut *tx, Day::Monday.into())
+            .await
+            .unwrap()
+            .is_empty());
+        assert!(fetch_trashed_time_entries(&mut *tx)
+            .await
+            .unwrap()
+            .is_empty());
+
+        tx.rollback().await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn only_old_trash_is_purged() {
+        let pool = get_connection().await;
+        let mut tx = pool.begin().await.unwrap();
+
+        let old = create_time_entry(&mut *tx, Day::Monday).await.unwrap();
+        let recent = create_time_entry(&mut *tx, Day::Monday).await.unwrap();
+        let kept = create_time_entry(&mut *tx, Day::Monday).await.unwrap();
+        delete_time_entry(&mut *tx, old.id).await.unwrap();
+        delete_time_entry(&mut *tx, recent.id).await.unwrap();
+        sqlx::query(
+            "UPDATE time_tracking.time_entries SET deleted_at = NOW() - INTERVAL '3 days' WHERE id = $1",
+        )
+        .bind(old.id)
+        .execute(&mut *tx)
+        .await
+        .unwrap();
+
+        let purged = purge_trashed_time_entries(&mut *tx, 2).await.unwrap();
+        assert_eq!(purged, vec![old.id]);
+        let trash = fetch_trashed_time_entries(&mut *tx).await.unwrap();
+        assert_eq!(trash.len(), 1);
+        assert_eq!(trash[0].id, recent.id);
+        let day = fetch_time_entries_for_day(&mut *tx, Day::Monday.into())
+            .await
+            .unwrap();
+        assert_eq!(day.len(), 1);
+        assert_eq!(day[0].id, kept.id);
+
+        tx.rollback().await.unwrap()
+    }
 }
@@ -0,0 +1,334 @@
+use chrono::{DateTime, Utc};
+use shared_lib::models::time_segment::{TimeRange, TimeSegment};
+use sqlx::{Executor, Postgres};
+
+pub async fn fetch_all_time_segments<'e, E>(exec: E) -> Result<Vec<TimeSegment>, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_as::<_, TimeSegment>(
+        "SELECT ts.id, ts.time_entry_id, ts.start_time, ts.end_time
+         FROM time_tracking.time_segments ts
+         JOIN time_tracking.time_entries te ON ts.time_entry_id = te.id
+         WHERE te.deleted_at IS NULL
+         ORDER BY ts.start_time",
+    )
+    .fetch_all(exec)
+    .await
+}
+
+/// Makes `range` the only time on an entry, stopping its timer if it was running
+pub async fn set_time_entry_range<'e, E>(
+    exec: E,
+    entry_id: i32,
+    range: TimeRange,
+) -> Result<(), sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query(
+        "WITH cleared AS (
+             DELETE FROM time_tracking.time_segments WHERE time_entry_id = $1
+         ), segment AS (
+             INSERT INTO time_tracking.time_segments (time_entry_id, start_time, end_time)
+             VALUES ($1, $2, $3)
+         )
+         UPDATE time_tracking.time_entries
+         SET total_time = $4, start_time = NULL
+         WHERE id = $1",
+    )
+    .bind(entry_id)
+    .bind(range.start)
+    .bind(range.end)
+    .bind(range.millis())
+    .execute(exec)
+    .await?;
+
+    Ok(())
+}
+
+/// Drops an entry's segments, for a typed in total that doesn't say when the time was worked
+pub async fn clear_time_segments<'e, E>(exec: E, entry_id: i32) -> Result<(), sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query("DELETE FROM time_tracking.time_segments WHERE time_entry_id = $1")
+        .bind(entry_id)
+        .execute(exec)
+        .await?;
+
+    Ok(())
+}
+
+/// Hands the segments of `from_ids` to another entry, so they outlive the entries being merged away
+pub async fn move_time_segments<'e, E>(
+    exec: E,
+    from_ids: &[i32],
+    to_id: i32,
+) -> Result<(), sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query(
+        "UPDATE time_tracking.time_segments SET time_entry_id = $2 WHERE time_entry_id = ANY($1)",
+    )
+    .bind(from_ids)
+    .bind(to_id)
+    .execute(exec)
+    .await?;
+
+    Ok(())
+}
+
+/// One entry's segments, trashed or not, for the undo log
+pub async fn fetch_time_segments_for_entry<'e, E>(
+    exec: E,
+    entry_id: i32,
+) -> Result<Vec<TimeSegment>, sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    sqlx::query_as::<_, TimeSegment>(
+        "SELECT id, time_entry_id, start_time, end_time
+         FROM time_tracking.time_segments
+         WHERE time_entry_id = $1
+         ORDER BY start_time, id",
+    )
+    .bind(entry_id)
+    .fetch_all(exec)
+    .await
+}
+
+/// Makes `segments` exactly the entry's segments again, ids and all. Ones that have since moved
+/// to another entry come back from it.
+pub async fn replace_time_segments_for_entry<'e, E>(
+    exec: E,
+    entry_id: i32,
+    segments: &[TimeSegment],
+) -> Result<(), sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
+    let ids: Vec<i32> = segments.iter().map(|segment| segment.id).collect();
+    let starts: Vec<DateTime<Utc>> = segments.iter().map(|segment| segment.start_time).collect();
+    let ends: Vec<DateTime<Utc>> = segments.iter().map(|segment| segment.end_time).collect();
+    sqlx::query(
+        "WITH cleared AS (
+             DELETE FROM time_tracking.time_segments
+             WHERE time_entry_id = $1 AND id <> ALL($2)
+         )
+         INSERT INTO time_tracking.time_segments (id, time_entry_id, start_time, end_time)
+         SELECT id, $1, start_time, end_time
+         FROM UNNEST($2::INTEGER[], $3::TIMESTAMPTZ[], $4::TIMESTAMPTZ[])
+             AS restored (id, start_time, end_time)
+         ON CONFLICT (id) DO UPDATE SET
+         time_entry_id = EXCLUDED.time_entry_id,
+         start_time = EXCLUDED.start_time,
+         end_time = EXCLUDED.end_time",
+    )
+    .bind(entry_id)
+    .bind(ids)
+    .bind(starts)
+    .bind(ends)
+    .execute(exec)
+    .await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::db::time_entry_repo::{
+        add_time_to_entry, create_time_entry, fetch_time_entry_by_id, pause_time_entry,
+        play_time_entry_and_return_day, update_time_for_time_entry,
+    };
+    use crate::db::time_segment_repo::*;
+    use crate::services::{
+        operation_service::undo_last_operation, split_service::split_time_entry,
+    };
+    use crate::utils::connections::get_connection;
+    use chrono::{Duration, SubsecRound, Utc};
+    use shared_lib::models::{
+        day::Day,
+        split::{NoteSplit, SplitAmount, SplitRequest},
+    };
+
+    #[tokio::test]
+    async fn stopped_timers_and_ranges_are_kept() {
+        let pool = get_connection().await;
+        let mut tx = pool.begin().await.unwrap();
+
+        let entry = create_time_entry(&mut *tx, Day::Monday).await.unwrap();
+        // postgres keeps microseconds, so whole seconds compare cleanly
+        let started = Utc::now().trunc_subsecs(0) - Duration::minutes(30);
+        play_time_entry_and_return_day(&mut *tx, entry.id, started)
+            .await
+            .unwrap();
+        pause_time_entry(&mut *tx, entry.id, 30 * 60_000)
+            .await
+            .unwrap();
+
+        let segments: Vec<TimeSegment> = fetch_all_time_segments(&mut *tx)
+            .await
+            .unwrap()
+            .into_iter()
+            .filter(|segment| segment.time_entry_id == entry.id)
+            .collect();
+        assert_eq!(segments.len(), 1);
+        assert_eq!(
+            segments[0].end_time - segments[0].start_time,
+            Duration::minutes(30)
+        );
+
+        let range = TimeRange {
+            start: started - Duration::hours(2),
+            end: started - Duration::minutes(30),
+        };
+        set_time_entry_range(&mut *tx, entry.id, range)
+            .await
+            .unwrap();
+        let segments: Vec<TimeSegment> = fetch_all_time_segments(&mut *tx)
+            .await
+            .unwrap()
+            .into_iter()
+            .filter(|segment| segment.time_entry_id == entry.id)
+            .collect();
+        assert_eq!(segments.len(), 1);
+        assert_eq!(segments[0].start_time, range.start);
+        let updated = fetch_time_entry_by_id(&mut *tx, entry.id).await.unwrap();
+        assert_eq!(updated.total_time, 90 * 60_000);
+
+        tx.rollback().await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn merged_entries_keep_their_segments() {
+        let pool = get_connection().await;
+        let mut tx = pool.begin().await.unwrap();
+
+        let kept = create_time_entry(&mut *tx, Day::Monday).await.unwrap();
+        let merged = create_time_entry(&mut *tx, Day::Monday).await.unwrap();
+        let start = Utc::now().trunc_subsecs(0) - Duration::hours(3);
+        for (entry_id, offset) in [(kept.id, 0), (merged.id, 1)] {
+            let range = TimeRange {
+                start: start + Duration::hours(offset),
+                end: start + Duration::hours(offset) + Duration::minutes(30),
+            };
+            set_time_entry_range(&mut *tx, entry_id, range)
+                .await
+                .unwrap();
+        }
+
+        let merged_before = fetch_time_segments_for_entry(&mut *tx, merged.id)
+            .await
+            .unwrap();
+        move_time_segments(&mut *tx, &[merged.id], kept.id)
+            .await
+            .unwrap();
+        let segments: Vec<TimeSegment> = fetch_all_time_segments(&mut *tx)
+            .await
+            .unwrap()
+            .into_iter()
+            .filter(|segment| [kept.id, merged.id].contains(&segment.time_entry_id))
+            .collect();
+        assert_eq!(segments.len(), 2);
+        assert!(segments
+            .iter()
+            .all(|segment| segment.time_entry_id == kept.id));
+
+        // an undone merge hands them back
+        replace_time_segments_for_entry(&mut *tx, merged.id, &merged_before)
+            .await
+            .unwrap();
+        let merged_after = fetch_time_segments_for_entry(&mut *tx, merged.id)
+            .await
+            .unwrap();
+        assert_eq!(merged_after, merged_before);
+        let kept_after = fetch_time_segments_for_entry(&mut *tx, kept.id)
+            .await
+            .unwrap();
+        assert_eq!(kept_after.len(), 1);
+
+        tx.rollback().await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn typed_in_times_clear_the_segments() {
+        let pool = get_connection().await;
+        let mut tx = pool.begin().await.unwrap();
+
+        let set = create_time_entry(&mut *tx, Day::Monday).await.unwrap();
+        let added = create_time_entry(&mut *tx, Day::Monday).await.unwrap();
+        let start = Utc::now().trunc_subsecs(0) - Duration::hours(3);
+        for entry_id in [set.id, added.id] {
+            let range = TimeRange {
+                start,
+                end: start + Duration::minutes(30),
+            };
+            set_time_entry_range(&mut *tx, entry_id, range)
+                .await
+                .unwrap();
+        }
+
+        update_time_for_time_entry(&mut *tx, set.id, 60 * 60_000)
+            .await
+            .unwrap();
+        add_time_to_entry(&mut *tx, added.id, -10 * 60_000)
+            .await
+            .unwrap();
+        for entry_id in [set.id, added.id] {
+            clear_time_segments(&mut *tx, entry_id).await.unwrap();
+        }
+        let segments: Vec<TimeSegment> = fetch_all_time_segments(&mut *tx)
+            .await
+            .unwrap()
+            .into_iter()
+            .filter(|segment| [set.id, added.id].contains(&segment.time_entry_id))
+            .collect();
+        assert!(segments.is_empty());
+        let updated = fetch_time_entry_by_id(&mut *tx, added.id).await.unwrap();
+        assert_eq!(updated.total_time, 20 * 60_000);
+
+        tx.rollback().await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn splits_and_their_undo_keep_the_segments() {
+        let pool = get_connection().await;
+        let mut tx = pool.begin().await.unwrap();
+
+        let entry = create_time_entry(&mut *tx, Day::Monday).await.unwrap();
+        let start = Utc::now().trunc_subsecs(0) - Duration::hours(3);
+        let range = TimeRange {
+            start,
+            end: start + Duration::hours(1),
+        };
+        set_time_entry_range(&mut *tx, entry.id, range)
+            .await
+            .unwrap();
+        let entry_segments = |segments: Vec<TimeSegment>| -> Vec<TimeSegment> {
+            segments
+                .into_iter()
+                .filter(|segment| segment.time_entry_id == entry.id)
+                .collect()
+        };
+        let before = entry_segments(fetch_all_time_segments(&mut *tx).await.unwrap());
+
+        let request = SplitRequest {
+            amount: SplitAmount::Millis(20 * 60_000),
+            charge_code_id: None,
+            note: NoteSplit::Copy,
+        };
+        split_time_entry(&mut tx, entry.id, &request).await.unwrap();
+        let split = entry_segments(fetch_all_time_segments(&mut *tx).await.unwrap());
+        assert_eq!(split, before);
+
+        undo_last_operation(&mut tx).await.unwrap();
+        let undone = entry_segments(fetch_all_time_segments(&mut *tx).await.unwrap());
+        assert_eq!(undone, before);
+        let restored = fetch_time_entry_by_id(&mut *tx, entry.id).await.unwrap();
+        assert_eq!(restored.total_time, 60 * 60_000);
+
+        tx.rollback().await.unwrap()
+    }
+}
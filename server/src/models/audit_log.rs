@@ -0,0 +1,27 @@
+use chrono::{DateTime, Utc};
+use shared_lib::models::{audit_log::MonitorAuditEntry, time_entry::TimeEntryVM};
+use sqlx::types::Json;
+
+#[derive(sqlx::FromRow, Debug)]
+pub struct MonitorAuditRaw {
+    pub id: i32,
+    pub rule_name: String,
+    pub action: String,
+    pub entry_id: i32,
+    pub entry_before: Option<Json<TimeEntryVM>>,
+    pub entry_after: Option<Json<TimeEntryVM>>,
+    pub applied_at: DateTime<Utc>,
+}
+
+impl From<MonitorAuditRaw> for MonitorAuditEntry {
+    fn from(value: MonitorAuditRaw) -> Self {
+        Self {
+            rule_name: value.rule_name,
+            action: value.action,
+            entry_id: value.entry_id,
+            entry_before: value.entry_before.map(|json| json.0),
+            entry_after: value.entry_after.map(|json| json.0),
+            applied_at: value.applied_at,
+        }
+    }
+}
@@ -0,0 +1,23 @@
+use shared_lib::models::{charge_code::ChargeCodeVM, copy_day::EntryCopy};
+
+#[derive(sqlx::FromRow, Debug)]
+pub struct EntryCopyRaw {
+    pub charge_code_id: Option<i32>,
+    pub alias: Option<String>,
+    pub total_time: i64,
+    pub note: String,
+}
+
+impl From<EntryCopyRaw> for EntryCopy {
+    fn from(value: EntryCopyRaw) -> Self {
+        let charge_code = match (value.charge_code_id, value.alias) {
+            (Some(id), Some(alias)) => Some(ChargeCodeVM { id, alias }),
+            _ => None,
+        };
+        Self {
+            charge_code,
+            total_time: value.total_time,
+            note: value.note,
+        }
+    }
+}
@@ -1,3 +1,5 @@
+use shared_lib::models::costpoint_entry::CostpointEntryVM;
+
 #[derive(sqlx::FromRow, serde::Serialize)]
 pub struct CostpointEntryRaw {
     pub charge_code: Option<String>,
@@ -6,14 +8,6 @@ pub struct CostpointEntryRaw {
     pub notes: String,
 }
 
-#[derive(serde::Serialize)]
-pub struct CostpointEntryVM {
-    pub charge_code: String,
-    pub hours: String,
-    pub date: String,
-    pub notes: String,
-}
-
 impl TryFrom<CostpointEntryRaw> for CostpointEntryVM {
     type Error = anyhow::Error;
 
@@ -0,0 +1,25 @@
+use chrono::NaiveDate;
+use shared_lib::models::day_off::{DayOff, DayOffKind};
+
+#[derive(sqlx::FromRow, Debug)]
+pub struct DayOffRaw {
+    pub date: NaiveDate,
+    pub kind: DayOffKind,
+    pub name: String,
+    pub minutes: i32,
+    pub charge_code_id: Option<i32>,
+    pub entry_id: Option<i32>,
+}
+
+impl From<DayOffRaw> for DayOff {
+    fn from(value: DayOffRaw) -> Self {
+        Self {
+            date: value.date,
+            kind: value.kind,
+            name: value.name,
+            minutes: value.minutes as i64,
+            charge_code_id: value.charge_code_id,
+            entry_id: value.entry_id,
+        }
+    }
+}
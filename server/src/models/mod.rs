@@ -2,19 +2,37 @@ use self::time_entry::TimeEntryRaw;
 
 use shared_lib::models::{day::Day, time_entry::TimeEntryVM};
 
+pub mod audit_log;
+pub mod copy_day;
 pub mod costpoint_entry;
+pub mod day_off;
+pub mod operation;
+pub mod targets;
 pub mod time_entry;
 
 #[derive(serde::Serialize, Debug)]
 pub struct DayTimeEntries {
     pub day: Day,
     pub entries: Vec<TimeEntryVM>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub created_id: Option<i32>,
 }
 
 impl DayTimeEntries {
     pub fn new(day: Day, entries: &[TimeEntryRaw]) -> Self {
-        let mut vms: Vec<TimeEntryVM> = entries.iter().map(|x| x.into()).collect();
-        vms.sort_by(|a, b| a.id.cmp(&b.id));
-        Self { day, entries: vms }
+        let mut sorted: Vec<&TimeEntryRaw> = entries.iter().collect();
+        sorted.sort_by_key(|entry| entry.day_order());
+        let vms: Vec<TimeEntryVM> = sorted.into_iter().map(|x| x.into()).collect();
+        Self {
+            day,
+            entries: vms,
+            created_id: None,
+        }
+    }
+
+    /// Names the entry the request made, so clients don't have to pick it out of the day
+    pub fn with_created(mut self, id: i32) -> Self {
+        self.created_id = Some(id);
+        self
     }
 }
@@ -0,0 +1,24 @@
+use chrono::{DateTime, Utc};
+use shared_lib::models::operation::{EntryChange, Operation};
+use sqlx::types::Json;
+
+#[derive(sqlx::FromRow, Debug)]
+pub struct OperationRaw {
+    pub id: i32,
+    pub description: String,
+    pub changes: Json<Vec<EntryChange>>,
+    pub is_undone: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<OperationRaw> for Operation {
+    fn from(value: OperationRaw) -> Self {
+        Self {
+            id: value.id,
+            description: value.description,
+            changes: value.changes.0,
+            is_undone: value.is_undone,
+            created_at: value.created_at,
+        }
+    }
+}
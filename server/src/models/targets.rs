@@ -0,0 +1,15 @@
+use chrono::NaiveDate;
+use shared_lib::models::day::Day;
+
+#[derive(sqlx::FromRow, Debug)]
+pub struct DailyTargetRaw {
+    pub day: Day,
+    pub target_minutes: i32,
+}
+
+#[derive(sqlx::FromRow, Debug)]
+pub struct WeekBalanceRaw {
+    pub week_start: NaiveDate,
+    pub worked_minutes: i64,
+    pub target_minutes: i32,
+}
@@ -1,16 +1,31 @@
-use chrono::NaiveDateTime;
-use shared_lib::models::{charge_code::ChargeCodeVM, day::Day, time_entry::TimeEntryVM};
+use chrono::{DateTime, Utc};
+use shared_lib::models::{
+    charge_code::ChargeCodeVM, day::Day, time_entry::TimeEntryVM, trash::TrashedEntry,
+};
 
 #[derive(sqlx::FromRow, Debug)]
 pub struct TimeEntryRaw {
     pub id: i32,
-    pub start_time: Option<NaiveDateTime>,
+    pub start_time: Option<DateTime<Utc>>,
     pub total_time: i64, // milliseconds
     pub note: String,
     // Fields for charge code
     pub charge_code_id: Option<i32>,
     pub day: Day,
     pub alias: Option<String>,
+    // only selected where the order of a day matters
+    #[sqlx(default)]
+    pub position: Option<i32>,
+    // only selected for the trash
+    #[sqlx(default)]
+    pub deleted_at: Option<DateTime<Utc>>,
+}
+
+impl TimeEntryRaw {
+    /// Moved entries by their position, then the rest in the order they were created
+    pub fn day_order(&self) -> (i32, i32) {
+        (self.position.unwrap_or(i32::MAX), self.id)
+    }
 }
 
 impl From<TimeEntryRaw> for TimeEntryVM {
@@ -50,3 +65,13 @@ impl From<&TimeEntryRaw> for TimeEntryVM {
     }
 }
 
+impl From<TimeEntryRaw> for TrashedEntry {
+    fn from(value: TimeEntryRaw) -> Self {
+        Self {
+            // always set on entries read from the trash
+            deleted_at: value.deleted_at.unwrap_or_default(),
+            entry: value.into(),
+        }
+    }
+}
+
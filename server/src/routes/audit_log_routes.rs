@@ -0,0 +1,20 @@
+use crate::db::audit_log_repo::{fetch_audit_entries, insert_audit_entry};
+use crate::utils::error::Result;
+use axum::{http::StatusCode, Extension, Json};
+use shared_lib::models::audit_log::MonitorAuditEntry;
+use sqlx::PgPool;
+
+pub async fn create_audit_entry_request(
+    Extension(pool): Extension<PgPool>,
+    Json(entry): Json<MonitorAuditEntry>,
+) -> Result<StatusCode> {
+    insert_audit_entry(&pool, &entry).await?;
+    Ok(StatusCode::CREATED)
+}
+
+pub async fn get_audit_log_request(
+    Extension(pool): Extension<PgPool>,
+) -> Result<Json<Vec<MonitorAuditEntry>>> {
+    let records = fetch_audit_entries(&pool).await?;
+    Ok(Json(records.into_iter().map(|x| x.into()).collect()))
+}
@@ -1,7 +1,14 @@
-use crate::db::charge_code_repo::fetch_charge_codes;
+use crate::db::charge_code_repo::{
+    fetch_charge_codes, update_charge_code_budget, update_charge_code_favorite,
+    update_charge_code_pinned,
+};
+use crate::services::budget_service::fetch_budget_usage;
 use crate::utils::error::Result;
-use axum::{Extension, Json};
-use shared_lib::models::charge_code::ChargeCode;
+use axum::{extract::Path, Extension, Json};
+use shared_lib::models::{
+    budget::{BudgetUsage, ChargeCodeBudget},
+    charge_code::ChargeCode,
+};
 use sqlx::PgPool;
 
 pub async fn get_charge_codes(Extension(pool): Extension<PgPool>) -> Result<Json<Vec<ChargeCode>>> {
@@ -9,3 +16,45 @@ pub async fn get_charge_codes(Extension(pool): Extension<PgPool>) -> Result<Json
 
     Ok(Json(records))
 }
+
+pub async fn get_budget_usage_request(
+    Extension(pool): Extension<PgPool>,
+) -> Result<Json<Vec<BudgetUsage>>> {
+    let usage = fetch_budget_usage(&pool).await?;
+
+    Ok(Json(usage))
+}
+
+// a null body removes the budget
+pub async fn update_charge_code_budget_request(
+    Path(id): Path<i32>,
+    Extension(pool): Extension<PgPool>,
+    Json(budget): Json<Option<ChargeCodeBudget>>,
+) -> Result<Json<Vec<BudgetUsage>>> {
+    update_charge_code_budget(&pool, id, budget.as_ref()).await?;
+    let usage = fetch_budget_usage(&pool).await?;
+
+    Ok(Json(usage))
+}
+
+pub async fn update_charge_code_favorite_request(
+    Path(id): Path<i32>,
+    Extension(pool): Extension<PgPool>,
+    Json(is_favorite): Json<bool>,
+) -> Result<Json<Vec<ChargeCode>>> {
+    update_charge_code_favorite(&pool, id, is_favorite).await?;
+    let records = fetch_charge_codes(&pool).await?;
+
+    Ok(Json(records))
+}
+
+pub async fn update_charge_code_pinned_request(
+    Path(id): Path<i32>,
+    Extension(pool): Extension<PgPool>,
+    Json(is_pinned): Json<bool>,
+) -> Result<Json<Vec<ChargeCode>>> {
+    update_charge_code_pinned(&pool, id, is_pinned).await?;
+    let records = fetch_charge_codes(&pool).await?;
+
+    Ok(Json(records))
+}
@@ -0,0 +1,44 @@
+use crate::db::day_off_repo::delete_day_off;
+use crate::services::day_off_service::{fetch_this_weeks_days_off, import_days_off};
+use crate::utils::error::Result;
+use axum::{
+    extract::{Path, Query},
+    http::StatusCode,
+    Extension, Json,
+};
+use chrono::NaiveDate;
+use serde::Deserialize;
+use shared_lib::models::day_off::{DayOff, DayOffKind};
+use sqlx::PgPool;
+
+#[derive(Deserialize)]
+pub struct ImportParams {
+    // calendar files don't say what kind of day off each event is
+    pto: Option<bool>,
+}
+
+pub async fn get_days_off_request(Extension(pool): Extension<PgPool>) -> Result<Json<Vec<DayOff>>> {
+    let days_off = fetch_this_weeks_days_off(&pool).await?;
+    Ok(Json(days_off))
+}
+
+pub async fn import_days_off_request(
+    Query(params): Query<ImportParams>,
+    Extension(pool): Extension<PgPool>,
+    body: String,
+) -> Result<Json<Vec<DayOff>>> {
+    let default_kind = match params.pto {
+        Some(true) => DayOffKind::Pto,
+        _ => DayOffKind::Holiday,
+    };
+    let days_off = import_days_off(&pool, &body, default_kind).await?;
+    Ok(Json(days_off))
+}
+
+pub async fn delete_day_off_request(
+    Path(date): Path<NaiveDate>,
+    Extension(pool): Extension<PgPool>,
+) -> Result<StatusCode> {
+    delete_day_off(&pool, date).await?;
+    Ok(StatusCode::OK)
+}
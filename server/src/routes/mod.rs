@@ -1,2 +1,6 @@
+pub mod audit_log_routes;
+pub mod day_off_routes;
+pub mod operation_routes;
+pub mod target_routes;
 pub mod time_entry_routes;
 pub mod charge_code_routes;
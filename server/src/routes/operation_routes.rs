@@ -0,0 +1,43 @@
+use crate::db::operation_repo::fetch_operations;
+use crate::services::operation_service::{redo_next_operation, undo_last_operation};
+use crate::utils::error::Result;
+use axum::{extract::Query, Extension, Json};
+use serde::Deserialize;
+use shared_lib::models::operation::Operation;
+use sqlx::PgPool;
+
+const DEFAULT_HISTORY_LIMIT: i64 = 100;
+
+#[derive(Deserialize)]
+pub struct HistoryParams {
+    limit: Option<i64>,
+}
+
+pub async fn get_operations_request(
+    Query(params): Query<HistoryParams>,
+    Extension(pool): Extension<PgPool>,
+) -> Result<Json<Vec<Operation>>> {
+    let limit = params.limit.unwrap_or(DEFAULT_HISTORY_LIMIT);
+    let operations = fetch_operations(&pool, limit).await?;
+    Ok(Json(operations.into_iter().map(Operation::from).collect()))
+}
+
+/// `null` when there's nothing left to undo
+pub async fn undo_operation_request(
+    Extension(pool): Extension<PgPool>,
+) -> Result<Json<Option<Operation>>> {
+    let mut tx = pool.begin().await?;
+    let operation = undo_last_operation(&mut tx).await?;
+    tx.commit().await?;
+    Ok(Json(operation))
+}
+
+/// `null` when there's nothing left to redo
+pub async fn redo_operation_request(
+    Extension(pool): Extension<PgPool>,
+) -> Result<Json<Option<Operation>>> {
+    let mut tx = pool.begin().await?;
+    let operation = redo_next_operation(&mut tx).await?;
+    tx.commit().await?;
+    Ok(Json(operation))
+}
@@ -0,0 +1,19 @@
+use crate::services::target_service::{fetch_work_targets, update_work_targets};
+use crate::utils::error::Result;
+use axum::{Extension, Json};
+use shared_lib::models::targets::WorkTargets;
+use sqlx::PgPool;
+
+pub async fn get_targets_request(Extension(pool): Extension<PgPool>) -> Result<Json<WorkTargets>> {
+    let targets = fetch_work_targets(&pool).await?;
+    Ok(Json(targets))
+}
+
+pub async fn update_targets_request(
+    Extension(pool): Extension<PgPool>,
+    Json(targets): Json<WorkTargets>,
+) -> Result<Json<WorkTargets>> {
+    update_work_targets(&pool, &targets).await?;
+    let targets = fetch_work_targets(&pool).await?;
+    Ok(Json(targets))
+}
@@ -1,41 +1,216 @@
 use crate::db::charge_code_repo::fetch_charge_codes;
 use crate::db::time_entry_repo::update_time_entry_note;
 use crate::db::time_entry_repo::*;
-use crate::models::costpoint_entry::CostpointEntryVM;
-use crate::models::DayTimeEntries;
-use crate::services::time_entry_service::switch_to_timer;
-use crate::utils::error::Result;
+use crate::db::time_segment_repo::{
+    clear_time_segments, fetch_all_time_segments, fetch_time_segments_for_entry,
+    set_time_entry_range,
+};
+use crate::models::{time_entry::TimeEntryRaw, DayTimeEntries};
+use crate::services::budget_service::fetch_budget_usage;
+use crate::services::day_off_service::{create_pending_day_off_entries, fetch_this_weeks_days_off};
+use crate::services::operation_service::record_operation;
+use crate::services::split_service::{merge_time_entries, split_time_entry};
+use crate::services::target_service::{fetch_earlier_week_entries, fetch_work_targets};
+use crate::services::time_entry_service::{
+    clean_up_old_entries, copy_entries_to_day, create_pinned_entries, create_quick_entry,
+    fetch_last_weeks_entries, fetch_timer_status, move_time_entry, switch_to_timer,
+};
+use crate::utils::error::{AppError, Result};
 use crate::utils::time::get_elapsed_time;
-use axum::{extract::Path, http::StatusCode, Extension, Json};
+use axum::{
+    extract::{Path, Query},
+    http::StatusCode,
+    Extension, Json,
+};
 use serde::{Deserialize, Serialize};
-use shared_lib::models::{full_state::FullState, time_entry::TimeEntryVM};
-use sqlx::PgPool;
+use shared_lib::{
+    models::{
+        copy_day::{CopyDayRequest, EntryCopy},
+        costpoint_entry::CostpointEntryVM,
+        full_state::FullState,
+        operation::EntryChange,
+        quick_entry::{QuickEntry, QuickTime},
+        split::SplitRequest,
+        time_entry::TimeEntryVM,
+        time_segment::{TimeRange, TimeSegment},
+        timer_status::TimerStatus,
+        trash::TrashedEntry,
+    },
+    utils::{note_tags::split_query, time_zone::user_time_zone},
+};
+use sqlx::{PgConnection, PgPool};
+
+// an entry and its segments before a change, for `record_change`
+async fn snapshot(conn: &mut PgConnection, id: i32) -> Result<(TimeEntryRaw, Vec<TimeSegment>)> {
+    let entry = fetch_time_entry_by_id(&mut *conn, id).await?;
+    let segments = fetch_time_segments_for_entry(&mut *conn, id).await?;
+    Ok((entry, segments))
+}
+
+// logs an edit to one entry for undo, in the transaction that made it
+async fn record_change(
+    conn: &mut PgConnection,
+    (before, before_segments): (TimeEntryRaw, Vec<TimeSegment>),
+    after: &TimeEntryRaw,
+) -> Result<()> {
+    let after_segments = fetch_time_segments_for_entry(&mut *conn, after.id).await?;
+    let change = EntryChange::new(Some(before.into()), Some(after.into()))
+        .with_segments(before_segments, after_segments);
+    record_operation(conn, vec![change]).await?;
+    Ok(())
+}
 
 pub async fn get_everything_request(Extension(pool): Extension<PgPool>) -> Result<Json<FullState>> {
     let entries = fetch_all_time_entries(&pool).await?;
     let time_entries = organize_time_entries_by_day(entries);
     let charge_codes = fetch_charge_codes(&pool).await?;
+    let targets = fetch_work_targets(&pool).await?;
+    let budgets = fetch_budget_usage(&pool).await?;
+    let days_off = fetch_this_weeks_days_off(&pool).await?;
+    let segments = fetch_all_time_segments(&pool).await?;
+    let earlier_week_entries = fetch_earlier_week_entries(&pool).await?;
 
     let full_state = FullState {
         time_entries,
         charge_codes,
+        targets,
+        budgets,
+        days_off,
+        segments,
+        earlier_week_entries,
     };
 
     Ok(Json(full_state))
 }
 
+pub async fn get_status_request(Extension(pool): Extension<PgPool>) -> Result<Json<TimerStatus>> {
+    let status = fetch_timer_status(&pool).await?;
+    Ok(Json(status))
+}
+
 pub async fn create_time_entry_request(
     Path(day_num): Path<i16>,
     Extension(pool): Extension<PgPool>,
 ) -> Result<Json<DayTimeEntries>> {
-    let _ = create_time_entry(&pool, day_num.into()).await?;
+    let mut tx = pool.begin().await?;
+    let created = create_time_entry(&mut *tx, day_num.into()).await?;
+    record_operation(
+        &mut tx,
+        vec![EntryChange::new(None, Some((&created).into()))],
+    )
+    .await?;
+    tx.commit().await?;
     let entries = fetch_time_entries_for_day(&pool, day_num).await?;
 
-    let day_time_entries = DayTimeEntries::new(day_num.into(), entries.as_slice());
+    let day_time_entries =
+        DayTimeEntries::new(day_num.into(), entries.as_slice()).with_created(created.id);
+
+    Ok(Json(day_time_entries))
+}
+
+// entries picked from another day, created in one go
+pub async fn copy_time_entries_request(
+    Path(day_num): Path<i16>,
+    Extension(pool): Extension<PgPool>,
+    Json(request): Json<CopyDayRequest>,
+) -> Result<Json<DayTimeEntries>> {
+    let day_time_entries = copy_entries_to_day(&pool, day_num.into(), &request).await?;
+    Ok(Json(day_time_entries))
+}
+
+// a whole entry typed on one line
+pub async fn create_quick_entry_request(
+    Extension(pool): Extension<PgPool>,
+    Json(entry): Json<QuickEntry>,
+) -> Result<Json<DayTimeEntries>> {
+    if let QuickTime::Range(range) = &entry.time {
+        check_range(range)?;
+    }
+    let day_time_entries = create_quick_entry(&pool, &entry).await?;
+    Ok(Json(day_time_entries))
+}
+
+pub async fn get_last_weeks_entries_request(
+    Path(day_num): Path<i16>,
+    Extension(pool): Extension<PgPool>,
+) -> Result<Json<Vec<EntryCopy>>> {
+    let entries = fetch_last_weeks_entries(&pool, day_num.into()).await?;
+    Ok(Json(entries))
+}
+
+// typed in start and end times, as a new entry
+pub async fn create_time_entry_with_range_request(
+    Path(day_num): Path<i16>,
+    Extension(pool): Extension<PgPool>,
+    Json(range): Json<TimeRange>,
+) -> Result<Json<DayTimeEntries>> {
+    check_range(&range)?;
+    let mut tx = pool.begin().await?;
+    let created = create_time_entry(&mut *tx, day_num.into()).await?;
+    set_time_entry_range(&mut *tx, created.id, range).await?;
+    let created = fetch_time_entry_by_id(&mut *tx, created.id).await?;
+    let segments = fetch_time_segments_for_entry(&mut *tx, created.id).await?;
+    let change =
+        EntryChange::new(None, Some((&created).into())).with_segments(Vec::new(), segments);
+    record_operation(&mut tx, vec![change]).await?;
+    tx.commit().await?;
+
+    let entries = fetch_time_entries_for_day(&pool, day_num).await?;
+    let day_time_entries =
+        DayTimeEntries::new(day_num.into(), entries.as_slice()).with_created(created.id);
 
     Ok(Json(day_time_entries))
 }
 
+pub async fn set_time_entry_range_request(
+    Path(id): Path<i32>,
+    Extension(pool): Extension<PgPool>,
+    Json(range): Json<TimeRange>,
+) -> Result<Json<TimeEntryVM>> {
+    check_range(&range)?;
+    let mut tx = pool.begin().await?;
+    let before = snapshot(&mut tx, id).await?;
+    set_time_entry_range(&mut *tx, id, range).await?;
+    let updated_entry = fetch_time_entry_by_id(&mut *tx, id).await?;
+    record_change(&mut tx, before, &updated_entry).await?;
+    tx.commit().await?;
+    Ok(Json(updated_entry.into()))
+}
+
+pub async fn get_time_segments_request(
+    Extension(pool): Extension<PgPool>,
+) -> Result<Json<Vec<TimeSegment>>> {
+    let segments = fetch_all_time_segments(&pool).await?;
+    Ok(Json(segments))
+}
+
+fn check_range(range: &TimeRange) -> Result<()> {
+    match range.end > range.start {
+        true => Ok(()),
+        false => Err(AppError::InvalidRequest(
+            "The end has to be after the start".to_string(),
+        )),
+    }
+}
+
+#[derive(Deserialize)]
+pub struct SearchParams {
+    q: String,
+}
+
+// `#tags` and tickets have to be on the entry, other words anywhere in its note
+pub async fn search_time_entries_request(
+    Query(params): Query<SearchParams>,
+    Extension(pool): Extension<PgPool>,
+) -> Result<Json<Vec<TimeEntryVM>>> {
+    let (tags, words) = split_query(&params.q);
+    if tags.is_empty() && words.is_empty() {
+        return Ok(Json(Vec::new()));
+    }
+    let entries = search_time_entries(&pool, &tags, &words).await?;
+    Ok(Json(entries.into_iter().map(TimeEntryVM::from).collect()))
+}
+
 #[derive(Deserialize)]
 pub struct EntryAndCodeIdPath {
     id: i32,
@@ -46,8 +221,12 @@ pub async fn update_time_entry_charge_code_request(
     Path(params): Path<EntryAndCodeIdPath>,
     Extension(pool): Extension<PgPool>,
 ) -> Result<Json<TimeEntryVM>> {
-    update_charge_code_for_time_entry(&pool, params.id, params.code_id).await?;
-    let updated_entry = fetch_time_entry_by_id(&pool, params.id).await?;
+    let mut tx = pool.begin().await?;
+    let before = snapshot(&mut tx, params.id).await?;
+    update_charge_code_for_time_entry(&mut *tx, params.id, params.code_id).await?;
+    let updated_entry = fetch_time_entry_by_id(&mut *tx, params.id).await?;
+    record_change(&mut tx, before, &updated_entry).await?;
+    tx.commit().await?;
     Ok(Json(updated_entry.into()))
 }
 
@@ -61,8 +240,13 @@ pub async fn add_time_to_entry_request(
     Path((id, add_time)): Path<(i32, i64)>,
     Extension(pool): Extension<PgPool>,
 ) -> Result<Json<TimeEntryVM>> {
-    add_time_to_entry(&pool, id, add_time).await?;
-    let updated_entry = fetch_time_entry_by_id(&pool, id).await?;
+    let mut tx = pool.begin().await?;
+    let before = snapshot(&mut tx, id).await?;
+    add_time_to_entry(&mut *tx, id, add_time).await?;
+    clear_time_segments(&mut *tx, id).await?;
+    let updated_entry = fetch_time_entry_by_id(&mut *tx, id).await?;
+    record_change(&mut tx, before, &updated_entry).await?;
+    tx.commit().await?;
     Ok(Json(updated_entry.into()))
 }
 
@@ -70,8 +254,13 @@ pub async fn update_time_entry_time_request(
     Path(params): Path<EntryAndTimePath>,
     Extension(pool): Extension<PgPool>,
 ) -> Result<Json<TimeEntryVM>> {
-    update_time_for_time_entry(&pool, params.id, params.total_time).await?;
-    let updated_entry = fetch_time_entry_by_id(&pool, params.id).await?;
+    let mut tx = pool.begin().await?;
+    let before = snapshot(&mut tx, params.id).await?;
+    update_time_for_time_entry(&mut *tx, params.id, params.total_time).await?;
+    clear_time_segments(&mut *tx, params.id).await?;
+    let updated_entry = fetch_time_entry_by_id(&mut *tx, params.id).await?;
+    record_change(&mut tx, before, &updated_entry).await?;
+    tx.commit().await?;
     Ok(Json(updated_entry.into()))
 }
 
@@ -80,7 +269,17 @@ pub async fn update_time_entry_request(
     Json(update_request): Json<TimeEntryVM>,
 ) -> Result<Json<DayTimeEntries>> {
     let day = update_request.day;
-    upsert_time_entry(&pool, update_request).await?;
+    let id = update_request.id;
+    // an update can bring back an entry that was deleted
+    let mut tx = pool.begin().await?;
+    let before = fetch_time_entry_by_id(&mut *tx, id)
+        .await
+        .ok()
+        .map(TimeEntryVM::from);
+    upsert_time_entry(&mut *tx, update_request).await?;
+    let after = fetch_time_entry_by_id(&mut *tx, id).await?;
+    record_operation(&mut tx, vec![EntryChange::new(before, Some(after.into()))]).await?;
+    tx.commit().await?;
 
     let entries = fetch_time_entries_for_day(&pool, day.into()).await?;
     let day_entries = DayTimeEntries::new(day, entries.as_slice());
@@ -98,8 +297,12 @@ pub async fn update_time_entry_note_request(
     Extension(pool): Extension<PgPool>,
     Json(body): Json<NotePayload>,
 ) -> Result<Json<TimeEntryVM>> {
-    update_time_entry_note(&pool, id, body.note).await?;
-    let entry = fetch_time_entry_by_id(&pool, id).await?;
+    let mut tx = pool.begin().await?;
+    let before = snapshot(&mut tx, id).await?;
+    update_time_entry_note(&mut *tx, id, body.note).await?;
+    let entry = fetch_time_entry_by_id(&mut *tx, id).await?;
+    record_change(&mut tx, before, &entry).await?;
+    tx.commit().await?;
 
     Ok(Json(entry.into()))
 }
@@ -116,36 +319,109 @@ pub async fn pause_time_entry_request(
     Path(id): Path<i32>,
     Extension(pool): Extension<PgPool>,
 ) -> Result<Json<DayTimeEntries>> {
-    let entry = fetch_time_entry_by_id(&pool, id).await?;
-    let elapsed_time = get_elapsed_time(&entry);
-    pause_time_entry(&pool, id, elapsed_time).await?;
+    let mut tx = pool.begin().await?;
+    let before = snapshot(&mut tx, id).await?;
+    let day = before.0.day;
+    let elapsed_time = get_elapsed_time(&before.0);
+    pause_time_entry(&mut *tx, id, elapsed_time).await?;
+    let paused = fetch_time_entry_by_id(&mut *tx, id).await?;
+    record_change(&mut tx, before, &paused).await?;
+    tx.commit().await?;
 
-    let entries = fetch_time_entries_for_day(&pool, entry.day.into()).await?;
-    let day_entries = DayTimeEntries::new(entry.day, entries.as_slice());
+    let entries = fetch_time_entries_for_day(&pool, day.into()).await?;
+    let day_entries = DayTimeEntries::new(day, entries.as_slice());
 
     Ok(Json(day_entries))
 }
 
+pub async fn split_time_entry_request(
+    Path(id): Path<i32>,
+    Extension(pool): Extension<PgPool>,
+    Json(request): Json<SplitRequest>,
+) -> Result<Json<DayTimeEntries>> {
+    let mut tx = pool.begin().await?;
+    let entries = split_time_entry(&mut tx, id, &request).await?;
+    tx.commit().await?;
+    Ok(Json(entries))
+}
+
+pub async fn merge_time_entries_request(
+    Path(id): Path<i32>,
+    Extension(pool): Extension<PgPool>,
+) -> Result<Json<DayTimeEntries>> {
+    let mut tx = pool.begin().await?;
+    let entries = merge_time_entries(&mut tx, id).await?;
+    tx.commit().await?;
+    Ok(Json(entries))
+}
+
+// -1 moves the entry up a place and 1 moves it down
+pub async fn move_time_entry_request(
+    Path((id, offset)): Path<(i32, i32)>,
+    Extension(pool): Extension<PgPool>,
+) -> Result<Json<DayTimeEntries>> {
+    let entries = move_time_entry(&pool, id, offset).await?;
+    Ok(Json(entries))
+}
+
 pub async fn delete_time_entry_request(
     Path(id): Path<i32>,
     Extension(pool): Extension<PgPool>,
 ) -> Result<Json<DayTimeEntries>> {
-    let entry = fetch_time_entry_by_id(&pool, id).await?;
-    delete_time_entry(&pool, id).await?;
+    let mut tx = pool.begin().await?;
+    let (entry, segments) = snapshot(&mut tx, id).await?;
+    delete_time_entry(&mut *tx, id).await?;
+    // undo drops the segment the delete writes when it stops a running timer
+    let stopped_segments = fetch_time_segments_for_entry(&mut *tx, id).await?;
+    let change = EntryChange::new(Some((&entry).into()), None)
+        .trashed()
+        .with_segments(segments, stopped_segments);
+    record_operation(&mut tx, vec![change]).await?;
+    tx.commit().await?;
+    let entries = fetch_time_entries_for_day(&pool, entry.day.into()).await?;
+    let day_time_entries = DayTimeEntries::new(entry.day, entries.as_slice());
+    Ok(Json(day_time_entries))
+}
+
+pub async fn get_trash_request(
+    Extension(pool): Extension<PgPool>,
+) -> Result<Json<Vec<TrashedEntry>>> {
+    let entries = fetch_trashed_time_entries(&pool).await?;
+    Ok(Json(entries.into_iter().map(TrashedEntry::from).collect()))
+}
+
+// takes an entry back out of the trash, undoing this puts it back
+pub async fn restore_time_entry_request(
+    Path(id): Path<i32>,
+    Extension(pool): Extension<PgPool>,
+) -> Result<Json<DayTimeEntries>> {
+    let mut tx = pool.begin().await?;
+    restore_time_entry(&mut *tx, id).await?;
+    let entry = fetch_time_entry_by_id(&mut *tx, id).await?;
+    record_operation(
+        &mut tx,
+        vec![EntryChange::new(None, Some((&entry).into())).trashed()],
+    )
+    .await?;
+    tx.commit().await?;
     let entries = fetch_time_entries_for_day(&pool, entry.day.into()).await?;
     let day_time_entries = DayTimeEntries::new(entry.day, entries.as_slice());
     Ok(Json(day_time_entries))
 }
 
 pub async fn delete_old_entries_request(Extension(pool): Extension<PgPool>) -> Result<StatusCode> {
-    delete_old_time_entries(&pool).await?;
+    clean_up_old_entries(&pool).await?;
+    // the monitor calls this regularly, which also brings in each week's days off
+    // and each day's pinned entries
+    create_pending_day_off_entries(&pool).await?;
+    create_pinned_entries(&pool).await?;
     Ok(StatusCode::OK)
 }
 
 pub async fn get_costpoint_entries(
     Extension(pool): Extension<PgPool>,
 ) -> Result<Json<Vec<CostpointEntryVM>>> {
-    let mut raw_entries = fetch_costpoint_entries(&pool).await?;
+    let mut raw_entries = fetch_costpoint_entries(&pool, user_time_zone().name()).await?;
     let entries: Vec<CostpointEntryVM> = raw_entries
         .drain(..)
         .filter_map(|entry| match entry.try_into() {
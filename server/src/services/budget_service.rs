@@ -0,0 +1,34 @@
+use crate::db::charge_code_repo::{fetch_charge_codes, fetch_used_millis_since};
+use chrono::Utc;
+use shared_lib::{
+    models::budget::BudgetUsage,
+    utils::time_zone::{local_date, user_time_zone},
+};
+use sqlx::PgPool;
+
+pub async fn fetch_budget_usage(pool: &PgPool) -> Result<Vec<BudgetUsage>, sqlx::Error> {
+    let time_zone = user_time_zone();
+    let today = local_date(Utc::now(), time_zone);
+
+    let mut usage = Vec::new();
+    for charge_code in fetch_charge_codes(pool).await? {
+        let Some(budget) = charge_code.budget() else {
+            continue;
+        };
+
+        let period_start = budget.period.period_start(today, budget.start);
+        let used_millis =
+            fetch_used_millis_since(pool, charge_code.id, period_start, time_zone.name()).await?;
+
+        usage.push(BudgetUsage {
+            charge_code_id: charge_code.id,
+            alias: charge_code.alias,
+            period: budget.period,
+            period_start,
+            budget_minutes: budget.minutes,
+            used_minutes: used_millis / 60_000,
+        });
+    }
+
+    Ok(usage)
+}
@@ -0,0 +1,109 @@
+use crate::{
+    db::{
+        charge_code_repo::fetch_charge_codes,
+        day_off_repo::{
+            create_day_off_entry, fetch_days_off_between, fetch_days_off_without_entries,
+            upsert_day_off,
+        },
+        target_repo::fetch_daily_targets,
+    },
+    utils::{
+        calendar_import::parse_days_off,
+        error::{AppError, Result},
+    },
+};
+use chrono::{Duration, NaiveDate, Utc};
+use shared_lib::{
+    models::{
+        charge_code::ChargeCode,
+        day::Day,
+        day_off::{DayOff, DayOffKind},
+    },
+    utils::time_zone::{local_date, start_of_local_day, user_time_zone, week_start},
+};
+use sqlx::PgPool;
+use std::collections::HashMap;
+
+pub async fn import_days_off(
+    pool: &PgPool,
+    input: &str,
+    default_kind: DayOffKind,
+) -> Result<Vec<DayOff>> {
+    let imported = parse_days_off(input, default_kind).map_err(AppError::ImportError)?;
+
+    let daily_minutes: HashMap<Day, i64> = fetch_daily_targets(pool)
+        .await?
+        .into_iter()
+        .map(|target| (target.day, target.target_minutes as i64))
+        .collect();
+    let charge_codes = fetch_charge_codes(pool).await?;
+
+    let days_off: Vec<DayOff> = imported
+        .into_iter()
+        .map(|day_off| DayOff {
+            minutes: day_off.minutes.unwrap_or_else(|| {
+                Day::from_date(day_off.date)
+                    .and_then(|day| daily_minutes.get(&day).copied())
+                    .unwrap_or(0)
+            }),
+            charge_code_id: charge_code_for(day_off.kind, &charge_codes),
+            date: day_off.date,
+            kind: day_off.kind,
+            name: day_off.name,
+            entry_id: None,
+        })
+        .collect();
+
+    let mut tx = pool.begin().await?;
+    for day_off in &days_off {
+        upsert_day_off(&mut *tx, day_off).await?;
+    }
+    tx.commit().await?;
+
+    create_pending_day_off_entries(pool).await?;
+
+    Ok(days_off)
+}
+
+pub async fn fetch_this_weeks_days_off(pool: &PgPool) -> Result<Vec<DayOff>> {
+    let (monday, friday) = this_week();
+    let days_off = fetch_days_off_between(pool, monday, friday).await?;
+    Ok(days_off.into_iter().map(|day_off| day_off.into()).collect())
+}
+
+/// Fills in the entries for any days off in the current week that don't have one yet
+pub async fn create_pending_day_off_entries(pool: &PgPool) -> Result<()> {
+    let time_zone = user_time_zone();
+    let (monday, friday) = this_week();
+
+    for day_off in fetch_days_off_without_entries(pool, monday, friday).await? {
+        if let Some(day) = Day::from_date(day_off.date) {
+            let created_at = start_of_local_day(day_off.date, time_zone);
+            create_day_off_entry(pool, &day_off, day, created_at).await?;
+        }
+    }
+
+    Ok(())
+}
+
+fn this_week() -> (NaiveDate, NaiveDate) {
+    let monday = week_start(local_date(Utc::now(), user_time_zone()));
+    (monday, monday + Duration::days(4))
+}
+
+// matched against either the alias or the code, e.g. "Holiday" or "FRNGE.HOL"
+fn charge_code_for(kind: DayOffKind, charge_codes: &[ChargeCode]) -> Option<i32> {
+    let name = match kind {
+        DayOffKind::Holiday => {
+            std::env::var("HOLIDAY_CHARGE_CODE").unwrap_or_else(|_| "Holiday".to_string())
+        }
+        DayOffKind::Pto => {
+            std::env::var("PTO_CHARGE_CODE").unwrap_or_else(|_| "Vacation".to_string())
+        }
+    };
+
+    charge_codes
+        .iter()
+        .find(|code| code.alias == name || code.code == name)
+        .map(|code| code.id)
+}
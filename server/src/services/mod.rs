@@ -1 +1,6 @@
+pub mod budget_service;
+pub mod day_off_service;
+pub mod operation_service;
+pub mod split_service;
+pub mod target_service;
 pub mod time_entry_service;
@@ -0,0 +1,99 @@
+use crate::db::{
+    operation_repo::{
+        delete_undone_operations, fetch_first_undone_operation, fetch_last_done_operation,
+        insert_operation, prune_operations, set_operation_undone,
+    },
+    time_entry_repo::{delete_time_entry, purge_time_entry, upsert_time_entry},
+    time_segment_repo::replace_time_segments_for_entry,
+};
+use shared_lib::models::{
+    operation::{describe_changes, EntryChange, Operation},
+    time_entry::TimeEntryVM,
+};
+use sqlx::PgConnection;
+
+// how far back undo can go
+const MAX_OPERATIONS: i64 = 500;
+
+/// Logs a change so it can be undone. A new change drops anything that was undone before it.
+/// Call it in the transaction that made the change, so neither lands without the other.
+pub async fn record_operation(
+    conn: &mut PgConnection,
+    changes: Vec<EntryChange>,
+) -> Result<(), sqlx::Error> {
+    let changes: Vec<EntryChange> = changes
+        .into_iter()
+        .filter(|change| !change.is_empty())
+        .collect();
+    if changes.is_empty() {
+        return Ok(());
+    }
+
+    delete_undone_operations(&mut *conn).await?;
+    insert_operation(&mut *conn, &describe_changes(&changes), &changes).await?;
+    prune_operations(&mut *conn, MAX_OPERATIONS).await?;
+    Ok(())
+}
+
+/// Puts the entries back the way they were before the last change, if there is one
+pub async fn undo_last_operation(
+    conn: &mut PgConnection,
+) -> Result<Option<Operation>, sqlx::Error> {
+    let Some(operation) = fetch_last_done_operation(&mut *conn).await? else {
+        return Ok(None);
+    };
+
+    for change in operation.changes.iter().rev() {
+        restore(conn, &change.after, &change.before, change.trashed).await?;
+        if let (Some(segments), Some(before)) = (&change.segments, &change.before) {
+            replace_time_segments_for_entry(&mut *conn, before.id, &segments.before).await?;
+        }
+    }
+    set_operation_undone(&mut *conn, operation.id, true).await?;
+
+    Ok(Some(Operation {
+        is_undone: true,
+        ..operation.into()
+    }))
+}
+
+/// Applies the oldest undone change again, if there is one
+pub async fn redo_next_operation(
+    conn: &mut PgConnection,
+) -> Result<Option<Operation>, sqlx::Error> {
+    let Some(operation) = fetch_first_undone_operation(&mut *conn).await? else {
+        return Ok(None);
+    };
+
+    for change in operation.changes.iter() {
+        restore(conn, &change.before, &change.after, change.trashed).await?;
+        if let (Some(segments), Some(after)) = (&change.segments, &change.after) {
+            replace_time_segments_for_entry(&mut *conn, after.id, &segments.after).await?;
+        }
+    }
+    set_operation_undone(&mut *conn, operation.id, false).await?;
+
+    Ok(Some(Operation {
+        is_undone: false,
+        ..operation.into()
+    }))
+}
+
+// moves an entry from `from` to `to`, creating or deleting it when one side is empty. The
+// upsert takes an entry out of the trash as well. A delete only goes to the trash when the
+// change came from or went to it, an undone create shouldn't be left there to restore.
+// Segments are put back by the caller once the entry is there for them, a deleted entry's
+// go with it or are written by the delete.
+async fn restore(
+    conn: &mut PgConnection,
+    from: &Option<TimeEntryVM>,
+    to: &Option<TimeEntryVM>,
+    trashed: bool,
+) -> Result<(), sqlx::Error> {
+    match (from, to) {
+        (_, Some(entry)) => upsert_time_entry(&mut *conn, entry.clone()).await,
+        (Some(entry), None) if trashed => delete_time_entry(&mut *conn, entry.id).await,
+        (Some(entry), None) => purge_time_entry(&mut *conn, entry.id).await,
+        (None, None) => Ok(()),
+    }
+}
@@ -0,0 +1,122 @@
+use crate::{
+    db::{
+        time_entry_repo::{
+            create_time_entry, fetch_time_entries_for_day, fetch_time_entry_by_id,
+            purge_time_entry, update_charge_code_for_time_entry, update_time_entry_note,
+            update_time_for_time_entry, upsert_time_entry,
+        },
+        time_segment_repo::{fetch_time_segments_for_entry, move_time_segments},
+    },
+    models::{time_entry::TimeEntryRaw, DayTimeEntries},
+    services::operation_service::record_operation,
+    utils::error::{AppError, Result},
+};
+use shared_lib::models::{
+    operation::EntryChange,
+    split::{merge_notes, split_note, SplitRequest},
+    time_entry::TimeEntryVM,
+};
+use sqlx::PgConnection;
+
+/// Moves part of an entry's time onto a new entry on the same day
+pub async fn split_time_entry(
+    conn: &mut PgConnection,
+    id: i32,
+    request: &SplitRequest,
+) -> Result<DayTimeEntries> {
+    let entry = fetch_time_entry_by_id(&mut *conn, id).await?;
+    if entry.start_time.is_some() {
+        return Err(AppError::InvalidRequest(
+            "Pause the timer before splitting the entry".to_string(),
+        ));
+    }
+    let moved = request
+        .amount
+        .moved_millis(entry.total_time)
+        .map_err(AppError::InvalidRequest)?;
+    let (kept_note, moved_note) = split_note(&entry.note, request.note, moved, entry.total_time);
+
+    update_time_for_time_entry(&mut *conn, id, entry.total_time - moved).await?;
+    update_time_entry_note(&mut *conn, id, kept_note).await?;
+    let created = create_time_entry(&mut *conn, entry.day).await?;
+    update_time_for_time_entry(&mut *conn, created.id, moved).await?;
+    update_time_entry_note(&mut *conn, created.id, moved_note).await?;
+    if let Some(charge_code_id) = request.charge_code_id.or(entry.charge_code_id) {
+        update_charge_code_for_time_entry(&mut *conn, created.id, charge_code_id).await?;
+    }
+
+    let kept = fetch_time_entry_by_id(&mut *conn, id).await?;
+    let created = fetch_time_entry_by_id(&mut *conn, created.id).await?;
+    let day = entry.day;
+    record_operation(
+        &mut *conn,
+        vec![
+            EntryChange::new(Some(entry.into()), Some(kept.into())),
+            EntryChange::new(None, Some(created.into())),
+        ],
+    )
+    .await?;
+    let entries = fetch_time_entries_for_day(&mut *conn, day.into()).await?;
+
+    Ok(DayTimeEntries::new(day, entries.as_slice()))
+}
+
+/// Folds the other entries with the same code on the same day into this one
+pub async fn merge_time_entries(conn: &mut PgConnection, id: i32) -> Result<DayTimeEntries> {
+    let entry = fetch_time_entry_by_id(&mut *conn, id).await?;
+    let Some(charge_code_id) = entry.charge_code_id else {
+        return Err(AppError::InvalidRequest(
+            "Pick a charge code before merging".to_string(),
+        ));
+    };
+    let duplicates: Vec<TimeEntryRaw> = fetch_time_entries_for_day(&mut *conn, entry.day.into())
+        .await?
+        .into_iter()
+        .filter(|other| other.id != id && other.charge_code_id == Some(charge_code_id))
+        .collect();
+    if duplicates.is_empty() {
+        return Err(AppError::InvalidRequest(format!(
+            "Nothing else uses {} that day",
+            entry.alias.clone().unwrap_or_default()
+        )));
+    }
+
+    // a running timer on a duplicate keeps running on the merged entry
+    let before = TimeEntryVM::from(&entry);
+    let merged = TimeEntryVM {
+        total_time: entry.total_time + duplicates.iter().map(|e| e.total_time).sum::<i64>(),
+        note: merge_notes(
+            std::iter::once(entry.note.as_str()).chain(duplicates.iter().map(|e| e.note.as_str())),
+        ),
+        start_time: entry
+            .start_time
+            .or(duplicates.iter().find_map(|e| e.start_time)),
+        ..before.clone()
+    };
+    let before_segments = fetch_time_segments_for_entry(&mut *conn, id).await?;
+    upsert_time_entry(&mut *conn, merged).await?;
+    let duplicate_ids: Vec<i32> = duplicates.iter().map(|e| e.id).collect();
+    let mut duplicate_changes = Vec::new();
+    for duplicate in duplicates {
+        let segments = fetch_time_segments_for_entry(&mut *conn, duplicate.id).await?;
+        duplicate_changes.push(
+            EntryChange::new(Some(duplicate.into()), None).with_segments(segments, Vec::new()),
+        );
+    }
+    move_time_segments(&mut *conn, &duplicate_ids, id).await?;
+    // their time is on the merged entry now, so they don't go to the trash
+    for duplicate_id in &duplicate_ids {
+        purge_time_entry(&mut *conn, *duplicate_id).await?;
+    }
+
+    let after = fetch_time_entry_by_id(&mut *conn, id).await?;
+    let after_segments = fetch_time_segments_for_entry(&mut *conn, id).await?;
+    // the kept entry first, so a redo takes the segments before the duplicates are deleted
+    let mut changes = vec![EntryChange::new(Some(before), Some(after.into()))
+        .with_segments(before_segments, after_segments)];
+    changes.extend(duplicate_changes);
+    record_operation(&mut *conn, changes).await?;
+    let entries = fetch_time_entries_for_day(&mut *conn, entry.day.into()).await?;
+
+    Ok(DayTimeEntries::new(entry.day, entries.as_slice()))
+}
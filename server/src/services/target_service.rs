@@ -0,0 +1,84 @@
+use crate::{
+    db::{
+        day_off_repo::fetch_days_off_between,
+        target_repo::{
+            fetch_daily_targets, fetch_earlier_week_entry_ids, fetch_unsettled_week_balances,
+            fetch_week_balances_before, fetch_weekly_target, upsert_daily_target,
+            upsert_weekly_target,
+        },
+    },
+    models::targets::WeekBalanceRaw,
+};
+use chrono::{Duration, NaiveDate, Utc};
+use shared_lib::{
+    models::{day::Day, targets::WorkTargets},
+    utils::time_zone::{local_date, user_time_zone, week_start},
+};
+use sqlx::PgPool;
+use std::collections::HashMap;
+
+pub async fn fetch_work_targets(pool: &PgPool) -> Result<WorkTargets, sqlx::Error> {
+    let time_zone = user_time_zone();
+    let week_start = week_start(local_date(Utc::now(), time_zone));
+
+    let daily_minutes = fetch_daily_targets(pool)
+        .await?
+        .into_iter()
+        .map(|target| (target.day, target.target_minutes as i64))
+        .collect();
+    let weekly_minutes = fetch_weekly_target(pool).await? as i64;
+
+    let settled = fetch_week_balances_before(pool, week_start).await?;
+    let unsettled = fetch_unsettled_week_balances(pool, time_zone.name(), week_start).await?;
+
+    let days_off_minutes = fetch_days_off_between(pool, week_start, week_start + Duration::days(4))
+        .await?
+        .into_iter()
+        .filter_map(|day_off| Day::from_date(day_off.date).map(|day| (day, day_off.minutes as i64)))
+        .collect();
+
+    Ok(WorkTargets {
+        daily_minutes,
+        weekly_minutes,
+        carried_balance_minutes: carried_balance(settled, unsettled),
+        days_off_minutes,
+    })
+}
+
+/// The ids of last week's entries still waiting on the cleanup, which the carried balance
+/// already counts
+pub async fn fetch_earlier_week_entries(pool: &PgPool) -> Result<Vec<i32>, sqlx::Error> {
+    let time_zone = user_time_zone();
+    let week_start = week_start(local_date(Utc::now(), time_zone));
+    fetch_earlier_week_entry_ids(pool, time_zone.name(), week_start).await
+}
+
+pub async fn update_work_targets(pool: &PgPool, targets: &WorkTargets) -> Result<(), sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    for (day, minutes) in &targets.daily_minutes {
+        upsert_daily_target(&mut *tx, *day, *minutes as i32).await?;
+    }
+    upsert_weekly_target(&mut *tx, targets.weekly_minutes as i32).await?;
+    tx.commit().await
+}
+
+/// A week can be partly rolled up and partly still in time_entries while the cleanup
+/// works through it, so both halves are added together before comparing to the target.
+fn carried_balance(settled: Vec<WeekBalanceRaw>, unsettled: Vec<WeekBalanceRaw>) -> i64 {
+    let mut weeks: HashMap<NaiveDate, (i64, i32)> = settled
+        .into_iter()
+        .map(|week| (week.week_start, (week.worked_minutes, week.target_minutes)))
+        .collect();
+
+    for week in unsettled {
+        weeks
+            .entry(week.week_start)
+            .or_insert((0, week.target_minutes))
+            .0 += week.worked_minutes;
+    }
+
+    weeks
+        .values()
+        .map(|(worked, target)| worked - *target as i64)
+        .sum()
+}
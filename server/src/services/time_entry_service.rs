@@ -1,28 +1,80 @@
 use crate::{
-    db::time_entry_repo::{
-        fetch_all_running_timers, fetch_time_entries_for_day, pause_time_entry,
-        play_time_entry_and_return_day,
+    db::{
+        charge_code_repo::roll_up_charge_code_usage,
+        operation_repo::delete_operations_for_entries,
+        target_repo::roll_up_old_time_entries,
+        time_entry_repo::{
+            create_pinned_time_entries, create_time_entry, delete_old_time_entries,
+            fetch_all_notes, fetch_all_running_timers, fetch_entry_copies_for_date,
+            fetch_time_entries_for_day, fetch_time_entry_by_id, pause_time_entry,
+            play_time_entry_and_return_day, purge_trashed_time_entries, replace_all_note_tags,
+            set_time_entry_positions, update_charge_code_for_time_entry, update_time_entry_note,
+            update_time_for_time_entry,
+        },
+        time_segment_repo::{fetch_time_segments_for_entry, set_time_entry_range},
     },
     models::{time_entry::TimeEntryRaw, DayTimeEntries},
+    services::{operation_service::record_operation, target_service::fetch_earlier_week_entries},
     utils::time::get_elapsed_time,
 };
-use chrono::{NaiveDateTime, Utc};
-use shared_lib::models::day::Day;
-use sqlx::PgPool;
+use chrono::{DateTime, Duration, Utc};
+use shared_lib::{
+    models::{
+        copy_day::{CopyDayRequest, EntryCopy},
+        day::Day,
+        operation::EntryChange,
+        quick_entry::{QuickEntry, QuickTime},
+        time_entry::TimeEntryVM,
+        timer_status::TimerStatus,
+    },
+    utils::{
+        note_tags::parse_tags,
+        time_zone::{local_date, start_of_local_day, user_time_zone},
+    },
+};
+use sqlx::{Executor, PgPool, Postgres};
+
+// days a deleted entry can still be restored, unless `TRASH_RETENTION_DAYS` says otherwise
+const DEFAULT_TRASH_RETENTION_DAYS: i64 = 7;
 
 pub async fn switch_to_timer(pool: &PgPool, id: i32) -> Result<DayTimeEntries, sqlx::Error> {
+    let mut tx = pool.begin().await?;
     // pause all running timers
-    let running_timers = fetch_all_running_timers(pool).await?;
+    let running_timers = fetch_all_running_timers(&mut *tx).await?;
+    let mut changed_ids: Vec<i32> = running_timers.iter().map(|timer| timer.id).collect();
+    if !changed_ids.contains(&id) {
+        changed_ids.push(id);
+    }
+    let mut before = Vec::new();
+    for changed_id in &changed_ids {
+        before.push((
+            TimeEntryVM::from(fetch_time_entry_by_id(&mut *tx, *changed_id).await?),
+            fetch_time_segments_for_entry(&mut *tx, *changed_id).await?,
+        ));
+    }
     for timer in running_timers {
-        pause_timer(pool, &timer).await?;
+        pause_timer(&mut *tx, &timer).await?;
     }
 
     // start new timer
-    let start_time: NaiveDateTime = Utc::now().naive_utc();
-    let day = play_time_entry_and_return_day(pool, id, start_time).await?;
+    let start_time: DateTime<Utc> = Utc::now();
+    let day = play_time_entry_and_return_day(&mut *tx, id, start_time).await?;
+
+    let mut changes = Vec::new();
+    // the paused timers each wrote a segment, which undo has to take back
+    for (entry, segments) in before {
+        let after = fetch_time_entry_by_id(&mut *tx, entry.id).await?;
+        let after_segments = fetch_time_segments_for_entry(&mut *tx, entry.id).await?;
+        changes.push(
+            EntryChange::new(Some(entry), Some(after.into()))
+                .with_segments(segments, after_segments),
+        );
+    }
+    record_operation(&mut tx, changes).await?;
 
     // return current state of timers for this day
-    let entries = fetch_time_entries_for_day(pool, day).await?;
+    let entries = fetch_time_entries_for_day(&mut *tx, day).await?;
+    tx.commit().await?;
 
     let day = match entries.first() {
         Some(entry) => entry.day,
@@ -32,9 +84,172 @@ pub async fn switch_to_timer(pool: &PgPool, id: i32) -> Result<DayTimeEntries, s
     Ok(DayTimeEntries::new(day, entries.as_slice()))
 }
 
-async fn pause_timer(pool: &PgPool, entry: &TimeEntryRaw) -> Result<(), sqlx::Error> {
+/// The running timer and today's total, without loading the rest of the week
+pub async fn fetch_timer_status(pool: &PgPool) -> Result<TimerStatus, sqlx::Error> {
+    let running: Vec<TimeEntryVM> = fetch_all_running_timers(pool)
+        .await?
+        .into_iter()
+        .map(TimeEntryVM::from)
+        .collect();
+    let today = Day::get_current_day();
+    // last week's entries for this weekday wait on the cleanup, they aren't today's
+    let earlier_week_entries = fetch_earlier_week_entries(pool).await?;
+    let today_entries: Vec<TimeEntryVM> = fetch_time_entries_for_day(pool, today.into())
+        .await?
+        .into_iter()
+        .filter(|entry| !earlier_week_entries.contains(&entry.id))
+        .map(TimeEntryVM::from)
+        .collect();
+
+    Ok(TimerStatus::from_entries(
+        &running,
+        today,
+        &today_entries,
+        Utc::now(),
+    ))
+}
+
+async fn pause_timer<'e, E>(exec: E, entry: &TimeEntryRaw) -> Result<(), sqlx::Error>
+where
+    E: Executor<'e, Database = Postgres>,
+{
     let elapsed_time = get_elapsed_time(entry);
-    pause_time_entry(pool, entry.id, elapsed_time).await?;
+    pause_time_entry(exec, entry.id, elapsed_time).await?;
+
+    Ok(())
+}
+
+pub async fn clean_up_old_entries(pool: &PgPool) -> Result<(), sqlx::Error> {
+    // roll up and delete together, so time is never counted twice or lost
+    let time_zone = user_time_zone();
+    let mut tx = pool.begin().await?;
+    roll_up_old_time_entries(&mut *tx, time_zone.name()).await?;
+    roll_up_charge_code_usage(&mut *tx, time_zone.name()).await?;
+    let mut deleted_ids = delete_old_time_entries(&mut *tx).await?;
+    deleted_ids.extend(purge_trashed_time_entries(&mut *tx, trash_retention_days()).await?);
+    // undoing these would bring back entries whose time was already rolled up
+    delete_operations_for_entries(&mut *tx, &deleted_ids).await?;
+    tx.commit().await
+}
+
+// the weekly cleanup still takes old weeks out of the trash with everything else
+fn trash_retention_days() -> i64 {
+    std::env::var("TRASH_RETENTION_DAYS")
+        .ok()
+        .and_then(|days| days.parse().ok())
+        .unwrap_or(DEFAULT_TRASH_RETENTION_DAYS)
+}
+
+/// Moves an entry `offset` places within its day. The whole day is numbered so the entries
+/// that were never moved keep their place around it. Moving past either end does nothing.
+pub async fn move_time_entry(
+    pool: &PgPool,
+    id: i32,
+    offset: i32,
+) -> Result<DayTimeEntries, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    let entry = fetch_time_entry_by_id(&mut *tx, id).await?;
+    let mut entries = fetch_time_entries_for_day(&mut *tx, entry.day.into()).await?;
+    entries.sort_by_key(|entry| entry.day_order());
 
+    let mut ids: Vec<i32> = entries.iter().map(|entry| entry.id).collect();
+    if let Some(index) = ids.iter().position(|other_id| *other_id == id) {
+        let target = index as i64 + offset as i64;
+        if (0..ids.len() as i64).contains(&target) {
+            let moved = ids.remove(index);
+            ids.insert(target as usize, moved);
+            set_time_entry_positions(&mut *tx, &ids).await?;
+        }
+    }
+
+    let entries = fetch_time_entries_for_day(&mut *tx, entry.day.into()).await?;
+    tx.commit().await?;
+    Ok(DayTimeEntries::new(entry.day, entries.as_slice()))
+}
+
+/// Adds today's entries for the pinned charge codes, nothing is pinned on weekends
+pub async fn create_pinned_entries(pool: &PgPool) -> Result<(), sqlx::Error> {
+    let time_zone = user_time_zone();
+    let today = local_date(Utc::now(), time_zone);
+    if let Some(day) = Day::from_date(today) {
+        let created_at = start_of_local_day(today, time_zone);
+        create_pinned_time_entries(pool, today, day, created_at).await?;
+    }
     Ok(())
 }
+
+/// Creates the copies on `day` together, so one undo takes them all back
+pub async fn copy_entries_to_day(
+    pool: &PgPool,
+    day: Day,
+    request: &CopyDayRequest,
+) -> Result<DayTimeEntries, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    let mut changes = Vec::new();
+    for copy in request.copies() {
+        let created = create_time_entry(&mut *tx, day).await?;
+        update_time_for_time_entry(&mut *tx, created.id, copy.total_time).await?;
+        update_time_entry_note(&mut *tx, created.id, copy.note).await?;
+        if let Some(charge_code) = copy.charge_code {
+            update_charge_code_for_time_entry(&mut *tx, created.id, charge_code.id).await?;
+        }
+        let created = fetch_time_entry_by_id(&mut *tx, created.id).await?;
+        changes.push(EntryChange::new(None, Some(created.into())));
+    }
+    record_operation(&mut tx, changes).await?;
+    let entries = fetch_time_entries_for_day(&mut *tx, day.into()).await?;
+    tx.commit().await?;
+
+    Ok(DayTimeEntries::new(day, entries.as_slice()))
+}
+
+/// Creates a typed in entry with its time, note and charge code as one change
+pub async fn create_quick_entry(
+    pool: &PgPool,
+    entry: &QuickEntry,
+) -> Result<DayTimeEntries, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    let created = create_time_entry(&mut *tx, entry.day).await?;
+    match entry.time {
+        QuickTime::Duration(millis) => {
+            update_time_for_time_entry(&mut *tx, created.id, millis.max(0)).await?;
+        }
+        QuickTime::Range(range) => set_time_entry_range(&mut *tx, created.id, range).await?,
+    }
+    update_time_entry_note(&mut *tx, created.id, entry.note.clone()).await?;
+    if let Some(charge_code) = &entry.charge_code {
+        update_charge_code_for_time_entry(&mut *tx, created.id, charge_code.id).await?;
+    }
+    let created = fetch_time_entry_by_id(&mut *tx, created.id).await?;
+    let segments = fetch_time_segments_for_entry(&mut *tx, created.id).await?;
+    let change =
+        EntryChange::new(None, Some((&created).into())).with_segments(Vec::new(), segments);
+    record_operation(&mut tx, vec![change]).await?;
+    let entries = fetch_time_entries_for_day(&mut *tx, entry.day.into()).await?;
+    tx.commit().await?;
+
+    Ok(DayTimeEntries::new(entry.day, entries.as_slice()).with_created(created.id))
+}
+
+/// What was tracked on the same weekday a week before `day`
+pub async fn fetch_last_weeks_entries(
+    pool: &PgPool,
+    day: Day,
+) -> Result<Vec<EntryCopy>, sqlx::Error> {
+    let date = day.into_date() - Duration::days(7);
+    let copies = fetch_entry_copies_for_date(pool, date, user_time_zone().name()).await?;
+    Ok(copies.into_iter().map(EntryCopy::from).collect())
+}
+
+/// Parses the tags out of every note again. Notes saved before tags were indexed, or while the
+/// parsing was different, are only found by tag after this.
+pub async fn reindex_note_tags(pool: &PgPool) -> Result<(), sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    let (entry_ids, tags) = fetch_all_notes(&mut *tx)
+        .await?
+        .into_iter()
+        .flat_map(|(id, note)| parse_tags(&note).into_iter().map(move |tag| (id, tag)))
+        .unzip();
+    replace_all_note_tags(&mut *tx, entry_ids, tags).await?;
+    tx.commit().await
+}
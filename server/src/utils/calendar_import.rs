@@ -0,0 +1,194 @@
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+use shared_lib::models::day_off::DayOffKind;
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct ImportedDayOff {
+    pub date: NaiveDate,
+    pub kind: DayOffKind,
+    pub name: String,
+    // falls back to the day's target when the file doesn't say
+    pub minutes: Option<i64>,
+}
+
+/// Reads days off from an iCalendar (`.ics`) file or a CSV of `date,kind,name,hours`.
+/// Events from a calendar get `default_kind`, and weekends are skipped.
+pub fn parse_days_off(
+    input: &str,
+    default_kind: DayOffKind,
+) -> Result<Vec<ImportedDayOff>, String> {
+    let days_off = if input.trim_start().starts_with("BEGIN:VCALENDAR") {
+        parse_ics(input, default_kind)?
+    } else {
+        parse_csv(input)?
+    };
+
+    Ok(days_off
+        .into_iter()
+        .filter(|day_off| !matches!(day_off.date.weekday(), Weekday::Sat | Weekday::Sun))
+        .collect())
+}
+
+fn parse_ics(input: &str, kind: DayOffKind) -> Result<Vec<ImportedDayOff>, String> {
+    // long lines are folded onto the next line with a leading space or tab
+    let unfolded = input
+        .replace("\r\n", "\n")
+        .replace("\n ", "")
+        .replace("\n\t", "");
+
+    let mut days_off = Vec::new();
+    let mut start: Option<NaiveDate> = None;
+    let mut end: Option<NaiveDate> = None;
+    let mut summary = String::new();
+
+    for line in unfolded.lines() {
+        let Some((property, value)) = line.split_once(':') else {
+            continue;
+        };
+        // drop parameters like DTSTART;VALUE=DATE
+        let name = property.split(';').next().unwrap_or_default();
+
+        match name {
+            "BEGIN" if value == "VEVENT" => {
+                start = None;
+                end = None;
+                summary.clear();
+            }
+            "DTSTART" => start = Some(parse_ics_date(value)?),
+            "DTEND" => end = Some(parse_ics_date(value)?),
+            "SUMMARY" => summary = value.replace("\\,", ",").replace("\\;", ";"),
+            "END" if value == "VEVENT" => {
+                let start = start.ok_or("event without a DTSTART")?;
+                // all day events end the day after their last day
+                let last = end
+                    .map(|end| end - Duration::days(1))
+                    .filter(|last| *last > start)
+                    .unwrap_or(start);
+
+                let mut date = start;
+                while date <= last {
+                    days_off.push(ImportedDayOff {
+                        date,
+                        kind,
+                        name: summary.clone(),
+                        minutes: None,
+                    });
+                    date += Duration::days(1);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    Ok(days_off)
+}
+
+fn parse_ics_date(value: &str) -> Result<NaiveDate, String> {
+    // both 20241225 and 20241225T090000Z start with the date
+    let date = value.get(..8).unwrap_or(value);
+    NaiveDate::parse_from_str(date, "%Y%m%d").map_err(|_| format!("invalid date '{}'", value))
+}
+
+fn parse_csv(input: &str) -> Result<Vec<ImportedDayOff>, String> {
+    let mut days_off = Vec::new();
+
+    for (index, line) in input.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || (index == 0 && line.to_lowercase().starts_with("date")) {
+            continue;
+        }
+
+        let fields: Vec<&str> = line
+            .split(',')
+            .map(|field| field.trim().trim_matches('"'))
+            .collect();
+        let line_error = |message: &str| format!("line {}: {}", index + 1, message);
+
+        let date = fields
+            .first()
+            .and_then(|date| NaiveDate::parse_from_str(date, "%Y-%m-%d").ok())
+            .ok_or_else(|| line_error("expected a YYYY-MM-DD date"))?;
+        let kind = match fields.get(1).map(|kind| kind.to_lowercase()).as_deref() {
+            Some("holiday") => DayOffKind::Holiday,
+            Some("pto") | Some("vacation") | Some("leave") => DayOffKind::Pto,
+            _ => return Err(line_error("kind should be holiday or pto")),
+        };
+        let name = fields
+            .get(2)
+            .map(|name| name.to_string())
+            .unwrap_or_default();
+        let minutes = match fields.get(3).filter(|hours| !hours.is_empty()) {
+            Some(hours) => {
+                let hours: f64 = hours.parse().map_err(|_| line_error("invalid hours"))?;
+                Some((hours * 60.0).round() as i64)
+            }
+            None => None,
+        };
+
+        days_off.push(ImportedDayOff {
+            date,
+            kind,
+            name,
+            minutes,
+        });
+    }
+
+    Ok(days_off)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn reads_all_day_events_from_ics() {
+        let ics = "BEGIN:VCALENDAR\r\n\
+                   BEGIN:VEVENT\r\n\
+                   DTSTART;VALUE=DATE:20241128\r\n\
+                   DTEND;VALUE=DATE:20241130\r\n\
+                   SUMMARY:Thanksgiving\\, and the day\r\n  after\r\n\
+                   END:VEVENT\r\n\
+                   BEGIN:VEVENT\r\n\
+                   DTSTART;VALUE=DATE:20241225\r\n\
+                   SUMMARY:Christmas\r\n\
+                   END:VEVENT\r\n\
+                   END:VCALENDAR\r\n";
+
+        let days_off = parse_days_off(ics, DayOffKind::Holiday).unwrap();
+
+        assert_eq!(days_off.len(), 3);
+        assert_eq!(days_off[0].date, date(2024, 11, 28));
+        assert_eq!(days_off[1].date, date(2024, 11, 29));
+        assert_eq!(days_off[0].name, "Thanksgiving, and the day after");
+        assert_eq!(days_off[2].date, date(2024, 12, 25));
+        assert_eq!(days_off[2].kind, DayOffKind::Holiday);
+    }
+
+    #[test]
+    fn reads_csv_and_skips_weekends() {
+        let csv = "date,kind,name,hours\n\
+                   2024-07-04,holiday,Independence Day,8\n\
+                   2024-07-05,pto,Long weekend,4.5\n\
+                   2024-07-06,pto,Saturday,\n";
+
+        let days_off = parse_days_off(csv, DayOffKind::Holiday).unwrap();
+
+        assert_eq!(days_off.len(), 2);
+        assert_eq!(days_off[0].minutes, Some(480));
+        assert_eq!(days_off[1].kind, DayOffKind::Pto);
+        assert_eq!(days_off[1].minutes, Some(270));
+    }
+
+    #[test]
+    fn csv_errors_name_the_line() {
+        let csv = "2024-07-04,holiday,Independence Day,8\n07/05/2024,pto,,\n";
+
+        assert_eq!(
+            parse_days_off(csv, DayOffKind::Holiday),
+            Err("line 2: expected a YYYY-MM-DD date".to_string())
+        );
+    }
+}
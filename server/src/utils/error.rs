@@ -13,6 +13,10 @@ pub enum AppError {
     InternalError,
     #[error("Weekends are currently not supported")]
     WeekendError,
+    #[error("Could not import days off: {0}")]
+    ImportError(String),
+    #[error("{0}")]
+    InvalidRequest(String),
 }
 
 use axum::{
@@ -28,6 +32,9 @@ impl IntoResponse for AppError {
                 let error_message = format!("Database error: {}", e);
                 (StatusCode::INTERNAL_SERVER_ERROR, error_message).into_response()
             }
+            AppError::ImportError(_) | AppError::InvalidRequest(_) => {
+                (StatusCode::BAD_REQUEST, self.to_string()).into_response()
+            }
             // Handle other errors as needed
             _ => (StatusCode::INTERNAL_SERVER_ERROR, "Internal server error").into_response(),
         }
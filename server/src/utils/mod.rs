@@ -1,3 +1,4 @@
+pub mod calendar_import;
 pub mod connections;
 pub mod error;
 pub mod time;
@@ -1,11 +1,11 @@
-use chrono::{NaiveDateTime, Utc};
+use chrono::Utc;
 
 use crate::models::time_entry::TimeEntryRaw;
 
 pub fn get_elapsed_time(entry: &TimeEntryRaw) -> i64 {
     match entry.start_time {
         Some(start_time) => {
-            let end_time: NaiveDateTime = Utc::now().naive_utc();
+            let end_time = Utc::now();
             (end_time - start_time).num_milliseconds()
         }
         None => 0, // timer was "played" before it was "paused"
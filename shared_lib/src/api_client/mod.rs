@@ -2,8 +2,20 @@ use reqwest::Client;
 use serde::{Deserialize, Serialize};
 
 use crate::models::{
+    audit_log::MonitorAuditEntry,
+    budget::{BudgetUsage, ChargeCodeBudget},
+    charge_code::ChargeCode,
+    copy_day::{CopyDayRequest, EntryCopy},
+    costpoint_entry::CostpointEntryVM,
     full_state::{DayTimeEntries, FullState},
+    operation::Operation,
+    quick_entry::QuickEntry,
+    split::SplitRequest,
+    targets::WorkTargets,
     time_entry::TimeEntryVM,
+    time_segment::{TimeRange, TimeSegment},
+    timer_status::TimerStatus,
+    trash::TrashedEntry,
 };
 
 #[derive(Clone)]
@@ -34,6 +46,15 @@ impl ApiClient {
             .await
     }
 
+    pub async fn get_status(&self) -> Result<TimerStatus, reqwest::Error> {
+        self.client
+            .get(format!("{}/status", self.base_url))
+            .send()
+            .await?
+            .json::<TimerStatus>()
+            .await
+    }
+
     pub async fn create_time_entry(&self, day: i16) -> Result<DayTimeEntries, reqwest::Error> {
         self.client
             .post(&format!("{}/time_entries/day/{}", self.base_url, day))
@@ -43,6 +64,87 @@ impl ApiClient {
             .await
     }
 
+    pub async fn create_time_entry_with_range(
+        &self,
+        day: i16,
+        range: TimeRange,
+    ) -> Result<DayTimeEntries, reqwest::Error> {
+        self.client
+            .post(format!("{}/time_entries/day/{}/range", self.base_url, day))
+            .json(&range)
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<DayTimeEntries>()
+            .await
+    }
+
+    pub async fn copy_entries(
+        &self,
+        day: i16,
+        request: &CopyDayRequest,
+    ) -> Result<DayTimeEntries, reqwest::Error> {
+        self.client
+            .post(format!("{}/time_entries/day/{}/copy", self.base_url, day))
+            .json(request)
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<DayTimeEntries>()
+            .await
+    }
+
+    pub async fn create_quick_entry(
+        &self,
+        entry: &QuickEntry,
+    ) -> Result<DayTimeEntries, reqwest::Error> {
+        self.client
+            .post(format!("{}/time_entries/quick", self.base_url))
+            .json(entry)
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<DayTimeEntries>()
+            .await
+    }
+
+    pub async fn get_last_weeks_entries(&self, day: i16) -> Result<Vec<EntryCopy>, reqwest::Error> {
+        self.client
+            .get(format!(
+                "{}/time_entries/day/{}/last_week",
+                self.base_url, day
+            ))
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<Vec<EntryCopy>>()
+            .await
+    }
+
+    pub async fn set_time_entry_range(
+        &self,
+        id: i32,
+        range: TimeRange,
+    ) -> Result<TimeEntryVM, reqwest::Error> {
+        self.client
+            .put(format!("{}/time_entries/{}/range", self.base_url, id))
+            .json(&range)
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<TimeEntryVM>()
+            .await
+    }
+
+    pub async fn get_time_segments(&self) -> Result<Vec<TimeSegment>, reqwest::Error> {
+        self.client
+            .get(format!("{}/time_segments", self.base_url))
+            .send()
+            .await?
+            .json::<Vec<TimeSegment>>()
+            .await
+    }
+
     pub async fn update_time_entry(
         &self,
         time_entry_update: TimeEntryVM,
@@ -145,6 +247,114 @@ impl ApiClient {
             .await
     }
 
+    pub async fn get_trash(&self) -> Result<Vec<TrashedEntry>, reqwest::Error> {
+        self.client
+            .get(format!("{}/time_entries/trash", self.base_url))
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<Vec<TrashedEntry>>()
+            .await
+    }
+
+    pub async fn restore_entry(&self, id: i32) -> Result<DayTimeEntries, reqwest::Error> {
+        self.client
+            .put(format!("{}/time_entries/{}/restore", self.base_url, id))
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<DayTimeEntries>()
+            .await
+    }
+
+    pub async fn split_entry(
+        &self,
+        id: i32,
+        request: &SplitRequest,
+    ) -> Result<DayTimeEntries, reqwest::Error> {
+        self.client
+            .post(format!("{}/time_entries/{}/split", self.base_url, id))
+            .json(request)
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<DayTimeEntries>()
+            .await
+    }
+
+    pub async fn merge_entries(&self, id: i32) -> Result<DayTimeEntries, reqwest::Error> {
+        self.client
+            .post(format!("{}/time_entries/{}/merge", self.base_url, id))
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<DayTimeEntries>()
+            .await
+    }
+
+    pub async fn move_entry(&self, id: i32, offset: i32) -> Result<DayTimeEntries, reqwest::Error> {
+        self.client
+            .put(format!(
+                "{}/time_entries/{}/move/{}",
+                self.base_url, id, offset
+            ))
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<DayTimeEntries>()
+            .await
+    }
+
+    pub async fn search_entries(&self, query: &str) -> Result<Vec<TimeEntryVM>, reqwest::Error> {
+        self.client
+            .get(format!("{}/search", self.base_url))
+            .query(&[("q", query)])
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<Vec<TimeEntryVM>>()
+            .await
+    }
+
+    pub async fn get_costpoint_entries(&self) -> Result<Vec<CostpointEntryVM>, reqwest::Error> {
+        self.client
+            .get(format!("{}/time_entries/costpoint", self.base_url))
+            .send()
+            .await?
+            .json::<Vec<CostpointEntryVM>>()
+            .await
+    }
+
+    /// Newest first
+    pub async fn get_operations(&self) -> Result<Vec<Operation>, reqwest::Error> {
+        self.client
+            .get(format!("{}/operations", self.base_url))
+            .send()
+            .await?
+            .json::<Vec<Operation>>()
+            .await
+    }
+
+    /// The operation that was undone, `None` when there was nothing to undo
+    pub async fn undo_operation(&self) -> Result<Option<Operation>, reqwest::Error> {
+        self.client
+            .post(format!("{}/operations/undo", self.base_url))
+            .send()
+            .await?
+            .json::<Option<Operation>>()
+            .await
+    }
+
+    /// The operation that was redone, `None` when there was nothing to redo
+    pub async fn redo_operation(&self) -> Result<Option<Operation>, reqwest::Error> {
+        self.client
+            .post(format!("{}/operations/redo", self.base_url))
+            .send()
+            .await?
+            .json::<Option<Operation>>()
+            .await
+    }
+
     pub async fn cleanup_entries(&self) -> Result<(), reqwest::Error> {
         self.client
             .post(&format!("{}/admin/cleanup", self.base_url))
@@ -153,4 +363,109 @@ impl ApiClient {
             .error_for_status()
             .map(|_| ())
     }
+
+    pub async fn create_audit_entry(
+        &self,
+        entry: &MonitorAuditEntry,
+    ) -> Result<(), reqwest::Error> {
+        self.client
+            .post(format!("{}/audit_log", self.base_url))
+            .json(entry)
+            .send()
+            .await?
+            .error_for_status()
+            .map(|_| ())
+    }
+
+    pub async fn get_audit_log(&self) -> Result<Vec<MonitorAuditEntry>, reqwest::Error> {
+        self.client
+            .get(format!("{}/audit_log", self.base_url))
+            .send()
+            .await?
+            .json::<Vec<MonitorAuditEntry>>()
+            .await
+    }
+
+    pub async fn get_targets(&self) -> Result<WorkTargets, reqwest::Error> {
+        self.client
+            .get(format!("{}/targets", self.base_url))
+            .send()
+            .await?
+            .json::<WorkTargets>()
+            .await
+    }
+
+    pub async fn update_targets(
+        &self,
+        targets: &WorkTargets,
+    ) -> Result<WorkTargets, reqwest::Error> {
+        self.client
+            .put(format!("{}/targets", self.base_url))
+            .json(targets)
+            .send()
+            .await?
+            .json::<WorkTargets>()
+            .await
+    }
+
+    pub async fn get_budget_usage(&self) -> Result<Vec<BudgetUsage>, reqwest::Error> {
+        self.client
+            .get(format!("{}/charge_codes/budgets", self.base_url))
+            .send()
+            .await?
+            .json::<Vec<BudgetUsage>>()
+            .await
+    }
+
+    pub async fn update_charge_code_budget(
+        &self,
+        charge_code_id: i32,
+        budget: Option<ChargeCodeBudget>,
+    ) -> Result<Vec<BudgetUsage>, reqwest::Error> {
+        self.client
+            .put(format!(
+                "{}/charge_codes/{}/budget",
+                self.base_url, charge_code_id
+            ))
+            .json(&budget)
+            .send()
+            .await?
+            .json::<Vec<BudgetUsage>>()
+            .await
+    }
+
+    pub async fn update_charge_code_favorite(
+        &self,
+        charge_code_id: i32,
+        is_favorite: bool,
+    ) -> Result<Vec<ChargeCode>, reqwest::Error> {
+        self.client
+            .put(format!(
+                "{}/charge_codes/{}/favorite",
+                self.base_url, charge_code_id
+            ))
+            .json(&is_favorite)
+            .send()
+            .await?
+            .json::<Vec<ChargeCode>>()
+            .await
+    }
+
+    pub async fn update_charge_code_pinned(
+        &self,
+        charge_code_id: i32,
+        is_pinned: bool,
+    ) -> Result<Vec<ChargeCode>, reqwest::Error> {
+        self.client
+            .put(format!(
+                "{}/charge_codes/{}/pinned",
+                self.base_url, charge_code_id
+            ))
+            .json(&is_pinned)
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<Vec<ChargeCode>>()
+            .await
+    }
 }
@@ -0,0 +1,15 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::time_entry::TimeEntryVM;
+
+/// A record of a change the monitor service applied to a time entry on its own.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct MonitorAuditEntry {
+    pub rule_name: String,
+    pub action: String,
+    pub entry_id: i32,
+    pub entry_before: Option<TimeEntryVM>,
+    pub entry_after: Option<TimeEntryVM>,
+    pub applied_at: DateTime<Utc>,
+}
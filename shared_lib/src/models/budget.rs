@@ -0,0 +1,108 @@
+use chrono::{Datelike, Duration, NaiveDate};
+
+use crate::utils::time_zone::week_start;
+use serde::{Deserialize, Serialize};
+use serde_repr::{Deserialize_repr, Serialize_repr};
+
+const PAY_PERIOD_DAYS: i64 = 14;
+
+#[derive(Debug, Clone, Copy, Eq, Hash, PartialEq, Serialize_repr, Deserialize_repr, sqlx::Type)]
+#[repr(i16)]
+pub enum BudgetPeriod {
+    Week = 0,
+    // two weeks, counted from the budget's start date
+    PayPeriod = 1,
+    Month = 2,
+    // the whole contract, counted from the budget's start date
+    Contract = 3,
+}
+
+impl BudgetPeriod {
+    /// First day of the period containing `today`. Pay periods line up with `anchor`,
+    /// and contracts run from it, falling back to the current week when there isn't one.
+    pub fn period_start(&self, today: NaiveDate, anchor: Option<NaiveDate>) -> NaiveDate {
+        let week_start = week_start(today);
+        match (self, anchor) {
+            (BudgetPeriod::Week, _) => week_start,
+            (BudgetPeriod::PayPeriod, Some(anchor)) => {
+                let periods = (today - anchor).num_days().div_euclid(PAY_PERIOD_DAYS);
+                anchor + Duration::days(periods * PAY_PERIOD_DAYS)
+            }
+            (BudgetPeriod::Month, _) => today.with_day(1).unwrap_or(today),
+            (BudgetPeriod::Contract, Some(anchor)) => anchor,
+            (_, None) => week_start,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChargeCodeBudget {
+    pub minutes: i64,
+    pub period: BudgetPeriod,
+    pub start: Option<NaiveDate>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BudgetUsage {
+    pub charge_code_id: i32,
+    pub alias: String,
+    pub period: BudgetPeriod,
+    pub period_start: NaiveDate,
+    pub budget_minutes: i64,
+    pub used_minutes: i64,
+}
+
+impl BudgetUsage {
+    /// Negative once the budget has been overrun
+    pub fn remaining_minutes(&self) -> i64 {
+        self.budget_minutes - self.used_minutes
+    }
+
+    pub fn used_percent(&self) -> i64 {
+        if self.budget_minutes <= 0 {
+            return 100;
+        }
+        self.used_minutes * 100 / self.budget_minutes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn pay_periods_line_up_with_the_anchor() {
+        let anchor = date(2024, 1, 1);
+
+        assert_eq!(
+            BudgetPeriod::PayPeriod.period_start(date(2024, 1, 14), Some(anchor)),
+            date(2024, 1, 1)
+        );
+        assert_eq!(
+            BudgetPeriod::PayPeriod.period_start(date(2024, 1, 15), Some(anchor)),
+            date(2024, 1, 15)
+        );
+    }
+
+    #[test]
+    fn calendar_periods_ignore_the_anchor() {
+        let today = date(2024, 3, 14);
+
+        assert_eq!(
+            BudgetPeriod::Week.period_start(today, None),
+            date(2024, 3, 11)
+        );
+        assert_eq!(
+            BudgetPeriod::Month.period_start(today, Some(date(2023, 6, 5))),
+            date(2024, 3, 1)
+        );
+        assert_eq!(
+            BudgetPeriod::Contract.period_start(today, Some(date(2023, 6, 5))),
+            date(2023, 6, 5)
+        );
+    }
+}
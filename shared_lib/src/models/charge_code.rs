@@ -1,14 +1,44 @@
+use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Deserialize, Clone, Eq, Hash, PartialEq, Debug, sqlx::FromRow)]
+use super::budget::{BudgetPeriod, ChargeCodeBudget};
+
+#[derive(Default, Serialize, Deserialize, Clone, Eq, Hash, PartialEq, Debug, sqlx::FromRow)]
 pub struct ChargeCode {
     pub id: i32,
     pub alias: String,
     pub code: String,
     pub is_nc: bool,
+    #[serde(default)]
+    pub budget_minutes: Option<i32>,
+    #[serde(default)]
+    pub budget_period: Option<BudgetPeriod>,
+    #[serde(default)]
+    pub budget_start: Option<NaiveDate>,
+    #[serde(default)]
+    pub is_favorite: bool,
+    #[serde(default)]
+    pub last_used_at: Option<DateTime<Utc>>,
+    // gets an empty entry each day
+    #[serde(default)]
+    #[sqlx(default)]
+    pub is_pinned: bool,
+}
+
+impl ChargeCode {
+    pub fn budget(&self) -> Option<ChargeCodeBudget> {
+        match (self.budget_minutes, self.budget_period) {
+            (Some(minutes), Some(period)) => Some(ChargeCodeBudget {
+                minutes: minutes as i64,
+                period,
+                start: self.budget_start,
+            }),
+            _ => None,
+        }
+    }
 }
 
-#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+#[derive(Default, Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
 pub struct ChargeCodeVM {
     pub id: i32,
     pub alias: String,
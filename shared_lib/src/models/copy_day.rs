@@ -0,0 +1,81 @@
+use serde::{Deserialize, Serialize};
+
+use super::{charge_code::ChargeCodeVM, time_entry::TimeEntryVM};
+
+/// An entry to recreate on another day
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct EntryCopy {
+    pub charge_code: Option<ChargeCodeVM>,
+    pub total_time: i64, // milliseconds
+    pub note: String,
+}
+
+impl From<&TimeEntryVM> for EntryCopy {
+    fn from(entry: &TimeEntryVM) -> Self {
+        Self {
+            charge_code: entry.charge_code.clone(),
+            // a running timer is copied with the time it has so far
+            total_time: entry.real_total_time(),
+            note: entry.note.clone(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct CopyDayRequest {
+    pub entries: Vec<EntryCopy>,
+    // without these the copies start out empty, with just the charge code
+    pub with_time: bool,
+    pub with_notes: bool,
+}
+
+impl CopyDayRequest {
+    /// The entries as they'll be created
+    pub fn copies(&self) -> Vec<EntryCopy> {
+        self.entries
+            .iter()
+            .map(|entry| EntryCopy {
+                charge_code: entry.charge_code.clone(),
+                total_time: match self.with_time {
+                    true => entry.total_time.max(0),
+                    false => 0,
+                },
+                note: match self.with_notes {
+                    true => entry.note.clone(),
+                    false => String::new(),
+                },
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn copies_leave_out_time_and_notes_unless_asked() {
+        let entry = EntryCopy {
+            charge_code: Some(ChargeCodeVM {
+                id: 3,
+                alias: "Ops".to_string(),
+            }),
+            total_time: 90 * 60_000,
+            note: "patching".to_string(),
+        };
+        let mut request = CopyDayRequest {
+            entries: vec![entry.clone()],
+            with_time: false,
+            with_notes: false,
+        };
+
+        let copies = request.copies();
+        assert_eq!(copies[0].charge_code, entry.charge_code);
+        assert_eq!(copies[0].total_time, 0);
+        assert_eq!(copies[0].note, "");
+
+        request.with_time = true;
+        request.with_notes = true;
+        assert_eq!(request.copies(), vec![entry]);
+    }
+}
@@ -0,0 +1,10 @@
+use serde::{Deserialize, Serialize};
+
+/// A line for the Costpoint timesheet: one charge code on one day
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct CostpointEntryVM {
+    pub charge_code: String,
+    pub hours: String,
+    pub date: String,
+    pub notes: String,
+}
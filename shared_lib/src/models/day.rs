@@ -1,5 +1,9 @@
-use chrono::{Datelike, Duration, NaiveDate, Utc, Weekday};
+use chrono::{DateTime, Datelike, Duration, NaiveDate, Utc, Weekday};
+use chrono_tz::Tz;
 use serde_repr::{Deserialize_repr, Serialize_repr};
+use std::str::FromStr;
+
+use crate::utils::time_zone::{local_date, user_time_zone};
 
 // Serialize_repr makes this serialize as the varient number, instead of a string for the day
 // ditto for Deserialize_repr, but in reverse
@@ -18,8 +22,11 @@ pub enum Day {
 
 impl Day {
     pub fn get_current_day() -> Self {
-        let today = Utc::now().date_naive().weekday();
-        match today {
+        Self::current_day_in(user_time_zone(), Utc::now())
+    }
+
+    pub fn current_day_in(tz: Tz, now: DateTime<Utc>) -> Self {
+        match local_date(now, tz).weekday() {
             Weekday::Mon => Day::Monday,
             Weekday::Tue => Day::Tuesday,
             Weekday::Wed => Day::Wednesday,
@@ -29,8 +36,24 @@ impl Day {
         }
     }
 
+    /// The tracked day a date falls on, weekends aren't tracked
+    pub fn from_date(date: NaiveDate) -> Option<Self> {
+        match date.weekday() {
+            Weekday::Mon => Some(Day::Monday),
+            Weekday::Tue => Some(Day::Tuesday),
+            Weekday::Wed => Some(Day::Wednesday),
+            Weekday::Thu => Some(Day::Thursday),
+            Weekday::Fri => Some(Day::Friday),
+            Weekday::Sat | Weekday::Sun => None,
+        }
+    }
+
     pub fn into_date(&self) -> NaiveDate {
-        let current_date = Utc::now().date_naive();
+        self.into_date_in(user_time_zone(), Utc::now())
+    }
+
+    pub fn into_date_in(&self, tz: Tz, now: DateTime<Utc>) -> NaiveDate {
+        let current_date = local_date(now, tz);
         let current_weekday = current_date.weekday();
         let target_weekday = match self {
             Day::Monday => Weekday::Mon,
@@ -53,6 +76,22 @@ impl From<Day> for i16 {
     }
 }
 
+impl FromStr for Day {
+    type Err = String;
+
+    /// Accepts full or short weekday names, like `tuesday` or `tue`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "mon" | "monday" => Ok(Day::Monday),
+            "tue" | "tues" | "tuesday" => Ok(Day::Tuesday),
+            "wed" | "wednesday" => Ok(Day::Wednesday),
+            "thu" | "thur" | "thurs" | "thursday" => Ok(Day::Thursday),
+            "fri" | "friday" => Ok(Day::Friday),
+            other => Err(format!("'{}' is not a weekday", other)),
+        }
+    }
+}
+
 impl From<i16> for Day {
     fn from(value: i16) -> Self {
         match value {
@@ -65,3 +104,41 @@ impl From<i16> for Day {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono_tz::America::Chicago;
+
+    fn utc(s: &str) -> DateTime<Utc> {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn current_day_does_not_flip_in_the_evening() {
+        // Monday 8:30pm CDT, right after spring forward, is Tuesday in UTC
+        let now = utc("2024-03-12T01:30:00Z");
+        assert_eq!(Day::current_day_in(Chicago, now), Day::Monday);
+        assert_eq!(Day::current_day_in(Tz::UTC, now), Day::Tuesday);
+    }
+
+    #[test]
+    fn parses_weekday_names() {
+        assert_eq!("tue".parse::<Day>(), Ok(Day::Tuesday));
+        assert_eq!("Friday".parse::<Day>(), Ok(Day::Friday));
+        assert!("sat".parse::<Day>().is_err());
+    }
+
+    #[test]
+    fn into_date_is_relative_to_the_local_week() {
+        let now = utc("2024-03-12T01:30:00Z");
+        assert_eq!(
+            Day::Friday.into_date_in(Chicago, now),
+            NaiveDate::from_ymd_opt(2024, 3, 15).unwrap()
+        );
+        assert_eq!(
+            Day::Monday.into_date_in(Chicago, now),
+            NaiveDate::from_ymd_opt(2024, 3, 11).unwrap()
+        );
+    }
+}
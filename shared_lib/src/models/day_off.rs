@@ -0,0 +1,21 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use serde_repr::{Deserialize_repr, Serialize_repr};
+
+#[derive(Debug, Clone, Copy, Eq, Hash, PartialEq, Serialize_repr, Deserialize_repr, sqlx::Type)]
+#[repr(i16)]
+pub enum DayOffKind {
+    Holiday = 0,
+    Pto = 1,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DayOff {
+    pub date: NaiveDate,
+    pub kind: DayOffKind,
+    pub name: String,
+    pub minutes: i64,
+    pub charge_code_id: Option<i32>,
+    // the entry created for this day, once its week comes around
+    pub entry_id: Option<i32>,
+}
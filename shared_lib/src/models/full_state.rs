@@ -1,24 +1,42 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
-use super::{charge_code::ChargeCode, day::Day, time_entry::TimeEntryVM};
+use super::{
+    budget::BudgetUsage,
+    charge_code::ChargeCode,
+    day::Day,
+    day_off::DayOff,
+    targets::{TargetProgress, WorkTargets},
+    time_entry::TimeEntryVM,
+    time_segment::TimeSegment,
+};
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct FullState {
     pub time_entries: HashMap<Day, Vec<TimeEntryVM>>,
     pub charge_codes: Vec<ChargeCode>,
+    #[serde(default)]
+    pub targets: WorkTargets,
+    #[serde(default)]
+    pub budgets: Vec<BudgetUsage>,
+    // holidays and PTO in the current week
+    #[serde(default)]
+    pub days_off: Vec<DayOff>,
+    // when the entries were worked, for the timeline
+    #[serde(default)]
+    pub segments: Vec<TimeSegment>,
+    // last week's entries until the cleanup removes them, already counted in the carried balance
+    #[serde(default)]
+    pub earlier_week_entries: Vec<i32>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, Eq, PartialEq)]
 pub struct DayTimeEntries {
     pub day: Day,
     pub entries: Vec<TimeEntryVM>,
-}
-
-#[derive(Debug, Clone)]
-pub struct TimeEntriesDiff {
-    pub to_upsert: Vec<TimeEntryVM>,
-    pub to_delete: Vec<i32>, // Assuming `id` is sufficient to identify deletions
+    // the entry the request made, when it made one
+    #[serde(default)]
+    pub created_id: Option<i32>,
 }
 
 impl FullState {
@@ -29,49 +47,87 @@ impl FullState {
             .collect()
     }
 
+    pub fn get_budget_for_code(&self, charge_code_id: i32) -> Option<&BudgetUsage> {
+        self.budgets
+            .iter()
+            .find(|budget| budget.charge_code_id == charge_code_id)
+    }
+
     pub fn get_vms_for_day(&self, day: Day) -> Option<&Vec<TimeEntryVM>> {
         self.time_entries.get(&day)
     }
 
-    pub fn diff(&self, other: &Self) -> TimeEntriesDiff {
-        let mut to_upsert = Vec::new();
-        let mut self_entries_map = HashMap::new();
+    pub fn get_entry_by_id(&self, id: i32) -> Option<&TimeEntryVM> {
+        self.time_entries
+            .values()
+            .flatten()
+            .find(|entry| entry.id == id)
+    }
 
-        // Build a map for quick lookup of self entries
-        for entries in self.time_entries.values().flatten() {
-            self_entries_map.insert(entries.id, entries);
-        }
+    pub fn get_day_off(&self, day: Day) -> Option<&DayOff> {
+        let date = day.into_date();
+        self.days_off.iter().find(|day_off| day_off.date == date)
+    }
+
+    /// Entries created for holidays and PTO, which don't count as time worked
+    pub fn is_day_off_entry(&self, entry_id: i32) -> bool {
+        self.days_off
+            .iter()
+            .any(|day_off| day_off.entry_id == Some(entry_id))
+    }
 
-        // Entries in `other` to check against `self` for changes
-        for (&day, entries) in &other.time_entries {
-            if let Some(self_entries) = self.time_entries.get(&day) {
-                let self_entries_map: HashMap<i32, &TimeEntryVM> =
-                    self_entries.iter().map(|e| (e.id, e)).collect();
-
-                for entry in entries {
-                    if let Some(self_entry) = self_entries_map.get(&entry.id) {
-                        if entry != *self_entry {
-                            // Entry has changed, mark for upsert from `other`
-                            to_upsert.push(entry.clone());
-                        }
-                    } else {
-                        // Entry not found in `self`, it's a new entry, mark for upsert
-                        to_upsert.push(entry.clone());
-                    }
-                }
-            } else {
-                // If the day doesn't exist in `self`, all these entries are new or changed
-                to_upsert.extend(entries.clone());
-            }
+    /// Whether the entry's time is worked this week, rather than a day off or an earlier week
+    pub fn counts_as_worked(&self, entry_id: i32) -> bool {
+        !self.is_day_off_entry(entry_id) && !self.earlier_week_entries.contains(&entry_id)
+    }
+
+    pub fn day_progress(&self, day: Day) -> TargetProgress {
+        let worked = self.get_vms_for_day(day).map_or(0, |entries| {
+            entries
+                .iter()
+                .filter(|e| self.counts_as_worked(e.id))
+                .map(|e| e.real_total_time())
+                .sum()
+        });
+        TargetProgress::new(worked, self.targets.minutes_for_day(day))
+    }
+
+    pub fn week_progress(&self) -> TargetProgress {
+        let worked = self
+            .time_entries
+            .values()
+            .flatten()
+            .filter(|e| self.counts_as_worked(e.id))
+            .map(|e| e.real_total_time())
+            .sum();
+        TargetProgress::new(worked, self.targets.adjusted_weekly_minutes())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HOUR: i64 = 3_600_000;
+
+    fn entry(id: i32, total_time: i64) -> TimeEntryVM {
+        TimeEntryVM {
+            id,
+            total_time,
+            day: Day::Tuesday,
+            ..Default::default()
         }
+    }
 
-        // Entries in `self` that aren't in `other`, mark for deletion
-        let to_delete: Vec<i32> = self_entries_map
-            .keys()
-            .filter(|id| !other.time_entries.values().flatten().any(|e| e.id == **id))
-            .cloned()
-            .collect();
+    #[test]
+    fn earlier_weeks_dont_count_toward_this_week() {
+        let state = FullState {
+            time_entries: HashMap::from([(Day::Tuesday, vec![entry(1, 2 * HOUR), entry(2, HOUR)])]),
+            earlier_week_entries: vec![1],
+            ..Default::default()
+        };
 
-        TimeEntriesDiff { to_upsert, to_delete }
+        assert_eq!(state.week_progress().worked_millis, HOUR);
+        assert_eq!(state.day_progress(Day::Tuesday).worked_millis, HOUR);
     }
 }
@@ -1,4 +1,16 @@
+pub mod audit_log;
+pub mod budget;
 pub mod charge_code;
+pub mod copy_day;
+pub mod costpoint_entry;
 pub mod day;
+pub mod day_off;
 pub mod full_state;
+pub mod operation;
+pub mod quick_entry;
+pub mod split;
+pub mod targets;
 pub mod time_entry;
+pub mod time_segment;
+pub mod timer_status;
+pub mod trash;
@@ -0,0 +1,210 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::{time_entry::TimeEntryVM, time_segment::TimeSegment};
+
+/// One entry on either side of a change. `before` is empty for a new entry, `after` for a deleted one.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct EntryChange {
+    pub before: Option<TimeEntryVM>,
+    pub after: Option<TimeEntryVM>,
+    // the entry's segments on either side, when the change wrote, moved or dropped any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub segments: Option<SegmentChange>,
+    // the empty side is the trash rather than gone for good, for deletes and restores
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub trashed: bool,
+}
+
+/// An entry's segments before and after a change, kept with their ids so undo and redo can
+/// put back exactly those rows
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct SegmentChange {
+    pub before: Vec<TimeSegment>,
+    pub after: Vec<TimeSegment>,
+}
+
+/// A change made through the API, kept with what it replaced so it can be undone and redone.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct Operation {
+    pub id: i32,
+    pub description: String,
+    pub changes: Vec<EntryChange>,
+    pub is_undone: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+fn format_time(millis: i64) -> String {
+    let minutes = millis / 60_000;
+    format!("{}:{:02}", minutes / 60, minutes % 60)
+}
+
+fn alias(entry: &TimeEntryVM) -> &str {
+    entry
+        .charge_code
+        .as_ref()
+        .map_or("no code", |code| code.alias.as_str())
+}
+
+impl EntryChange {
+    pub fn new(before: Option<TimeEntryVM>, after: Option<TimeEntryVM>) -> Self {
+        Self {
+            before,
+            after,
+            segments: None,
+            trashed: false,
+        }
+    }
+
+    pub fn trashed(mut self) -> Self {
+        self.trashed = true;
+        self
+    }
+
+    pub fn with_segments(mut self, before: Vec<TimeSegment>, after: Vec<TimeSegment>) -> Self {
+        if before != after {
+            self.segments = Some(SegmentChange { before, after });
+        }
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.before == self.after && self.segments.is_none()
+    }
+
+    /// Reads like "changed time on #42 from 1:15 to 2:00"
+    pub fn describe(&self) -> String {
+        let (before, after) = match (&self.before, &self.after) {
+            (None, None) => return "nothing".to_string(),
+            (None, Some(after)) if self.trashed => {
+                return format!("restored #{} from the trash", after.id)
+            }
+            (None, Some(after)) => return format!("added #{} on {:?}", after.id, after.day),
+            (Some(before), None) => {
+                return format!(
+                    "deleted #{} ({}, {})",
+                    before.id,
+                    alias(before),
+                    format_time(before.total_time)
+                )
+            }
+            (Some(before), Some(after)) => (before, after),
+        };
+
+        // the total moves whenever a timer stops, so that's all a stop says
+        match (before.start_time, after.start_time) {
+            (None, Some(_)) => return format!("started #{}", after.id),
+            (Some(_), None) => return format!("stopped #{}", after.id),
+            _ => {}
+        }
+
+        let mut parts = Vec::new();
+        if before.total_time != after.total_time {
+            parts.push(format!(
+                "time on #{} from {} to {}",
+                after.id,
+                format_time(before.total_time),
+                format_time(after.total_time)
+            ));
+        }
+        if before.charge_code != after.charge_code {
+            parts.push(format!(
+                "code on #{} from {} to {}",
+                after.id,
+                alias(before),
+                alias(after)
+            ));
+        }
+        if before.note != after.note {
+            parts.push(format!("note on #{}", after.id));
+        }
+        if before.day != after.day {
+            parts.push(format!(
+                "day on #{} from {:?} to {:?}",
+                after.id, before.day, after.day
+            ));
+        }
+
+        match parts.is_empty() {
+            true => format!("touched #{}", after.id),
+            false => format!("changed {}", parts.join(", ")),
+        }
+    }
+}
+
+pub fn describe_changes(changes: &[EntryChange]) -> String {
+    changes
+        .iter()
+        .filter(|change| !change.is_empty())
+        .map(EntryChange::describe)
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{charge_code::ChargeCodeVM, day::Day};
+
+    fn entry(total_time: i64) -> TimeEntryVM {
+        TimeEntryVM {
+            id: 42,
+            total_time,
+            day: Day::Monday,
+            charge_code: Some(ChargeCodeVM {
+                id: 1,
+                alias: "Ops".to_string(),
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn describes_changes() {
+        let before = entry(75 * 60_000);
+        let after = entry(120 * 60_000);
+
+        assert_eq!(
+            EntryChange::new(Some(before.clone()), Some(after.clone())).describe(),
+            "changed time on #42 from 1:15 to 2:00"
+        );
+        assert_eq!(
+            EntryChange::new(None, Some(after.clone())).describe(),
+            "added #42 on Monday"
+        );
+        assert_eq!(
+            EntryChange::new(Some(before.clone()), None).describe(),
+            "deleted #42 (Ops, 1:15)"
+        );
+        assert_eq!(
+            EntryChange::new(None, Some(after.clone()))
+                .trashed()
+                .describe(),
+            "restored #42 from the trash"
+        );
+
+        let renamed = TimeEntryVM {
+            note: "standup".to_string(),
+            charge_code: None,
+            ..before.clone()
+        };
+        assert_eq!(
+            EntryChange::new(Some(before.clone()), Some(renamed)).describe(),
+            "changed code on #42 from Ops to no code, note on #42"
+        );
+
+        let started = TimeEntryVM {
+            start_time: Some(Utc::now()),
+            is_active: true,
+            ..before.clone()
+        };
+        let stopped = EntryChange::new(Some(started.clone()), Some(after));
+        assert_eq!(
+            describe_changes(&[
+                EntryChange::new(Some(before.clone()), Some(before)),
+                stopped,
+            ]),
+            "stopped #42"
+        );
+    }
+}
@@ -0,0 +1,27 @@
+use serde::{Deserialize, Serialize};
+
+use super::{charge_code::ChargeCodeVM, day::Day, time_segment::TimeRange};
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum QuickTime {
+    Duration(i64), // milliseconds
+    Range(TimeRange),
+}
+
+/// A whole entry typed on one line, see `utils::quick_add`
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct QuickEntry {
+    pub day: Day,
+    pub charge_code: Option<ChargeCodeVM>,
+    pub time: QuickTime,
+    pub note: String,
+}
+
+impl QuickEntry {
+    pub fn millis(&self) -> i64 {
+        match self.time {
+            QuickTime::Duration(millis) => millis,
+            QuickTime::Range(range) => range.millis(),
+        }
+    }
+}
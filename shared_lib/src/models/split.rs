@@ -0,0 +1,135 @@
+use crate::utils::duration::parse_duration;
+use serde::{Deserialize, Serialize};
+
+/// How much of an entry's time moves to the new entry
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SplitAmount {
+    Millis(i64),
+    Percent(i64),
+}
+
+/// What happens to the note when an entry is split
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum NoteSplit {
+    // both entries get the whole note
+    #[default]
+    Copy,
+    // the lines are shared out in proportion to the time
+    Divide,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct SplitRequest {
+    pub amount: SplitAmount,
+    // `None` keeps the code the entry already has
+    pub charge_code_id: Option<i32>,
+    pub note: NoteSplit,
+}
+
+// anything else leaves nothing on one of the entries
+const SPLIT_PERCENTS: std::ops::RangeInclusive<i64> = 1..=99;
+
+impl SplitAmount {
+    /// Reads `40%` as a share of the entry, anything else as a duration like `1h30m`
+    pub fn parse(input: &str) -> Result<Self, String> {
+        let invalid = || format!("invalid percentage '{}'", input.trim());
+        match input.trim().strip_suffix('%') {
+            Some(percent) => match percent.trim().parse() {
+                Ok(percent) if SPLIT_PERCENTS.contains(&percent) => {
+                    Ok(SplitAmount::Percent(percent))
+                }
+                _ => Err(invalid()),
+            },
+            None => parse_duration(input).map(SplitAmount::Millis),
+        }
+    }
+
+    /// The time taken off an entry with `total_time` on it, which has to leave some on both sides
+    pub fn moved_millis(&self, total_time: i64) -> Result<i64, String> {
+        let moved = match *self {
+            SplitAmount::Millis(millis) => millis,
+            SplitAmount::Percent(percent) if SPLIT_PERCENTS.contains(&percent) => {
+                total_time * percent / 100
+            }
+            SplitAmount::Percent(_) => 0,
+        };
+        match moved > 0 && moved < total_time {
+            true => Ok(moved),
+            false => Err("The split has to leave time on both entries".to_string()),
+        }
+    }
+}
+
+/// The kept and the moved note, with the last lines moving when the note is divided
+pub fn split_note(
+    note: &str,
+    how: NoteSplit,
+    moved_millis: i64,
+    total_time: i64,
+) -> (String, String) {
+    match how {
+        NoteSplit::Copy => (note.to_string(), note.to_string()),
+        NoteSplit::Divide => {
+            let lines: Vec<&str> = note.lines().collect();
+            let moved_lines = match total_time {
+                0 => 0,
+                _ => (lines.len() as i64 * moved_millis + total_time / 2) / total_time,
+            };
+            let (kept, moved) = lines.split_at(lines.len() - moved_lines as usize);
+            (kept.join("\n"), moved.join("\n"))
+        }
+    }
+}
+
+/// One note from several, leaving out blanks and lines already there
+pub fn merge_notes<'a>(notes: impl IntoIterator<Item = &'a str>) -> String {
+    let mut merged: Vec<&str> = Vec::new();
+    for line in notes.into_iter().flat_map(str::lines) {
+        if !line.trim().is_empty() && !merged.contains(&line) {
+            merged.push(line);
+        }
+    }
+    merged.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn amounts_take_durations_or_percentages() {
+        let hour = 3_600_000;
+        assert_eq!(SplitAmount::parse("40%"), Ok(SplitAmount::Percent(40)));
+        assert_eq!(SplitAmount::parse("1h"), Ok(SplitAmount::Millis(hour)));
+        assert!(SplitAmount::parse("lots%").is_err());
+        assert!(SplitAmount::parse("100%").is_err());
+        assert!(SplitAmount::parse("9223372036854775807%").is_err());
+
+        assert_eq!(SplitAmount::Percent(25).moved_millis(4 * hour), Ok(hour));
+        assert_eq!(SplitAmount::Millis(hour).moved_millis(3 * hour), Ok(hour));
+        assert!(SplitAmount::Millis(hour).moved_millis(hour).is_err());
+        assert!(SplitAmount::Percent(0).moved_millis(hour).is_err());
+        assert!(SplitAmount::Percent(i64::MAX).moved_millis(hour).is_err());
+    }
+
+    #[test]
+    fn notes_divide_by_time_and_merge_without_repeats() {
+        let note = "standup\nreview\nfix login\ndeploy";
+        assert_eq!(
+            split_note(note, NoteSplit::Divide, 1, 4),
+            (
+                "standup\nreview\nfix login".to_string(),
+                "deploy".to_string()
+            )
+        );
+        assert_eq!(
+            split_note(note, NoteSplit::Copy, 1, 4),
+            (note.to_string(), note.to_string())
+        );
+
+        assert_eq!(
+            merge_notes(["standup\nreview", "", "review\ndeploy"]),
+            "standup\nreview\ndeploy"
+        );
+    }
+}
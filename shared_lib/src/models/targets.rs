@@ -0,0 +1,150 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use super::day::Day;
+
+const DEFAULT_DAILY_MINUTES: i64 = 8 * 60;
+const DEFAULT_WEEKLY_MINUTES: i64 = 40 * 60;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WorkTargets {
+    pub daily_minutes: HashMap<Day, i64>,
+    pub weekly_minutes: i64,
+    // minutes over (positive) or under (negative) target from previous weeks,
+    // computed by the server and ignored on updates
+    #[serde(default)]
+    pub carried_balance_minutes: i64,
+    // time off this week, which isn't expected to be worked
+    #[serde(default)]
+    pub days_off_minutes: HashMap<Day, i64>,
+}
+
+impl Default for WorkTargets {
+    fn default() -> Self {
+        let daily_minutes = [
+            Day::Monday,
+            Day::Tuesday,
+            Day::Wednesday,
+            Day::Thursday,
+            Day::Friday,
+        ]
+        .into_iter()
+        .map(|day| (day, DEFAULT_DAILY_MINUTES))
+        .collect();
+
+        Self {
+            daily_minutes,
+            weekly_minutes: DEFAULT_WEEKLY_MINUTES,
+            carried_balance_minutes: 0,
+            days_off_minutes: HashMap::new(),
+        }
+    }
+}
+
+impl WorkTargets {
+    /// The day's target less any time off taken that day
+    pub fn minutes_for_day(&self, day: Day) -> i64 {
+        let target = self.daily_minutes.get(&day).copied().unwrap_or(0);
+        let off = self.days_off_minutes.get(&day).copied().unwrap_or(0);
+        (target - off).max(0)
+    }
+
+    /// This week's target after applying the balance carried from previous weeks,
+    /// so overtime lowers the goal and a shortfall raises it, and removing time off.
+    pub fn adjusted_weekly_minutes(&self) -> i64 {
+        let off: i64 = self.days_off_minutes.values().sum();
+        self.weekly_minutes - self.carried_balance_minutes - off
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TargetProgress {
+    pub worked_millis: i64,
+    pub target_millis: i64,
+}
+
+impl TargetProgress {
+    pub fn new(worked_millis: i64, target_minutes: i64) -> Self {
+        Self {
+            worked_millis,
+            target_millis: target_minutes * 60_000,
+        }
+    }
+
+    pub fn remaining_millis(&self) -> i64 {
+        (self.target_millis - self.worked_millis).max(0)
+    }
+
+    /// Positive when ahead of the target, negative when behind
+    pub fn balance_millis(&self) -> i64 {
+        self.worked_millis - self.target_millis
+    }
+
+    pub fn ratio(&self) -> f64 {
+        if self.target_millis <= 0 {
+            return 1.0;
+        }
+        (self.worked_millis as f64 / self.target_millis as f64).clamp(0.0, 1.0)
+    }
+
+    /// When the target will be hit if work continues without a break from `now`
+    pub fn projected_finish(&self, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        match self.remaining_millis() {
+            0 => None,
+            remaining => Some(now + Duration::milliseconds(remaining)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HOUR: i64 = 3_600_000;
+
+    #[test]
+    fn progress_reports_remaining_time_and_finish() {
+        let progress = TargetProgress::new(5 * HOUR, 8 * 60);
+        let now: DateTime<Utc> = "2024-03-11T18:00:00Z".parse().unwrap();
+
+        assert_eq!(progress.remaining_millis(), 3 * HOUR);
+        assert_eq!(progress.balance_millis(), -3 * HOUR);
+        assert_eq!(
+            progress.projected_finish(now),
+            Some("2024-03-11T21:00:00Z".parse().unwrap())
+        );
+    }
+
+    #[test]
+    fn progress_past_target_has_no_finish() {
+        let progress = TargetProgress::new(9 * HOUR, 8 * 60);
+
+        assert_eq!(progress.remaining_millis(), 0);
+        assert_eq!(progress.balance_millis(), HOUR);
+        assert_eq!(progress.ratio(), 1.0);
+        assert_eq!(progress.projected_finish(Utc::now()), None);
+    }
+
+    #[test]
+    fn carried_balance_adjusts_the_weekly_target() {
+        let targets = WorkTargets {
+            carried_balance_minutes: 90,
+            ..WorkTargets::default()
+        };
+
+        assert_eq!(targets.adjusted_weekly_minutes(), 40 * 60 - 90);
+    }
+
+    #[test]
+    fn time_off_is_not_expected_to_be_worked() {
+        let targets = WorkTargets {
+            days_off_minutes: HashMap::from([(Day::Friday, 8 * 60), (Day::Monday, 4 * 60)]),
+            ..WorkTargets::default()
+        };
+
+        assert_eq!(targets.minutes_for_day(Day::Friday), 0);
+        assert_eq!(targets.minutes_for_day(Day::Monday), 4 * 60);
+        assert_eq!(targets.adjusted_weekly_minutes(), 28 * 60);
+    }
+}
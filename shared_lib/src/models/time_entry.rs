@@ -1,13 +1,13 @@
-use chrono::{NaiveDateTime, Utc};
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::hash::{Hash, Hasher};
 
 use super::{charge_code::ChargeCodeVM, day::Day};
 
-#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+#[derive(Default, Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
 pub struct TimeEntryVM {
     pub id: i32,
-    pub start_time: Option<NaiveDateTime>,
+    pub start_time: Option<DateTime<Utc>>,
     pub total_time: i64, // milliseconds
     pub note: String,
     pub day: Day,
@@ -23,7 +23,10 @@ impl Hash for TimeEntryVM {
 
 impl TimeEntryVM {
     pub fn real_total_time(&self) -> i64 {
-        let now: NaiveDateTime = Utc::now().naive_utc();
+        self.total_time_at(Utc::now())
+    }
+
+    pub fn total_time_at(&self, now: DateTime<Utc>) -> i64 {
         let elapsed_since_start = self
             .start_time
             .map(|start| now.signed_duration_since(start).num_milliseconds())
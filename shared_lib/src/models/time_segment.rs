@@ -0,0 +1,25 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// A stretch of time an entry was worked, from a stopped timer or typed in as clock times
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug, sqlx::FromRow)]
+pub struct TimeSegment {
+    pub id: i32,
+    pub time_entry_id: i32,
+    pub start_time: DateTime<Utc>,
+    pub end_time: DateTime<Utc>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct TimeRange {
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+}
+
+impl TimeRange {
+    pub fn millis(&self) -> i64 {
+        self.end
+            .signed_duration_since(self.start)
+            .num_milliseconds()
+    }
+}
@@ -0,0 +1,142 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::{day::Day, time_entry::TimeEntryVM};
+
+/// What's running right now, small enough to poll every second from a prompt or status bar
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TimerStatus {
+    pub active: Option<ActiveTimer>,
+    pub today: Day,
+    pub today_total: i64, // milliseconds, including the running timer
+    pub as_of: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ActiveTimer {
+    pub id: i32,
+    pub alias: Option<String>,
+    pub note: String,
+    pub day: Day,
+    pub started: DateTime<Utc>,
+    pub elapsed: i64, // milliseconds, the entry's whole total
+}
+
+impl TimerStatus {
+    pub fn from_entries(
+        running: &[TimeEntryVM],
+        today: Day,
+        today_entries: &[TimeEntryVM],
+        now: DateTime<Utc>,
+    ) -> Self {
+        let active = running.iter().find_map(|entry| {
+            entry.start_time.map(|started| ActiveTimer {
+                id: entry.id,
+                alias: entry.charge_code.as_ref().map(|code| code.alias.clone()),
+                note: entry.note.clone(),
+                day: entry.day,
+                started,
+                elapsed: entry.total_time_at(now),
+            })
+        });
+
+        Self {
+            active,
+            today,
+            today_total: today_entries
+                .iter()
+                .map(|entry| entry.total_time_at(now))
+                .sum(),
+            as_of: now,
+        }
+    }
+
+    /// The same status at a later time, counting the running timer forward
+    pub fn at(&self, now: DateTime<Utc>) -> Self {
+        let since = match self.active {
+            Some(_) => now
+                .signed_duration_since(self.as_of)
+                .num_milliseconds()
+                .max(0),
+            None => 0,
+        };
+        // a timer left running on another day doesn't add to today
+        let today_since = match &self.active {
+            Some(active) if active.day == self.today => since,
+            _ => 0,
+        };
+
+        Self {
+            active: self.active.clone().map(|active| ActiveTimer {
+                elapsed: active.elapsed + since,
+                ..active
+            }),
+            today: self.today,
+            today_total: self.today_total + today_since,
+            as_of: now,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+
+    use super::*;
+    use crate::models::{charge_code::ChargeCodeVM, day::Day};
+
+    fn entry(id: i32, day: Day, total_time: i64, start_time: Option<DateTime<Utc>>) -> TimeEntryVM {
+        TimeEntryVM {
+            id,
+            start_time,
+            total_time,
+            day,
+            is_active: start_time.is_some(),
+            charge_code: Some(ChargeCodeVM {
+                id,
+                alias: format!("code {}", id),
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn counts_the_running_timer() {
+        let now = Utc::now();
+        let running = entry(2, Day::Monday, 1_000, Some(now - Duration::seconds(60)));
+        let today = vec![entry(1, Day::Monday, 30_000, None), running.clone()];
+
+        let status = TimerStatus::from_entries(&[running], Day::Monday, &today, now);
+        let active = status.active.clone().unwrap();
+        assert_eq!(active.alias.as_deref(), Some("code 2"));
+        assert_eq!(active.elapsed, 61_000);
+        assert_eq!(status.today_total, 91_000);
+
+        let later = status.at(now + Duration::seconds(5));
+        assert_eq!(later.active.unwrap().elapsed, 66_000);
+        assert_eq!(later.today_total, 96_000);
+    }
+
+    #[test]
+    fn idle_status_stays_put() {
+        let now = Utc::now();
+        let today = vec![entry(1, Day::Monday, 30_000, None)];
+        let status = TimerStatus::from_entries(&[], Day::Monday, &today, now);
+
+        let later = status.at(now + Duration::seconds(5));
+        assert!(later.active.is_none());
+        assert_eq!(later.today_total, 30_000);
+    }
+
+    #[test]
+    fn timer_on_another_day_isnt_counted_today() {
+        let now = Utc::now();
+        let running = entry(2, Day::Friday, 0, Some(now - Duration::seconds(60)));
+        let today = vec![entry(1, Day::Monday, 30_000, None)];
+        let status = TimerStatus::from_entries(&[running], Day::Monday, &today, now);
+
+        let later = status.at(now + Duration::seconds(5));
+        assert_eq!(later.active.unwrap().elapsed, 65_000);
+        assert_eq!(later.today_total, 30_000);
+    }
+}
@@ -0,0 +1,11 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::time_entry::TimeEntryVM;
+
+/// A deleted entry, which can be restored until the trash is purged
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct TrashedEntry {
+    pub entry: TimeEntryVM,
+    pub deleted_at: DateTime<Utc>,
+}
@@ -0,0 +1,110 @@
+use chrono::{DateTime, LocalResult, NaiveDate, NaiveTime, TimeZone, Utc};
+use chrono_tz::Tz;
+
+use crate::models::{day::Day, time_segment::TimeRange};
+
+/// Parses a clock time like `9`, `9:30`, `0930`, `13:30` or `1:30pm`
+pub fn parse_clock_time(input: &str) -> Result<NaiveTime, String> {
+    let input: String = input
+        .to_lowercase()
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect();
+    let invalid = || format!("invalid time '{}'", input);
+
+    let (digits, is_pm) = match input.strip_suffix("am").or_else(|| input.strip_suffix('a')) {
+        Some(digits) => (digits, Some(false)),
+        None => match input.strip_suffix("pm").or_else(|| input.strip_suffix('p')) {
+            Some(digits) => (digits, Some(true)),
+            None => (input.as_str(), None),
+        },
+    };
+
+    let (hours, minutes) = match digits.split_once(':') {
+        Some((hours, minutes)) if minutes.len() == 2 => (hours, minutes),
+        Some(_) => return Err(invalid()),
+        None if digits.len() > 2 => digits.split_at(digits.len() - 2),
+        None => (digits, "00"),
+    };
+    let hours: u32 = hours.parse().map_err(|_| invalid())?;
+    let minutes: u32 = minutes.parse().map_err(|_| invalid())?;
+
+    let hours = match is_pm {
+        Some(_) if !(1..=12).contains(&hours) => return Err(invalid()),
+        Some(true) => hours % 12 + 12,
+        Some(false) => hours % 12,
+        None => hours,
+    };
+    NaiveTime::from_hms_opt(hours, minutes, 0).ok_or_else(invalid)
+}
+
+/// The instant a local clock time happens on `date`, the earlier one when clocks fall back
+pub fn local_time_on(date: NaiveDate, time: NaiveTime, tz: Tz) -> Result<DateTime<Utc>, String> {
+    match tz.from_local_datetime(&date.and_time(time)) {
+        LocalResult::Single(time) | LocalResult::Ambiguous(time, _) => Ok(time.with_timezone(&Utc)),
+        LocalResult::None => Err(format!(
+            "{} doesn't exist on {}",
+            time.format("%H:%M"),
+            date
+        )),
+    }
+}
+
+/// Start and end clock times on `day` of the week `now` falls in
+pub fn clock_range(
+    day: Day,
+    start: &str,
+    end: &str,
+    tz: Tz,
+    now: DateTime<Utc>,
+) -> Result<TimeRange, String> {
+    let date = day.into_date_in(tz, now);
+    let range = TimeRange {
+        start: local_time_on(date, parse_clock_time(start)?, tz)?,
+        end: local_time_on(date, parse_clock_time(end)?, tz)?,
+    };
+    match range.end > range.start {
+        true => Ok(range),
+        false => Err("the end has to be after the start".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono_tz::America::Chicago;
+
+    fn time(hours: u32, minutes: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(hours, minutes, 0).unwrap()
+    }
+
+    #[test]
+    fn reads_clock_times() {
+        assert_eq!(parse_clock_time("9"), Ok(time(9, 0)));
+        assert_eq!(parse_clock_time("9:30"), Ok(time(9, 30)));
+        assert_eq!(parse_clock_time("0930"), Ok(time(9, 30)));
+        assert_eq!(parse_clock_time("1330"), Ok(time(13, 30)));
+        assert_eq!(parse_clock_time("1:30pm"), Ok(time(13, 30)));
+        assert_eq!(parse_clock_time("12 am"), Ok(time(0, 0)));
+        assert_eq!(parse_clock_time("12p"), Ok(time(12, 0)));
+
+        assert!(parse_clock_time("").is_err());
+        assert!(parse_clock_time("25:00").is_err());
+        assert!(parse_clock_time("9:5").is_err());
+        assert!(parse_clock_time("13pm").is_err());
+    }
+
+    #[test]
+    fn ranges_land_on_the_day_in_the_users_zone() {
+        // a Wednesday
+        let now: DateTime<Utc> = "2024-03-06T15:00:00Z".parse().unwrap();
+        let range = clock_range(Day::Monday, "9:00", "10:30", Chicago, now).unwrap();
+
+        assert_eq!(
+            range.start,
+            "2024-03-04T15:00:00Z".parse::<DateTime<Utc>>().unwrap()
+        );
+        assert_eq!(range.millis(), 90 * 60_000);
+        assert!(clock_range(Day::Monday, "10:30", "9:00", Chicago, now).is_err());
+    }
+}
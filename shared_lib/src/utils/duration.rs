@@ -0,0 +1,75 @@
+/// Parses a duration like `1h30m`, `1h15`, `90m`, `1.5h`, `1:30` or `45` into milliseconds.
+/// A bare number is minutes, including one that follows the hours.
+pub fn parse_duration(input: &str) -> Result<i64, String> {
+    let input = input.trim().to_lowercase();
+    let invalid = || format!("invalid duration '{}'", input);
+
+    if let Some((hours, minutes)) = input.split_once(':') {
+        let hours: i64 = hours.parse().map_err(|_| invalid())?;
+        let minutes: i64 = minutes.parse().map_err(|_| invalid())?;
+        if minutes >= 60 {
+            return Err(invalid());
+        }
+        return Ok((hours * 60 + minutes) * 60_000);
+    }
+
+    let mut millis = 0.0;
+    let mut last_unit = None;
+    let mut chars = input.chars().filter(|c| !c.is_whitespace()).peekable();
+    while chars.peek().is_some() {
+        let number: String =
+            std::iter::from_fn(|| chars.next_if(|c| c.is_ascii_digit() || *c == '.')).collect();
+        let unit: String =
+            std::iter::from_fn(|| chars.next_if(|c| c.is_ascii_alphabetic())).collect();
+        let value: f64 = number.parse().map_err(|_| invalid())?;
+
+        let unit = match (unit.chars().next(), last_unit) {
+            (Some(unit), _) => unit,
+            // a fraction on its own is more likely hours than minutes, so make it say which
+            (None, _) if number.contains('.') => {
+                return Err(format!("'{}' needs a unit, like {}h", input, number))
+            }
+            (None, Some('h')) | (None, None) => 'm',
+            (None, Some(_)) => return Err(invalid()),
+        };
+        millis += value
+            * match unit {
+                'h' => 3_600_000.0,
+                'm' => 60_000.0,
+                's' => 1_000.0,
+                _ => return Err(invalid()),
+            };
+        last_unit = Some(unit);
+    }
+
+    match last_unit {
+        Some(_) => Ok(millis.round() as i64),
+        None => Err(invalid()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_hours_and_minutes() {
+        assert_eq!(parse_duration("1h30m"), Ok(90 * 60_000));
+        assert_eq!(parse_duration("1h15"), Ok(75 * 60_000));
+        assert_eq!(parse_duration("1.5h"), Ok(90 * 60_000));
+        assert_eq!(parse_duration("2:05"), Ok(125 * 60_000));
+        assert_eq!(parse_duration("45"), Ok(45 * 60_000));
+        assert_eq!(parse_duration("30s"), Ok(30_000));
+        assert_eq!(parse_duration("2hrs 10mins"), Ok(130 * 60_000));
+    }
+
+    #[test]
+    fn rejects_nonsense() {
+        assert!(parse_duration("").is_err());
+        assert!(parse_duration("soon").is_err());
+        assert!(parse_duration("1:75").is_err());
+        assert!(parse_duration("1x").is_err());
+        assert!(parse_duration("1.5").is_err());
+        assert!(parse_duration("1h7.5").is_err());
+    }
+}
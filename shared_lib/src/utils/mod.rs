@@ -0,0 +1,5 @@
+pub mod clock;
+pub mod duration;
+pub mod note_tags;
+pub mod quick_add;
+pub mod time_zone;
@@ -0,0 +1,80 @@
+// characters that can't be part of a tag or ticket, so they end one
+fn is_separator(c: char) -> bool {
+    !(c.is_alphanumeric() || c == '#' || c == '-' || c == '_')
+}
+
+fn as_tag(token: &str) -> Option<String> {
+    let name = token.strip_prefix('#')?.trim_end_matches('-');
+    let is_name = !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '-' || c == '_');
+    is_name.then(|| format!("#{}", name.to_lowercase()))
+}
+
+// a project key and a number, like ABC-123
+fn as_ticket(token: &str) -> Option<String> {
+    let (key, number) = token.split_once('-')?;
+    let is_key = key.starts_with(|c: char| c.is_ascii_uppercase())
+        && key
+            .chars()
+            .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit());
+    let is_number = !number.is_empty() && number.chars().all(|c| c.is_ascii_digit());
+    (is_key && is_number).then(|| token.to_string())
+}
+
+/// The `#tags` and ticket references like `ABC-123` in a note, each once.
+/// Tags are lowercased so `#Deploy` and `#deploy` are the same tag.
+pub fn parse_tags(note: &str) -> Vec<String> {
+    let mut tags: Vec<String> = Vec::new();
+    for token in note.split(is_separator) {
+        let Some(tag) = as_tag(token).or_else(|| as_ticket(token)) else {
+            continue;
+        };
+        if !tags.contains(&tag) {
+            tags.push(tag);
+        }
+    }
+    tags
+}
+
+/// Splits a search into the tags an entry has to have and the words its note has to contain.
+/// Tickets are matched whatever case they're typed in.
+pub fn split_query(query: &str) -> (Vec<String>, Vec<String>) {
+    let mut tags = Vec::new();
+    let mut words = Vec::new();
+    for token in query.split_whitespace() {
+        match as_tag(token).or_else(|| as_ticket(&token.to_uppercase())) {
+            Some(tag) if !tags.contains(&tag) => tags.push(tag),
+            Some(_) => {}
+            None => words.push(token.to_lowercase()),
+        }
+    }
+    (tags, words)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_tags_and_tickets() {
+        assert_eq!(
+            parse_tags("Paired on ABC-123 (#Review), then #deploy.\n#deploy again, see OPS2-7"),
+            vec!["ABC-123", "#review", "#deploy", "OPS2-7"]
+        );
+        // lowercase keys, bare hashes and dates aren't references
+        assert!(parse_tags("covid-19 # 2024-01-02 #").is_empty());
+    }
+
+    #[test]
+    fn queries_split_into_tags_and_words() {
+        assert_eq!(
+            split_query("#Deploy abc-42 Login bug"),
+            (
+                vec!["#deploy".to_string(), "ABC-42".to_string()],
+                vec!["login".to_string(), "bug".to_string()]
+            )
+        );
+    }
+}
@@ -0,0 +1,267 @@
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use chrono_tz::Tz;
+use fuzzy_matcher::{skim::SkimMatcherV2, FuzzyMatcher};
+
+use crate::{
+    models::{
+        charge_code::{ChargeCode, ChargeCodeVM},
+        day::Day,
+        quick_entry::{QuickEntry, QuickTime},
+    },
+    utils::{
+        clock::{clock_range, parse_clock_time},
+        duration::parse_duration,
+        time_zone::{local_date, week_start},
+    },
+};
+
+enum Token {
+    Word(String),
+    Quoted(String),
+}
+
+// the time as typed, a range can't be placed until the day is known
+enum TimeText {
+    Duration(i64),
+    Range(String, String),
+}
+
+// words split on whitespace, with anything in double quotes kept together
+fn tokenize(input: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut rest = input;
+    loop {
+        rest = rest.trim_start();
+        if rest.is_empty() {
+            return tokens;
+        }
+        match rest.strip_prefix('"') {
+            Some(quoted) => {
+                // an unclosed quote runs to the end
+                let (text, after) = quoted.split_once('"').unwrap_or((quoted, ""));
+                tokens.push(Token::Quoted(text.to_string()));
+                rest = after;
+            }
+            None => {
+                let end = rest
+                    .find(|c: char| c.is_whitespace() || c == '"')
+                    .unwrap_or(rest.len());
+                tokens.push(Token::Word(rest[..end].to_string()));
+                rest = &rest[end..];
+            }
+        }
+    }
+}
+
+fn day_on(date: NaiveDate) -> Result<Day, String> {
+    Day::from_date(date).ok_or_else(|| format!("{} isn't a workday", date.format("%A")))
+}
+
+fn as_day(word: &str, today: NaiveDate) -> Option<Result<Day, String>> {
+    match word.to_lowercase().as_str() {
+        "today" => Some(day_on(today)),
+        "yesterday" => {
+            let date = today - Duration::days(1);
+            Some(match date >= week_start(today) {
+                true => day_on(date),
+                false => Err("yesterday was last week".to_string()),
+            })
+        }
+        other => other.parse::<Day>().ok().map(Ok),
+    }
+}
+
+fn as_time(word: &str) -> Option<TimeText> {
+    if !word.contains(|c: char| c.is_ascii_digit()) {
+        return None;
+    }
+    if let Some((start, end)) = word.split_once('-') {
+        return (parse_clock_time(start).is_ok() && parse_clock_time(end).is_ok())
+            .then(|| TimeText::Range(start.to_string(), end.to_string()));
+    }
+    parse_duration(word).ok().map(TimeText::Duration)
+}
+
+/// The charge code an alias or code names exactly, or else the best fuzzy match on the alias
+pub fn match_charge_code<'a>(codes: &'a [ChargeCode], query: &str) -> Option<&'a ChargeCode> {
+    let lowercase = query.to_lowercase();
+    let exact = codes.iter().find(|code| {
+        code.alias.to_lowercase() == lowercase || code.code.to_lowercase() == lowercase
+    });
+    if exact.is_some() {
+        return exact;
+    }
+
+    let matcher = SkimMatcherV2::default();
+    codes
+        .iter()
+        .filter_map(|code| Some((matcher.fuzzy_match(&code.alias, query)?, code)))
+        // the shorter alias wins a tie, it's the closer match
+        .max_by(|(a_score, a), (b_score, b)| {
+            a_score.cmp(b_score).then(b.alias.len().cmp(&a.alias.len()))
+        })
+        .map(|(_, code)| code)
+}
+
+/// Parses a one line entry like `1h15 ops "patching servers" yesterday` or
+/// `10:00-11:30 design review`, in any order. The first duration or clock range is the
+/// time, a weekday, `today` or `yesterday` the day (today otherwise), and anything in
+/// quotes the note. Without quotes the most leading words that match a charge code are its
+/// alias and the rest are the note.
+pub fn parse_quick_entry(
+    input: &str,
+    charge_codes: &[ChargeCode],
+    tz: Tz,
+    now: DateTime<Utc>,
+) -> Result<QuickEntry, String> {
+    let today = local_date(now, tz);
+    let mut day = None;
+    let mut time = None;
+    let mut words: Vec<String> = Vec::new();
+    let mut quoted: Vec<String> = Vec::new();
+    for token in tokenize(input) {
+        match token {
+            Token::Quoted(text) => quoted.push(text),
+            Token::Word(word) => {
+                if day.is_none() {
+                    if let Some(parsed) = as_day(&word, today) {
+                        day = Some(parsed?);
+                        continue;
+                    }
+                }
+                if time.is_none() {
+                    if let Some(parsed) = as_time(&word) {
+                        time = Some(parsed);
+                        continue;
+                    }
+                }
+                words.push(word);
+            }
+        }
+    }
+    if day.is_none() && time.is_none() && words.is_empty() && quoted.is_empty() {
+        return Err("nothing to add".to_string());
+    }
+
+    let day = day.unwrap_or_else(|| Day::current_day_in(tz, now));
+    let time = match time {
+        Some(TimeText::Range(start, end)) => {
+            QuickTime::Range(clock_range(day, &start, &end, tz, now)?)
+        }
+        Some(TimeText::Duration(millis)) => QuickTime::Duration(millis),
+        None => QuickTime::Duration(0),
+    };
+
+    let (charge_code, note) = match quoted.is_empty() {
+        // with a quoted note every other word is the alias
+        false if words.is_empty() => (None, quoted.join(" ")),
+        false => {
+            let alias = words.join(" ");
+            let code = match_charge_code(charge_codes, &alias)
+                .ok_or_else(|| format!("no charge code matches '{}'", alias))?;
+            (Some(code), quoted.join(" "))
+        }
+        true => (1..=words.len())
+            .rev()
+            .find_map(|count| {
+                let code = match_charge_code(charge_codes, &words[..count].join(" "))?;
+                Some((Some(code), words[count..].join(" ")))
+            })
+            .unwrap_or_else(|| (None, words.join(" "))),
+    };
+
+    Ok(QuickEntry {
+        day,
+        charge_code: charge_code.map(|code| ChargeCodeVM {
+            id: code.id,
+            alias: code.alias.clone(),
+        }),
+        time,
+        note,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::time_segment::TimeRange;
+    use chrono_tz::America::Chicago;
+
+    fn codes() -> Vec<ChargeCode> {
+        ["Ops", "Design", "Design Review", "Forge Development"]
+            .iter()
+            .enumerate()
+            .map(|(index, alias)| ChargeCode {
+                id: index as i32 + 1,
+                alias: alias.to_string(),
+                code: format!("CODE.{}", index + 1),
+                ..Default::default()
+            })
+            .collect()
+    }
+
+    fn utc(s: &str) -> DateTime<Utc> {
+        s.parse().unwrap()
+    }
+
+    // a Wednesday morning in Chicago
+    fn parse(input: &str) -> Result<QuickEntry, String> {
+        parse_quick_entry(input, &codes(), Chicago, utc("2024-03-06T15:00:00Z"))
+    }
+
+    fn alias(entry: &QuickEntry) -> Option<&str> {
+        entry.charge_code.as_ref().map(|code| code.alias.as_str())
+    }
+
+    #[test]
+    fn reads_duration_code_note_and_day() {
+        let entry = parse(r#"1h15 ops "patching servers" yesterday"#).unwrap();
+        assert_eq!(entry.day, Day::Tuesday);
+        assert_eq!(entry.time, QuickTime::Duration(75 * 60_000));
+        assert_eq!(alias(&entry), Some("Ops"));
+        assert_eq!(entry.note, "patching servers");
+
+        let entry = parse("fri 45 forge dev fixed the build").unwrap();
+        assert_eq!(entry.day, Day::Friday);
+        assert_eq!(entry.millis(), 45 * 60_000);
+        assert_eq!(alias(&entry), Some("Forge Development"));
+        assert_eq!(entry.note, "fixed the build");
+    }
+
+    #[test]
+    fn reads_clock_ranges_on_the_day() {
+        let entry = parse("10:00-11:30 design review").unwrap();
+        assert_eq!(entry.day, Day::Wednesday);
+        assert_eq!(
+            entry.time,
+            QuickTime::Range(TimeRange {
+                start: utc("2024-03-06T16:00:00Z"),
+                end: utc("2024-03-06T17:30:00Z"),
+            })
+        );
+        assert_eq!(alias(&entry), Some("Design Review"));
+        assert_eq!(entry.note, "");
+
+        assert!(parse("11:30-10:00 design").is_err());
+    }
+
+    #[test]
+    fn unmatched_words_are_the_note() {
+        let entry = parse("30 lunch walk").unwrap();
+        assert_eq!(entry.charge_code, None);
+        assert_eq!(entry.note, "lunch walk");
+
+        let entry = parse(r#"design "sketches""#).unwrap();
+        assert_eq!(alias(&entry), Some("Design"));
+        assert_eq!(entry.time, QuickTime::Duration(0));
+    }
+
+    #[test]
+    fn rejects_what_cant_be_added() {
+        assert!(parse("").is_err());
+        assert!(parse(r#"1h xyzzy "notes""#).is_err());
+        // a Monday, so yesterday was last week
+        let monday = utc("2024-03-04T15:00:00Z");
+        assert!(parse_quick_entry("1h ops yesterday", &codes(), Chicago, monday).is_err());
+    }
+}
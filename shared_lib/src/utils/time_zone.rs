@@ -0,0 +1,91 @@
+use chrono::{DateTime, Datelike, Duration, LocalResult, NaiveDate, NaiveTime, TimeZone, Utc};
+use chrono_tz::Tz;
+
+/// The user's time zone, read from `RUSTIC_TIME_ZONE` (an IANA name like `America/Chicago`),
+/// then `TZ`, falling back to UTC. Day boundaries are decided in this zone.
+pub fn user_time_zone() -> Tz {
+    ["RUSTIC_TIME_ZONE", "TZ"]
+        .iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .find_map(|name| name.parse::<Tz>().ok())
+        .unwrap_or(Tz::UTC)
+}
+
+pub fn local_date(time: DateTime<Utc>, tz: Tz) -> NaiveDate {
+    time.with_timezone(&tz).date_naive()
+}
+
+/// The Monday of the week `date` falls in
+pub fn week_start(date: NaiveDate) -> NaiveDate {
+    date - Duration::days(date.weekday().num_days_from_monday() as i64)
+}
+
+/// The instant a local calendar day begins. When a DST change skips midnight,
+/// the day starts at the first local time that exists.
+pub fn start_of_local_day(date: NaiveDate, tz: Tz) -> DateTime<Utc> {
+    let mut local_time = date.and_time(NaiveTime::MIN);
+    loop {
+        match tz.from_local_datetime(&local_time) {
+            LocalResult::Single(time) | LocalResult::Ambiguous(time, _) => {
+                return time.with_timezone(&Utc)
+            }
+            LocalResult::None => local_time += Duration::minutes(30),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono_tz::America::{Chicago, Sao_Paulo};
+
+    fn utc(s: &str) -> DateTime<Utc> {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn local_date_follows_the_users_evening_not_utc() {
+        // 7pm CST on a Thursday is already Friday in UTC
+        let time = utc("2024-03-08T01:00:00Z");
+        assert_eq!(
+            local_date(time, Chicago),
+            NaiveDate::from_ymd_opt(2024, 3, 7).unwrap()
+        );
+    }
+
+    #[test]
+    fn local_date_across_fall_back() {
+        // both sides of the repeated 1am hour on 2024-11-03 are still the 3rd
+        let first_one_am = utc("2024-11-03T06:30:00Z");
+        let second_one_am = utc("2024-11-03T07:30:00Z");
+        let expected = NaiveDate::from_ymd_opt(2024, 11, 3).unwrap();
+
+        assert_eq!(local_date(first_one_am, Chicago), expected);
+        assert_eq!(local_date(second_one_am, Chicago), expected);
+    }
+
+    #[test]
+    fn start_of_day_uses_the_offset_in_effect_that_day() {
+        let before_spring_forward = NaiveDate::from_ymd_opt(2024, 3, 10).unwrap();
+        let after_spring_forward = NaiveDate::from_ymd_opt(2024, 3, 11).unwrap();
+
+        assert_eq!(
+            start_of_local_day(before_spring_forward, Chicago),
+            utc("2024-03-10T06:00:00Z")
+        );
+        assert_eq!(
+            start_of_local_day(after_spring_forward, Chicago),
+            utc("2024-03-11T05:00:00Z")
+        );
+    }
+
+    #[test]
+    fn start_of_day_when_midnight_is_skipped() {
+        // Brazil moved clocks from 00:00 to 01:00 on 2018-11-04
+        let date = NaiveDate::from_ymd_opt(2018, 11, 4).unwrap();
+        assert_eq!(
+            start_of_local_day(date, Sao_Paulo),
+            utc("2018-11-04T03:00:00Z")
+        );
+    }
+}
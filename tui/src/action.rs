@@ -1,9 +1,9 @@
 use serde::{Deserialize, Serialize};
-use shared_lib::models::day::Day;
-use strum::Display;
+use shared_lib::models::{day::Day, full_state::FullState};
+use strum::{Display, EnumIter, EnumString};
 
 use crate::{
-    api_client::{ApiRequest, ApiResponse},
+    api_client::{offline::ConnectionStatus, ApiRequest, ApiResponse},
     mode::Mode,
 };
 
@@ -25,6 +25,8 @@ pub enum UIAct {
     Refresh,
     Error(String),
     Help,
+    // suspends the tui while $EDITOR has the note
+    EditNoteExternally { id: i32, note: String },
 }
 
 #[derive(Clone, PartialEq, Eq, Serialize, Display, Deserialize, Debug)]
@@ -34,8 +36,58 @@ pub enum TTAct {
     EditChargeCode(i32),
     EditTime(EditTimeAction),
     SwapTime(i32),
+    // a search result, possibly on another day
+    JumpToEntry(Day, i32),
     UpdateMode(Mode),
-    SaveState,
+    Command(Command),
+    // a request that was held back until the user said yes
+    Confirmed(ApiRequest),
+}
+
+/// Everything a key can be bound to in the config, by name
+#[derive(
+    Clone, Copy, PartialEq, Eq, Hash, Serialize, Display, EnumString, EnumIter, Deserialize, Debug,
+)]
+pub enum Command {
+    AddEntry,
+    DeleteEntry,
+    ToggleTimer,
+    EditChargeCode,
+    EditTime,
+    SwapTime,
+    EditRange,
+    AddRange,
+    QuickAdd,
+    ToggleTimeline,
+    SplitEntry,
+    MergeEntries,
+    CopyDay,
+    MoveEntryUp,
+    MoveEntryDown,
+    TogglePin,
+    CopyStandup,
+    CopyStandupSlack,
+    SaveStandup,
+    EditNote,
+    EditNoteExternally,
+    NextEntry,
+    PreviousEntry,
+    NextDay,
+    PreviousDay,
+    Monday,
+    Tuesday,
+    Wednesday,
+    Thursday,
+    Friday,
+    CrudMode,
+    StandupMode,
+    WeekMode,
+    Undo,
+    Redo,
+    History,
+    Trash,
+    Search,
+    ErrorHistory,
 }
 
 #[derive(Clone, PartialEq, Eq, Serialize, Deserialize, Debug)]
@@ -49,6 +101,11 @@ pub enum ApiAct {
     Request(ApiRequest),
     Response(ApiResponse),
     Error(String),
+    Connection(ConnectionStatus),
+    // an offline change that couldn't be replayed
+    Conflict(String),
+    // the server answered a retry, with its state to check the queued changes against
+    ServerBack(Box<FullState>),
 }
 
 impl Action {
@@ -1,12 +1,20 @@
 pub mod models;
+pub mod offline;
 
 use crate::action::{Action, ApiAct, UIAct};
 use serde::{Deserialize, Serialize};
 use shared_lib::{
     api_client::ApiClient,
     models::{
+        charge_code::ChargeCode,
+        copy_day::{CopyDayRequest, EntryCopy},
         full_state::{DayTimeEntries, FullState},
+        quick_entry::{QuickEntry, QuickTime},
+        trash::TrashedEntry,
+        operation::Operation,
+        split::SplitRequest,
         time_entry::TimeEntryVM,
+        time_segment::{TimeRange, TimeSegment},
     },
 };
 use strum::Display;
@@ -43,16 +51,82 @@ pub enum ApiRequest {
     DeleteEntry {
         id: i32,
     },
+    // deleted entries wait in the trash on the server
+    GetTrash,
+    RestoreEntry {
+        id: i32,
+    },
     FullEntryUpdate {
         entry: TimeEntryVM
-    }
+    },
+    SetTimeRange {
+        id: i32,
+        range: TimeRange,
+    },
+    CreateTimeRange {
+        day: i16,
+        range: TimeRange,
+    },
+    CopyEntries {
+        day: i16,
+        request: CopyDayRequest,
+    },
+    QuickAdd {
+        entry: QuickEntry,
+    },
+    // what was tracked on the same weekday a week before `day`
+    GetLastWeeksEntries {
+        day: i16,
+    },
+    SplitEntry {
+        id: i32,
+        request: SplitRequest,
+    },
+    MergeEntries {
+        id: i32,
+    },
+    // -1 moves the entry up a place and 1 down
+    MoveEntry {
+        id: i32,
+        offset: i32,
+    },
+    // the history lives on the server, so these can't be queued offline
+    Undo,
+    Redo,
+    GetOperations,
+    Search {
+        query: String,
+    },
+    SetFavorite {
+        charge_code_id: i32,
+        is_favorite: bool,
+    },
+    SetPinned {
+        charge_code_id: i32,
+        is_pinned: bool,
+    },
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Display, Deserialize)]
 pub enum ApiResponse {
-    FullState(FullState),
+    FullState(Box<FullState>),
     DayEntriesUpdate(DayTimeEntries),
+    // the day after a split, with the new entry last
+    Split(DayTimeEntries),
     TimeEntryUpdate(TimeEntryVM),
+    // `None` when there was nothing to undo or redo
+    Undone(Option<Operation>),
+    Redone(Option<Operation>),
+    Operations(Vec<Operation>),
+    // the query comes back so results for an older one can be dropped
+    SearchResults {
+        query: String,
+        entries: Vec<TimeEntryVM>,
+    },
+    ChargeCodes(Vec<ChargeCode>),
+    Segments(Vec<TimeSegment>),
+    LastWeeksEntries(Vec<EntryCopy>),
+    Trash(Vec<TrashedEntry>),
 }
 
 #[derive(Serialize, Deserialize)]
@@ -60,6 +134,19 @@ struct NotePaylaod {
     note: String,
 }
 
+// anything that stops a timer or sets a range changes the timeline
+async fn send_segments(
+    client: &ApiClient,
+    action_tx: &UnboundedSender<Action>,
+) -> Result<(), reqwest::Error> {
+    let rcv = client.get_time_segments().await?;
+    let response = ApiResponse::Segments(rcv);
+    action_tx
+        .send(Action::api_response_action(response))
+        .unwrap();
+    Ok(())
+}
+
 pub trait ApiClientExt {
     // public async traits is a no no, normally
     // these should only be used in this crate, so ignore warnings
@@ -77,13 +164,14 @@ impl ApiClientExt for ApiClient {
     async fn process_api_action(&self, action: &ApiAct, action_tx: &UnboundedSender<Action>) {
         match self.process_api_action_inner(action, action_tx).await {
             Ok(_x) => {}
-            // if we have reqwest errors, swallow them but send an error action
-            Err(error) => action_tx
-                .send(Action::UI(UIAct::Error(format!(
-                    "Failed api request: {:?}",
-                    error
-                ))))
-                .unwrap(),
+            // if we have reqwest errors, send them back so the UI can undo what it assumed
+            Err(error) => {
+                let message = match action {
+                    ApiAct::Request(request) => format!("{} failed: {}", request, error),
+                    _ => format!("Failed api request: {}", error),
+                };
+                action_tx.send(Action::Api(ApiAct::Error(message))).unwrap()
+            }
         }
     }
 
@@ -96,7 +184,7 @@ impl ApiClientExt for ApiClient {
             ApiAct::Request(request) => match request {
                 ApiRequest::GetFullState => {
                     let rcv = self.get_full_state().await?;
-                    let response = ApiResponse::FullState(rcv);
+                    let response = ApiResponse::FullState(Box::new(rcv));
                     action_tx
                         .send(Action::api_response_action(response))
                         .unwrap();
@@ -137,7 +225,7 @@ impl ApiClientExt for ApiClient {
                     action_tx
                         .send(Action::api_response_action(response))
                         .unwrap();
-                    Ok(())
+                    send_segments(self, action_tx).await
                 }
                 ApiRequest::AddTime { id, millis } => {
                     let rcv = self.add_time_to_entry(*id, *millis).await?;
@@ -145,7 +233,7 @@ impl ApiClientExt for ApiClient {
                     action_tx
                         .send(Action::api_response_action(response))
                         .unwrap();
-                    Ok(())
+                    send_segments(self, action_tx).await
                 },
                 ApiRequest::PlayEntry { id } => {
                     let rcv = self.play_entry(*id).await?;
@@ -153,7 +241,7 @@ impl ApiClientExt for ApiClient {
                     action_tx
                         .send(Action::api_response_action(response))
                         .unwrap();
-                    Ok(())
+                    send_segments(self, action_tx).await
                 }
                 ApiRequest::PauseEntry { id } => {
                     let rcv = self.pause_entry(*id).await?;
@@ -161,16 +249,32 @@ impl ApiClientExt for ApiClient {
                     action_tx
                         .send(Action::api_response_action(response))
                         .unwrap();
-                    Ok(())
+                    send_segments(self, action_tx).await
                 }
                 ApiRequest::DeleteEntry { id } => {
                     let rcv = self.delete_entry(*id).await?;
                     let response = ApiResponse::DayEntriesUpdate(rcv);
+                    action_tx
+                        .send(Action::api_response_action(response))
+                        .unwrap();
+                    send_segments(self, action_tx).await
+                }
+                ApiRequest::GetTrash => {
+                    let rcv = self.get_trash().await?;
+                    let response = ApiResponse::Trash(rcv);
                     action_tx
                         .send(Action::api_response_action(response))
                         .unwrap();
                     Ok(())
                 }
+                ApiRequest::RestoreEntry { id } => {
+                    let rcv = self.restore_entry(*id).await?;
+                    let response = ApiResponse::DayEntriesUpdate(rcv);
+                    action_tx
+                        .send(Action::api_response_action(response))
+                        .unwrap();
+                    send_segments(self, action_tx).await
+                }
                 ApiRequest::FullEntryUpdate { entry } => {
                     let rcv = self.update_time_entry(entry.to_owned()).await?;
                     let response = ApiResponse::DayEntriesUpdate(rcv);
@@ -179,6 +283,134 @@ impl ApiClientExt for ApiClient {
                         .unwrap();
                     Ok(())
                 },
+                ApiRequest::SetTimeRange { id, range } => {
+                    let rcv = self.set_time_entry_range(*id, *range).await?;
+                    let response = ApiResponse::TimeEntryUpdate(rcv);
+                    action_tx
+                        .send(Action::api_response_action(response))
+                        .unwrap();
+                    send_segments(self, action_tx).await
+                }
+                ApiRequest::CreateTimeRange { day, range } => {
+                    let rcv = self.create_time_entry_with_range(*day, *range).await?;
+                    let response = ApiResponse::DayEntriesUpdate(rcv);
+                    action_tx
+                        .send(Action::api_response_action(response))
+                        .unwrap();
+                    send_segments(self, action_tx).await
+                }
+                ApiRequest::CopyEntries { day, request } => {
+                    let rcv = self.copy_entries(*day, request).await?;
+                    let response = ApiResponse::DayEntriesUpdate(rcv);
+                    action_tx
+                        .send(Action::api_response_action(response))
+                        .unwrap();
+                    Ok(())
+                }
+                ApiRequest::QuickAdd { entry } => {
+                    let rcv = self.create_quick_entry(entry).await?;
+                    let response = ApiResponse::DayEntriesUpdate(rcv);
+                    action_tx
+                        .send(Action::api_response_action(response))
+                        .unwrap();
+                    match entry.time {
+                        QuickTime::Range(_) => send_segments(self, action_tx).await,
+                        QuickTime::Duration(_) => Ok(()),
+                    }
+                }
+                ApiRequest::GetLastWeeksEntries { day } => {
+                    let rcv = self.get_last_weeks_entries(*day).await?;
+                    let response = ApiResponse::LastWeeksEntries(rcv);
+                    action_tx
+                        .send(Action::api_response_action(response))
+                        .unwrap();
+                    Ok(())
+                }
+                ApiRequest::SplitEntry { id, request } => {
+                    let rcv = self.split_entry(*id, request).await?;
+                    let response = ApiResponse::Split(rcv);
+                    action_tx
+                        .send(Action::api_response_action(response))
+                        .unwrap();
+                    Ok(())
+                }
+                ApiRequest::MergeEntries { id } => {
+                    let rcv = self.merge_entries(*id).await?;
+                    let response = ApiResponse::DayEntriesUpdate(rcv);
+                    action_tx
+                        .send(Action::api_response_action(response))
+                        .unwrap();
+                    send_segments(self, action_tx).await
+                }
+                ApiRequest::MoveEntry { id, offset } => {
+                    let rcv = self.move_entry(*id, *offset).await?;
+                    let response = ApiResponse::DayEntriesUpdate(rcv);
+                    action_tx
+                        .send(Action::api_response_action(response))
+                        .unwrap();
+                    Ok(())
+                }
+                ApiRequest::Undo | ApiRequest::Redo => {
+                    let response = match request {
+                        ApiRequest::Undo => ApiResponse::Undone(self.undo_operation().await?),
+                        _ => ApiResponse::Redone(self.redo_operation().await?),
+                    };
+                    action_tx
+                        .send(Action::api_response_action(response))
+                        .unwrap();
+                    // any entry could have changed
+                    let rcv = self.get_full_state().await?;
+                    let response = ApiResponse::FullState(Box::new(rcv));
+                    action_tx
+                        .send(Action::api_response_action(response))
+                        .unwrap();
+                    Ok(())
+                }
+                ApiRequest::SetFavorite {
+                    charge_code_id,
+                    is_favorite,
+                } => {
+                    let rcv = self
+                        .update_charge_code_favorite(*charge_code_id, *is_favorite)
+                        .await?;
+                    let response = ApiResponse::ChargeCodes(rcv);
+                    action_tx
+                        .send(Action::api_response_action(response))
+                        .unwrap();
+                    Ok(())
+                }
+                ApiRequest::SetPinned {
+                    charge_code_id,
+                    is_pinned,
+                } => {
+                    let rcv = self
+                        .update_charge_code_pinned(*charge_code_id, *is_pinned)
+                        .await?;
+                    let response = ApiResponse::ChargeCodes(rcv);
+                    action_tx
+                        .send(Action::api_response_action(response))
+                        .unwrap();
+                    Ok(())
+                }
+                ApiRequest::GetOperations => {
+                    let rcv = self.get_operations().await?;
+                    let response = ApiResponse::Operations(rcv);
+                    action_tx
+                        .send(Action::api_response_action(response))
+                        .unwrap();
+                    Ok(())
+                }
+                ApiRequest::Search { query } => {
+                    let rcv = self.search_entries(query).await?;
+                    let response = ApiResponse::SearchResults {
+                        query: query.clone(),
+                        entries: rcv,
+                    };
+                    action_tx
+                        .send(Action::api_response_action(response))
+                        .unwrap();
+                    Ok(())
+                }
             },
             ApiAct::Response(_response) => {
                 // intentionally left empty
@@ -188,13 +420,14 @@ impl ApiClientExt for ApiClient {
             }
             ApiAct::Error(error) => {
                 action_tx
-                    .send(Action::UI(UIAct::Error(format!(
-                        "Failed api request: {:?}",
-                        error
-                    ))))
+                    .send(Action::UI(UIAct::Error(error.clone())))
                     .unwrap();
                 Ok(())
             }
+            // these come from the offline queue and are only for the UI
+            ApiAct::Connection(_) | ApiAct::Conflict(_) => Ok(()),
+            // the offline queue replays its journal on this
+            ApiAct::ServerBack(_) => Ok(()),
         }
     }
 }
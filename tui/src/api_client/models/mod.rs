@@ -9,8 +9,24 @@ pub trait FullStateExt {
 
 impl FullStateExt for FullState {
     fn get_time_entries_for_day(&self, day: Day) -> Vec<TimeEntry> {
+        let pinned_ids: Vec<i32> = self
+            .charge_codes
+            .iter()
+            .filter(|code| code.is_pinned)
+            .map(|code| code.id)
+            .collect();
         match self.time_entries.get(&day) {
-            Some(entries) => entries.iter().map(|x| x.into()).collect(),
+            Some(entries) => entries
+                .iter()
+                .map(|x| {
+                    let mut entry: TimeEntry = x.into();
+                    entry.is_pinned = x
+                        .charge_code
+                        .as_ref()
+                        .is_some_and(|code| pinned_ids.contains(&code.id));
+                    entry
+                })
+                .collect(),
             None => Vec::default(),
         }
     }
@@ -0,0 +1,746 @@
+use std::{
+    collections::HashSet,
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use shared_lib::{
+    api_client::ApiClient,
+    models::{charge_code::ChargeCodeVM, day::Day, full_state::FullState, time_entry::TimeEntryVM},
+};
+use tokio::sync::mpsc::UnboundedSender;
+
+use super::{ApiClientExt, ApiRequest, ApiResponse};
+use crate::action::{Action, ApiAct, UIAct};
+
+const STATE_FILE: &str = "full_state.json";
+const JOURNAL_FILE: &str = "journal.json";
+const RETRY_INTERVAL: Duration = Duration::from_secs(15);
+// a dead network can hang a request for a long time, the check gives up sooner
+const RETRY_TIMEOUT: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ConnectionStatus {
+    Online,
+    Offline { queued: usize },
+    Syncing { queued: usize },
+}
+
+/// A write made while offline, waiting to be replayed
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QueuedRequest {
+    pub request: ApiRequest,
+    // the entry as the server last sent it, to spot changes made on the server in the meantime
+    pub base: Option<TimeEntryVM>,
+    // an entry created offline shows under this id until the server hands out a real one
+    #[serde(default)]
+    pub local_id: Option<i32>,
+}
+
+/// Keeps the last known state on disk and queues writes while the server can't be reached
+pub struct OfflineQueue {
+    data_dir: PathBuf,
+    state: Option<FullState>,
+    journal: Vec<QueuedRequest>,
+    is_offline: bool,
+    last_attempt: Option<Instant>,
+}
+
+impl OfflineQueue {
+    pub fn new(data_dir: PathBuf) -> Self {
+        let journal = std::fs::read_to_string(data_dir.join(JOURNAL_FILE))
+            .ok()
+            .and_then(|journal| serde_json::from_str(&journal).ok())
+            .unwrap_or_default();
+        let state = std::fs::read_to_string(data_dir.join(STATE_FILE))
+            .ok()
+            .and_then(|state| serde_json::from_str(&state).ok());
+
+        Self {
+            data_dir,
+            state,
+            journal,
+            is_offline: false,
+            last_attempt: None,
+        }
+    }
+
+    /// The state to start from when the server is down, with queued writes applied
+    pub fn cached_state(&self) -> Option<FullState> {
+        self.state.clone()
+    }
+
+    pub fn status(&self) -> ConnectionStatus {
+        match self.is_offline || !self.journal.is_empty() {
+            true => ConnectionStatus::Offline {
+                queued: self.journal.len(),
+            },
+            false => ConnectionStatus::Online,
+        }
+    }
+
+    /// Starts out offline, the next tick will try to sync
+    pub fn go_offline(&mut self) {
+        self.is_offline = true;
+        self.last_attempt = Some(Instant::now());
+    }
+
+    pub fn save_state(&mut self, state: &FullState) {
+        self.state = Some(state.clone());
+        self.write_file(STATE_FILE, state);
+    }
+
+    pub async fn process(
+        &mut self,
+        client: &ApiClient,
+        action: &ApiAct,
+        tx: &UnboundedSender<Action>,
+    ) {
+        match action {
+            ApiAct::Request(request) => self.process_request(client, request, tx).await,
+            ApiAct::Response(response) => {
+                if let Some(state) = self.state.as_mut() {
+                    apply_response(state, response.clone());
+                    let state = state.clone();
+                    self.save_state(&state);
+                }
+            }
+            ApiAct::ServerBack(server_state) => self.replay(client, server_state, tx).await,
+            _ => client.process_api_action(action, tx).await,
+        }
+    }
+
+    async fn process_request(
+        &mut self,
+        client: &ApiClient,
+        request: &ApiRequest,
+        tx: &UnboundedSender<Action>,
+    ) {
+        // keep writes in order, nothing jumps ahead of the queue
+        if self.is_offline || !self.journal.is_empty() {
+            self.handle_offline(request, tx);
+            return;
+        }
+
+        let action = ApiAct::Request(request.clone());
+        match client.process_api_action_inner(&action, tx).await {
+            Ok(()) => {}
+            Err(error) if is_connection_error(&error) => {
+                log::warn!("Server unreachable, working offline: {error:?}");
+                self.go_offline();
+                self.handle_offline(request, tx);
+            }
+            Err(error) => send(
+                tx,
+                Action::Api(ApiAct::Error(format!("{} failed: {}", request, error))),
+            ),
+        }
+    }
+
+    fn handle_offline(&mut self, request: &ApiRequest, tx: &UnboundedSender<Action>) {
+        if request.needs_server() {
+            send(
+                tx,
+                Action::Api(ApiAct::Error(format!("{} needs the server", request))),
+            );
+            return;
+        }
+
+        let Some(state) = self.state.as_mut() else {
+            send(
+                tx,
+                Action::UI(UIAct::Error("Offline with no saved state".to_string())),
+            );
+            return;
+        };
+
+        if *request != ApiRequest::GetFullState {
+            let queued = queue_request(state, &self.journal, request);
+            self.journal.extend(queued);
+            let journal = self.journal.clone();
+            self.write_file(JOURNAL_FILE, &journal);
+        }
+
+        if let Some(state) = self.state.clone() {
+            self.save_state(&state);
+            let response = ApiResponse::FullState(Box::new(state));
+            send(tx, Action::api_response_action(response));
+        }
+        send(tx, Action::Api(ApiAct::Connection(self.status())));
+    }
+
+    /// Called every tick, checks in the background whether the server is back so the ui
+    /// doesn't wait on a dead network. The journal is replayed once it answers.
+    pub fn retry(&mut self, client: &ApiClient, tx: &UnboundedSender<Action>) {
+        if self.status() == ConnectionStatus::Online {
+            return;
+        }
+        if self
+            .last_attempt
+            .is_some_and(|attempt| attempt.elapsed() < RETRY_INTERVAL)
+        {
+            return;
+        }
+        self.last_attempt = Some(Instant::now());
+
+        let client = client.clone();
+        let tx = tx.clone();
+        tokio::spawn(async move {
+            match tokio::time::timeout(RETRY_TIMEOUT, client.get_full_state()).await {
+                Ok(Ok(server_state)) => {
+                    send(&tx, Action::Api(ApiAct::ServerBack(Box::new(server_state))))
+                }
+                Ok(Err(error)) => log::debug!("Still offline: {error:?}"),
+                Err(_) => log::debug!("Still offline, timed out"),
+            }
+        });
+    }
+
+    async fn replay(
+        &mut self,
+        client: &ApiClient,
+        server_state: &FullState,
+        tx: &UnboundedSender<Action>,
+    ) {
+        if self.status() == ConnectionStatus::Online {
+            return;
+        }
+        send(
+            tx,
+            Action::Api(ApiAct::Connection(ConnectionStatus::Syncing {
+                queued: self.journal.len(),
+            })),
+        );
+        let mut replayed: HashSet<i32> = HashSet::new();
+        let mut conflicted: HashSet<i32> = HashSet::new();
+        while let Some(queued) = self.journal.first().cloned() {
+            let entry_id = queued.request.entry_id();
+            let already_conflicted = entry_id.is_some_and(|id| conflicted.contains(&id));
+            // once an entry has been replayed its server version is ours
+            let conflict = match entry_id {
+                Some(id) if !already_conflicted && !replayed.contains(&id) => {
+                    check_conflict(server_state, &queued)
+                }
+                _ => None,
+            };
+
+            if already_conflicted {
+                // already reported, the rest of the changes to it go too
+            } else if let Some(conflict) = conflict {
+                conflicted.extend(entry_id);
+                send(tx, Action::Api(ApiAct::Conflict(conflict)));
+            } else {
+                match replay_request(client, &queued.request, tx).await {
+                    Ok(created) => {
+                        // later changes to an entry made offline go to the one the server made
+                        if let (Some(local_id), Some(id)) = (queued.local_id, created) {
+                            for later in self.journal.iter_mut().skip(1) {
+                                later.request.replace_entry_id(local_id, id);
+                            }
+                        }
+                    }
+                    Err(error) if is_connection_error(&error) => {
+                        // lost it again, try the rest later
+                        send(tx, Action::Api(ApiAct::Connection(self.status())));
+                        return;
+                    }
+                    Err(error) => {
+                        // the changes to an entry that was never made go with it
+                        conflicted.extend(queued.local_id);
+                        send(
+                            tx,
+                            Action::Api(ApiAct::Conflict(format!(
+                                "Server rejected {}: {}",
+                                queued.request, error
+                            ))),
+                        )
+                    }
+                }
+                replayed.extend(entry_id);
+            }
+
+            self.journal.remove(0);
+            let journal = self.journal.clone();
+            self.write_file(JOURNAL_FILE, &journal);
+        }
+
+        self.is_offline = false;
+        send(tx, Action::api_request_action(ApiRequest::GetFullState));
+        send(tx, Action::Api(ApiAct::Connection(self.status())));
+    }
+
+    fn write_file<T: Serialize>(&self, file: &str, value: &T) {
+        let result = std::fs::create_dir_all(&self.data_dir)
+            .map_err(|e| e.to_string())
+            .and_then(|_| serde_json::to_string(value).map_err(|e| e.to_string()))
+            .and_then(|json| {
+                std::fs::write(self.data_dir.join(file), json).map_err(|e| e.to_string())
+            });
+        if let Err(error) = result {
+            log::error!("Failed to write {file}: {error}");
+        }
+    }
+}
+
+impl ApiRequest {
+    /// The time entry a request changes, if it changes one that already exists
+    pub fn entry_id(&self) -> Option<i32> {
+        match self {
+            ApiRequest::GetFullState
+            | ApiRequest::CreateTimeEntry { .. }
+            | ApiRequest::CreateTimeRange { .. }
+            | ApiRequest::CopyEntries { .. }
+            | ApiRequest::QuickAdd { .. }
+            | ApiRequest::GetLastWeeksEntries { .. }
+            | ApiRequest::GetTrash
+            | ApiRequest::RestoreEntry { .. }
+            | ApiRequest::Undo
+            | ApiRequest::Redo
+            | ApiRequest::GetOperations
+            | ApiRequest::Search { .. }
+            | ApiRequest::SetFavorite { .. }
+            | ApiRequest::SetPinned { .. } => None,
+            ApiRequest::UpdateChargeCode { time_entry_id, .. } => Some(*time_entry_id),
+            ApiRequest::SetTime { id, .. }
+            | ApiRequest::AddTime { id, .. }
+            | ApiRequest::UpdateEntryNote { id, .. }
+            | ApiRequest::PlayEntry { id }
+            | ApiRequest::PauseEntry { id }
+            | ApiRequest::DeleteEntry { id }
+            | ApiRequest::SetTimeRange { id, .. }
+            | ApiRequest::SplitEntry { id, .. }
+            | ApiRequest::MergeEntries { id }
+            | ApiRequest::MoveEntry { id, .. } => Some(*id),
+            ApiRequest::FullEntryUpdate { entry } => Some(entry.id),
+        }
+    }
+
+    /// Points a request at the entry the server created in place of one made offline
+    pub fn replace_entry_id(&mut self, from: i32, to: i32) {
+        let id = match self {
+            ApiRequest::UpdateChargeCode { time_entry_id, .. } => time_entry_id,
+            ApiRequest::SetTime { id, .. }
+            | ApiRequest::AddTime { id, .. }
+            | ApiRequest::UpdateEntryNote { id, .. }
+            | ApiRequest::PlayEntry { id }
+            | ApiRequest::PauseEntry { id }
+            | ApiRequest::DeleteEntry { id }
+            | ApiRequest::RestoreEntry { id }
+            | ApiRequest::SetTimeRange { id, .. }
+            | ApiRequest::SplitEntry { id, .. }
+            | ApiRequest::MergeEntries { id }
+            | ApiRequest::MoveEntry { id, .. } => id,
+            ApiRequest::FullEntryUpdate { entry } => &mut entry.id,
+            _ => return,
+        };
+        if *id == from {
+            *id = to;
+        }
+    }
+
+    /// Requests that can't be answered from the cached state or queued for later
+    pub fn needs_server(&self) -> bool {
+        matches!(
+            self,
+            ApiRequest::Undo
+                | ApiRequest::Redo
+                | ApiRequest::GetOperations
+                | ApiRequest::Search { .. }
+                | ApiRequest::SetFavorite { .. }
+                | ApiRequest::SetPinned { .. }
+                | ApiRequest::SplitEntry { .. }
+                | ApiRequest::MergeEntries { .. }
+                | ApiRequest::MoveEntry { .. }
+                | ApiRequest::CopyEntries { .. }
+                | ApiRequest::QuickAdd { .. }
+                | ApiRequest::GetLastWeeksEntries { .. }
+                | ApiRequest::GetTrash
+                | ApiRequest::RestoreEntry { .. }
+        )
+    }
+}
+
+fn is_connection_error(error: &reqwest::Error) -> bool {
+    error.is_connect() || error.is_timeout()
+}
+
+// sends a queued request, returning the id of the entry it created if it made one
+async fn replay_request(
+    client: &ApiClient,
+    request: &ApiRequest,
+    tx: &UnboundedSender<Action>,
+) -> Result<Option<i32>, reqwest::Error> {
+    let created = match request {
+        ApiRequest::CreateTimeEntry { day } => client.create_time_entry(*day).await?,
+        ApiRequest::CreateTimeRange { day, range } => {
+            client.create_time_entry_with_range(*day, *range).await?
+        }
+        _ => {
+            let action = ApiAct::Request(request.clone());
+            client.process_api_action_inner(&action, tx).await?;
+            return Ok(None);
+        }
+    };
+    let id = created.created_id;
+    send(
+        tx,
+        Action::api_response_action(ApiResponse::DayEntriesUpdate(created)),
+    );
+    Ok(id)
+}
+
+fn send(tx: &UnboundedSender<Action>, action: Action) {
+    if let Err(error) = tx.send(action) {
+        log::error!("Failed to send action: {error:?}");
+    }
+}
+
+fn find_entry(state: &FullState, id: i32) -> Option<&TimeEntryVM> {
+    state
+        .time_entries
+        .values()
+        .flatten()
+        .find(|entry| entry.id == id)
+}
+
+fn find_entry_mut(state: &mut FullState, id: i32) -> Option<&mut TimeEntryVM> {
+    state
+        .time_entries
+        .values_mut()
+        .flatten()
+        .find(|entry| entry.id == id)
+}
+
+/// Keeps the cached state in step with what the server sends back
+pub fn apply_response(state: &mut FullState, response: ApiResponse) {
+    match response {
+        ApiResponse::FullState(full_state) => *state = *full_state,
+        ApiResponse::DayEntriesUpdate(day_entries) | ApiResponse::Split(day_entries) => {
+            state
+                .time_entries
+                .insert(day_entries.day, day_entries.entries);
+        }
+        ApiResponse::TimeEntryUpdate(entry) => {
+            // the server marks a code as used when an entry gets it, the picker lists those first
+            if let Some(charge_code) = &entry.charge_code {
+                let is_new_code = find_entry(state, entry.id)
+                    .is_some_and(|existing_entry| existing_entry.charge_code != entry.charge_code);
+                if let Some(code) = state
+                    .charge_codes
+                    .iter_mut()
+                    .find(|code| is_new_code && code.id == charge_code.id)
+                {
+                    code.last_used_at = Some(Utc::now());
+                }
+            }
+            if let Some(existing_entry) = find_entry_mut(state, entry.id) {
+                *existing_entry = entry;
+            }
+        }
+        ApiResponse::ChargeCodes(charge_codes) => state.charge_codes = charge_codes,
+        ApiResponse::Segments(segments) => state.segments = segments,
+        // the full state follows these
+        ApiResponse::Undone(_) | ApiResponse::Redone(_) | ApiResponse::Operations(_) => {}
+        // only the popups and the copy modal use these
+        ApiResponse::SearchResults { .. }
+        | ApiResponse::LastWeeksEntries(_)
+        | ApiResponse::Trash(_) => {}
+    }
+}
+
+/// Applies a request to the cached state and returns what to put in the journal.
+/// Play and pause depend on when they happen, so they're queued as full updates
+/// carrying the times from now rather than from whenever they get replayed.
+fn queue_request(
+    state: &mut FullState,
+    journal: &[QueuedRequest],
+    request: &ApiRequest,
+) -> Vec<QueuedRequest> {
+    let base_for = |state: &FullState, id: i32| {
+        // an entry made offline has no server version to conflict with
+        if is_local_id(id) {
+            return None;
+        }
+        journal
+            .iter()
+            .find(|queued| queued.request.entry_id() == Some(id))
+            .map_or_else(
+                || find_entry(state, id).cloned(),
+                |queued| queued.base.clone(),
+            )
+    };
+
+    let now = Utc::now();
+    let mut changed: Vec<TimeEntryVM> = Vec::new();
+    match request {
+        ApiRequest::PlayEntry { id } => {
+            for entry in state.time_entries.values().flatten() {
+                if entry.id == *id && !entry.is_active {
+                    let mut entry = entry.clone();
+                    entry.is_active = true;
+                    entry.start_time = Some(now);
+                    changed.push(entry);
+                } else if entry.id != *id && entry.is_active {
+                    changed.push(paused(entry, now));
+                }
+            }
+        }
+        ApiRequest::PauseEntry { id } => {
+            if let Some(entry) = find_entry(state, *id).filter(|entry| entry.is_active) {
+                changed.push(paused(entry, now));
+            }
+        }
+        ApiRequest::CreateTimeEntry { day } => {
+            return vec![QueuedRequest {
+                request: request.clone(),
+                base: None,
+                local_id: Some(add_placeholder(state, *day, 0)),
+            }];
+        }
+        ApiRequest::CreateTimeRange { day, range } => {
+            return vec![QueuedRequest {
+                request: request.clone(),
+                base: None,
+                local_id: Some(add_placeholder(state, *day, range.millis())),
+            }];
+        }
+        _ => {
+            let base = request.entry_id().and_then(|id| base_for(state, id));
+            apply_locally(state, request);
+            return vec![QueuedRequest {
+                request: request.clone(),
+                base,
+                local_id: None,
+            }];
+        }
+    }
+
+    changed
+        .into_iter()
+        .map(|entry| {
+            let base = base_for(state, entry.id);
+            let request = ApiRequest::FullEntryUpdate { entry };
+            apply_locally(state, &request);
+            QueuedRequest {
+                request,
+                base,
+                local_id: None,
+            }
+        })
+        .collect()
+}
+
+fn is_local_id(id: i32) -> bool {
+    id < 0
+}
+
+// an entry made offline, under an id below any the server hands out
+fn add_placeholder(state: &mut FullState, day: i16, total_time: i64) -> i32 {
+    let id = state
+        .time_entries
+        .values()
+        .flatten()
+        .map(|entry| entry.id)
+        .filter(|id| is_local_id(*id))
+        .min()
+        .unwrap_or(0)
+        - 1;
+    let day = Day::from(day);
+    state
+        .time_entries
+        .entry(day)
+        .or_default()
+        .push(TimeEntryVM {
+            id,
+            start_time: None,
+            total_time,
+            note: String::new(),
+            day,
+            is_active: false,
+            charge_code: None,
+        });
+    id
+}
+
+fn paused(entry: &TimeEntryVM, now: chrono::DateTime<Utc>) -> TimeEntryVM {
+    let mut entry = entry.clone();
+    if let Some(start_time) = entry.start_time.take() {
+        entry.total_time += (now - start_time).num_milliseconds();
+    }
+    entry.is_active = false;
+    entry
+}
+
+// what the server would do, minus anything that needs an id it hasn't handed out yet
+fn apply_locally(state: &mut FullState, request: &ApiRequest) {
+    let charge_code = match request {
+        ApiRequest::UpdateChargeCode { charge_code_id, .. } => state
+            .charge_codes
+            .iter()
+            .find(|code| code.id == *charge_code_id)
+            .map(|code| ChargeCodeVM {
+                id: code.id,
+                alias: code.alias.clone(),
+            }),
+        _ => None,
+    };
+
+    match request {
+        ApiRequest::DeleteEntry { id } => {
+            for entries in state.time_entries.values_mut() {
+                entries.retain(|entry| entry.id != *id);
+            }
+        }
+        ApiRequest::FullEntryUpdate { entry } => {
+            if let Some(existing_entry) = find_entry_mut(state, entry.id) {
+                *existing_entry = entry.clone();
+            }
+        }
+        _ => {
+            let Some(entry) = request.entry_id().and_then(|id| find_entry_mut(state, id)) else {
+                return;
+            };
+            match request {
+                ApiRequest::UpdateChargeCode { .. } => entry.charge_code = charge_code,
+                ApiRequest::SetTime { millis, .. } => entry.total_time = *millis,
+                ApiRequest::AddTime { millis, .. } => entry.total_time += *millis,
+                ApiRequest::UpdateEntryNote { note, .. } => entry.note = note.clone(),
+                // the segment itself shows up once the server has it
+                ApiRequest::SetTimeRange { range, .. } => {
+                    entry.total_time = range.millis();
+                    entry.start_time = None;
+                    entry.is_active = false;
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+// the server wins when it changed an entry we also changed offline
+fn check_conflict(server_state: &FullState, queued: &QueuedRequest) -> Option<String> {
+    let base = queued.base.as_ref()?;
+    let alias = base
+        .charge_code
+        .as_ref()
+        .map_or("entry".to_string(), |code| code.alias.clone());
+
+    match find_entry(server_state, base.id) {
+        None => Some(format!(
+            "{} was deleted on the server, dropped offline change: {}",
+            alias, queued.request
+        )),
+        Some(server_entry) if server_entry != base => Some(format!(
+            "{} changed on the server, kept its version over offline change: {}",
+            alias, queued.request
+        )),
+        Some(_) => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration as ChronoDuration;
+    use shared_lib::models::day::Day;
+    use std::collections::HashMap;
+
+    fn entry(id: i32, is_active: bool) -> TimeEntryVM {
+        TimeEntryVM {
+            id,
+            start_time: is_active.then(|| Utc::now() - ChronoDuration::minutes(30)),
+            total_time: 60_000,
+            day: Day::Monday,
+            is_active,
+            ..Default::default()
+        }
+    }
+
+    fn state(entries: Vec<TimeEntryVM>) -> FullState {
+        FullState {
+            time_entries: HashMap::from([(Day::Monday, entries)]),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn play_is_queued_as_full_updates_with_local_times() {
+        let mut state = state(vec![entry(1, true), entry(2, false)]);
+        let server_state = state.clone();
+
+        let queued = queue_request(&mut state, &[], &ApiRequest::PlayEntry { id: 2 });
+
+        assert_eq!(queued.len(), 2);
+        assert!(queued
+            .iter()
+            .all(|q| matches!(q.request, ApiRequest::FullEntryUpdate { .. })));
+        let first = find_entry(&state, 1).unwrap();
+        assert!(!first.is_active);
+        assert!(first.total_time >= 60_000 + 30 * 60_000);
+        assert!(find_entry(&state, 2).unwrap().is_active);
+        // nothing changed on the server, so nothing conflicts
+        assert!(queued
+            .iter()
+            .all(|q| check_conflict(&server_state, q).is_none()));
+    }
+
+    #[test]
+    fn later_changes_keep_the_servers_version_as_base() {
+        let mut state = state(vec![entry(1, false)]);
+        let mut journal = Vec::new();
+
+        for note in ["first", "second"] {
+            let request = ApiRequest::UpdateEntryNote {
+                id: 1,
+                note: note.to_string(),
+            };
+            journal.extend(queue_request(&mut state, &journal, &request));
+        }
+
+        assert_eq!(find_entry(&state, 1).unwrap().note, "second");
+        assert_eq!(journal[1].base, Some(entry(1, false)));
+    }
+
+    #[test]
+    fn offline_creates_show_until_the_server_has_them() {
+        let mut state = state(vec![entry(1, false)]);
+        let mut journal = Vec::new();
+
+        let create = ApiRequest::CreateTimeEntry {
+            day: Day::Monday.into(),
+        };
+        journal.extend(queue_request(&mut state, &journal, &create));
+        let local_id = journal[0].local_id.unwrap();
+        let note = ApiRequest::UpdateEntryNote {
+            id: local_id,
+            note: "offline".to_string(),
+        };
+        journal.extend(queue_request(&mut state, &journal, &note));
+
+        assert!(local_id < 0);
+        assert_eq!(find_entry(&state, local_id).unwrap().note, "offline");
+        // there's nothing on the server for it to conflict with
+        assert_eq!(journal[1].base, None);
+
+        journal[1].request.replace_entry_id(local_id, 7);
+        assert_eq!(journal[1].request.entry_id(), Some(7));
+    }
+
+    #[test]
+    fn conflicts_when_the_server_changed_or_deleted_the_entry() {
+        let queued = QueuedRequest {
+            request: ApiRequest::AddTime { id: 1, millis: 1 },
+            base: Some(entry(1, false)),
+            local_id: None,
+        };
+
+        let mut changed = entry(1, false);
+        changed.note = "edited elsewhere".to_string();
+
+        assert!(check_conflict(&state(vec![changed]), &queued).is_some());
+        assert!(check_conflict(&state(vec![]), &queued).is_some());
+        assert!(check_conflict(&state(vec![entry(1, false)]), &queued).is_none());
+    }
+}
@@ -1,19 +1,23 @@
 use color_eyre::eyre::Result;
-use crossterm::event::KeyEvent;
+use crossterm::event::{KeyEvent, KeyModifiers};
 use ratatui::prelude::Rect;
 use shared_lib::api_client::ApiClient;
 use tokio::sync::mpsc::{self, UnboundedSender};
 
 use crate::{
     action::{
-        Action,
+        Action, TTAct,
         UIAct::{self, *},
     },
-    api_client::ApiClientExt,
+    api_client::{offline::OfflineQueue, ApiRequest},
     components::{home::Home, Component},
     config::Config,
     mode::Mode,
-    tui,
+    shared::{
+        editor::{edit_in_editor, edited_note},
+        utils::get_data_dir,
+    },
+    theme, tui,
 };
 
 pub struct App {
@@ -26,13 +30,33 @@ pub struct App {
     pub mode: Mode,
     pub last_tick_key_events: Vec<KeyEvent>,
     pub api_client: ApiClient,
+    pub offline_queue: OfflineQueue,
+    // the entry and note to hand to $EDITOR on the next suspend
+    pub external_edit: Option<(i32, String)>,
 }
 
 impl App {
     pub async fn new(tick_rate: f64, frame_rate: f64, api_client: &ApiClient) -> Result<Self> {
-        let starting_state = api_client.get_full_state().await?;
+        let mut offline_queue = OfflineQueue::new(get_data_dir());
+        // start from the last saved state if the server can't be reached
+        let starting_state = match api_client.get_full_state().await {
+            Ok(state) => {
+                offline_queue.save_state(&state);
+                state
+            }
+            Err(error) => match offline_queue.cached_state() {
+                Some(state) => {
+                    log::warn!("Starting offline: {error:?}");
+                    offline_queue.go_offline();
+                    state
+                }
+                None => return Err(error.into()),
+            },
+        };
         let config = Config::new()?;
-        let home = Home::new(starting_state, &config);
+        theme::init(&config);
+        let mut home = Home::new(starting_state, &config);
+        home.set_connection(offline_queue.status());
         let mode = Mode::Crud;
         Ok(Self {
             tick_rate,
@@ -44,6 +68,8 @@ impl App {
             mode,
             last_tick_key_events: Vec::new(),
             api_client: api_client.clone(),
+            offline_queue,
+            external_edit: None,
         })
     }
 
@@ -52,8 +78,8 @@ impl App {
 
         let mut tui = tui::Tui::new()?
             .tick_rate(self.tick_rate)
-            .frame_rate(self.frame_rate);
-        // tui.mouse(true);
+            .frame_rate(self.frame_rate)
+            .mouse(true);
         tui.enter()?;
 
         for component in self.components.iter_mut() {
@@ -76,11 +102,14 @@ impl App {
                     tui::Event::Render => action_tx.send(Action::UI(Render))?,
                     tui::Event::Resize(x, y) => action_tx.send(Action::UI(Resize(x, y)))?,
                     tui::Event::Key(key) => {
+                        let capturing = self.components.iter().any(|c| c.is_capturing_input());
                         if let Some(keymap) = self.config.keybindings.get(&self.mode) {
                             if let Some(action) = keymap.get(&vec![key]) {
-                                log::info!("Got action: {action:?}");
-                                action_tx.send(action.clone())?;
-                            } else {
+                                if !capturing || is_escape_binding(&key, action) {
+                                    log::info!("Got action: {action:?}");
+                                    action_tx.send(action.clone())?;
+                                }
+                            } else if !capturing {
                                 // If the key was not handled as a single key action,
                                 // then consider it for multi-key combinations.
                                 self.last_tick_key_events.push(key);
@@ -107,13 +136,18 @@ impl App {
                     log::debug!("{action:?}");
                 }
                 match &action {
+                    Action::UI(Tick) => {
+                        self.process_ui_action(&Tick, &mut tui, &action_tx)?;
+                        self.offline_queue.retry(&self.api_client, &action_tx);
+                    }
                     Action::UI(ui_act) => self.process_ui_action(ui_act, &mut tui, &action_tx)?,
+                    Action::TT(TTAct::UpdateMode(mode)) => self.mode = *mode,
                     Action::TT(_tt_act) => {
-                        // processing TT actions, should not happen in this loop
+                        // the rest of the TT actions are handled by the components
                     }
                     Action::Api(api_act) => {
-                        self.api_client
-                            .process_api_action(api_act, &action_tx)
+                        self.offline_queue
+                            .process(&self.api_client, api_act, &action_tx)
                             .await
                     }
                 }
@@ -124,12 +158,19 @@ impl App {
                 }
             }
             if self.should_suspend {
-                tui.suspend()?;
+                match self.external_edit.take() {
+                    // the editor gets the terminal instead of the shell
+                    Some((id, note)) => {
+                        tui.exit()?;
+                        self.edit_note_externally(id, note, &action_tx).await?;
+                    }
+                    None => tui.suspend()?,
+                }
                 action_tx.send(Action::UI(Resume))?;
                 tui = tui::Tui::new()?
                     .tick_rate(self.tick_rate)
-                    .frame_rate(self.frame_rate);
-                // tui.mouse(true);
+                    .frame_rate(self.frame_rate)
+                    .mouse(true);
                 tui.enter()?;
             } else if self.should_quit {
                 tui.stop()?;
@@ -140,6 +181,26 @@ impl App {
         Ok(())
     }
 
+    async fn edit_note_externally(
+        &self,
+        id: i32,
+        note: String,
+        action_tx: &UnboundedSender<Action>,
+    ) -> Result<()> {
+        match edit_in_editor(id, &note).await {
+            Ok(edited) => {
+                if let Some(edited) = edited_note(&note, &edited, &self.config.external_editor) {
+                    let request = ApiRequest::UpdateEntryNote { id, note: edited };
+                    action_tx.send(Action::api_request_action(request))?;
+                }
+            }
+            Err(error) => action_tx.send(Action::UI(Error(format!(
+                "Couldn't edit the note: {error}"
+            ))))?,
+        }
+        Ok(())
+    }
+
     fn process_ui_action(
         &mut self,
         action: &UIAct,
@@ -152,6 +213,10 @@ impl App {
             }
             UIAct::Quit => self.should_quit = true,
             UIAct::Suspend => self.should_suspend = true,
+            UIAct::EditNoteExternally { id, note } => {
+                self.external_edit = Some((*id, note.clone()));
+                self.should_suspend = true;
+            }
             UIAct::Resume => self.should_suspend = false,
             UIAct::Resize(w, h) => {
                 tui.resize(Rect::new(0, 0, *w, *h))?;
@@ -183,3 +248,12 @@ impl App {
         Ok(())
     }
 }
+
+// while a component is capturing input only ui actions on modified keys get through,
+// so ctrl-c still quits from a modal but typing 'q' into it doesn't
+fn is_escape_binding(key: &KeyEvent, action: &Action) -> bool {
+    matches!(action, Action::UI(_))
+        && key
+            .modifiers
+            .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT)
+}
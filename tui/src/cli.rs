@@ -1,5 +1,6 @@
 use crate::shared::utils::version;
-use clap::Parser;
+use clap::{Parser, Subcommand};
+use shared_lib::models::day::Day;
 
 #[derive(Parser, Debug)]
 #[command(author, version = version(), about)]
@@ -21,4 +22,78 @@ pub struct Cli {
         default_value_t = 60.0
     )]
     pub frame_rate: f64,
+
+    #[arg(long, global = true, help = "Print subcommand output as JSON")]
+    pub json: bool,
+
+    /// Run a single command instead of opening the interface
+    #[command(subcommand)]
+    pub command: Option<Commands>,
+}
+
+#[derive(Subcommand, Debug, Clone, PartialEq)]
+pub enum Commands {
+    /// Start the timer for a charge code today, creating the entry if needed
+    Start {
+        alias: String,
+        /// Added to the entry's note
+        #[arg(trailing_var_arg = true)]
+        note: Vec<String>,
+    },
+    /// Stop any running timer
+    Stop,
+    /// Show the running timer and today's total
+    Status {
+        #[arg(
+            long,
+            value_name = "TEMPLATE",
+            help = "Print a template like '{alias} {elapsed}' instead, cached for a few seconds \
+                    so prompts and status bars can call it every second. Also has {note}, \
+                    {today} and {id}"
+        )]
+        format: Option<String>,
+        #[arg(
+            long,
+            value_name = "TEMPLATE",
+            requires = "format",
+            help = "Template for when no timer is running, prints nothing by default"
+        )]
+        idle_format: Option<String>,
+    },
+    /// Add time to a charge code, like `add ops 1h30m --day tue`
+    Add {
+        alias: String,
+        duration: String,
+        #[arg(
+            long,
+            value_name = "DAY",
+            help = "Weekday like tue or friday, defaults to today"
+        )]
+        day: Option<Day>,
+    },
+    /// Add an entry from one line, like `quick 1h15 ops "patching servers" yesterday` or
+    /// `quick 10:00-11:30 design review`
+    Quick {
+        #[arg(trailing_var_arg = true, required = true)]
+        text: Vec<String>,
+    },
+    /// Set the note on an entry
+    Note {
+        id: i32,
+        #[arg(trailing_var_arg = true, required = true)]
+        text: Vec<String>,
+    },
+    /// List a day's entries
+    Ls {
+        #[arg(
+            long,
+            value_name = "DAY",
+            help = "Weekday like tue or friday, defaults to today"
+        )]
+        day: Option<Day>,
+    },
+    /// Hours by charge code and day for the week
+    Report,
+    /// The week's entries as Costpoint lines
+    Costpoint,
 }
@@ -1,18 +1,20 @@
+use crossterm::event::{KeyEvent, MouseButton, MouseEvent, MouseEventKind};
 use ratatui::{
     layout::Rect,
-    style::{Color, Style},
     text::{Line, Span},
     widgets::{Block, Borders, Paragraph},
     Frame,
 };
 
+use crate::{
+    config::parse_key_event,
+    theme::{self, StyleName},
+};
+
 pub fn draw_tooltip_bar(f: &mut Frame<'_>, area: Rect, tooltips: &[&str]) {
     let mut spans = Vec::new();
     for (i, tooltip) in tooltips.iter().enumerate() {
-        spans.push(Span::styled(
-            *tooltip,
-            Style::default().fg(Color::Black).bg(Color::Blue),
-        ));
+        spans.push(Span::styled(*tooltip, theme::style(StyleName::Tooltip)));
         // Don't add a separator after the last tooltip
         if i < tooltips.len() - 1 {
             spans.push(Span::raw(" "));
@@ -24,3 +26,100 @@ pub fn draw_tooltip_bar(f: &mut Frame<'_>, area: Rect, tooltips: &[&str]) {
     let paragraph = Paragraph::new(vec![line]).block(Block::default().borders(Borders::ALL));
     f.render_widget(paragraph, area);
 }
+
+/// A tooltip bar that remembers where it was drawn, so a click on one of its items can run it
+#[derive(Debug, Clone, Default)]
+pub struct TooltipBar {
+    area: Rect,
+    tooltips: Vec<String>,
+}
+
+impl TooltipBar {
+    pub fn draw(&mut self, f: &mut Frame<'_>, area: Rect, tooltips: &[&str]) {
+        self.area = area;
+        self.tooltips = tooltips.iter().map(|tooltip| tooltip.to_string()).collect();
+        draw_tooltip_bar(f, area, tooltips);
+    }
+
+    /// The index of the tooltip under a left click
+    pub fn clicked(&self, mouse: MouseEvent) -> Option<usize> {
+        if !is_left_click(mouse) || mouse.row != self.area.y + 1 {
+            return None;
+        }
+        // past the left border, one space between items
+        let mut x = self.area.x + 1;
+        let right = self.area.right().saturating_sub(1);
+        for (i, tooltip) in self.tooltips.iter().enumerate() {
+            let width = Span::raw(tooltip.as_str()).width() as u16;
+            if mouse.column >= x && mouse.column < (x + width).min(right) {
+                return Some(i);
+            }
+            x += width + 1;
+        }
+        None
+    }
+
+    /// The key in the clicked tooltip's brackets, the first one for something like `[Tab/Backtab]`
+    pub fn clicked_key(&self, mouse: MouseEvent) -> Option<KeyEvent> {
+        let tooltip = &self.tooltips[self.clicked(mouse)?];
+        let (_, key) = tooltip.rsplit_once('[')?;
+        let key = key.strip_suffix(']')?.split('/').next()?;
+        parse_key_event(key).ok()
+    }
+}
+
+pub fn is_left_click(mouse: MouseEvent) -> bool {
+    mouse.kind == MouseEventKind::Down(MouseButton::Left)
+}
+
+pub fn is_within(area: Rect, mouse: MouseEvent) -> bool {
+    mouse.column >= area.x
+        && mouse.column < area.right()
+        && mouse.row >= area.y
+        && mouse.row < area.bottom()
+}
+
+/// Where each span of a single line lands when it's drawn from `x` on row `y`
+pub fn span_areas(line: &Line, x: u16, y: u16) -> Vec<Rect> {
+    let mut x = x;
+    line.spans
+        .iter()
+        .map(|span| {
+            let width = span.width() as u16;
+            let area = Rect::new(x, y, width, 1);
+            x += width;
+            area
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crossterm::event::{KeyCode, KeyModifiers};
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn click(column: u16, row: u16) -> MouseEvent {
+        MouseEvent {
+            kind: MouseEventKind::Down(MouseButton::Left),
+            column,
+            row,
+            modifiers: KeyModifiers::NONE,
+        }
+    }
+
+    #[test]
+    fn clicks_land_on_tooltips() {
+        let bar = TooltipBar {
+            area: Rect::new(0, 10, 40, 3),
+            tooltips: vec!["Save [Enter]".to_string(), "Move [Up/Down]".to_string()],
+        };
+        assert_eq!(bar.clicked(click(1, 11)), Some(0));
+        assert_eq!(bar.clicked(click(13, 11)), None);
+        assert_eq!(bar.clicked(click(14, 11)), Some(1));
+        assert_eq!(bar.clicked(click(14, 10)), None);
+        assert_eq!(bar.clicked_key(click(5, 11)), Some(KeyCode::Enter.into()));
+        assert_eq!(bar.clicked_key(click(20, 11)), Some(KeyCode::Up.into()));
+    }
+}
@@ -1,34 +1,67 @@
 use super::{
-    component_utils::draw_tooltip_bar,
+    component_utils::TooltipBar,
     modals::{
-        charge_code_picker::ChargeCodePickerModal, swap_time_modal::layout::SwapTimeModal,
-        time_edit_modal::TimeEditModal,
+        charge_code_picker::ChargeCodePickerModal, confirm_modal::ConfirmModal,
+        copy_day_modal::CopyDayModal, quick_add_modal::QuickAddModal, split_modal::SplitModal,
+        swap_time_modal::layout::SwapTimeModal, time_edit_modal::TimeEditModal,
+        time_range_modal::TimeRangeModal,
     },
     notes::notes_wrapper::NotesWrapper,
-    standup::standup_container::StandupContainer,
-    time_entry::{entry::TimeEntry, time_entry_container::TimeEntryContainer},
+    operation_history::OperationHistory,
+    search_popup::SearchPopup,
+    standup::standup_container::{StandupContainer, StandupFormat},
+    status_bar::StatusBar,
+    time_entry::{
+        entry::TimeEntry, time_entry_container::TimeEntryContainer,
+        time_utils::format_hours_minutes, timeline::Timeline,
+    },
     top_bar::layout::TopBar,
+    trash_popup::TrashPopup,
+    week::week_container::WeekContainer,
     Component, Frame,
 };
 use crate::{
     action::{
-        Action, ApiAct, TTAct,
+        Action, ApiAct, Command, TTAct,
         UIAct::{self, *},
     },
-    action_history::ActionHistory,
-    api_client::{models::FullStateExt, ApiRequest, ApiResponse},
+    api_client::{
+        models::FullStateExt,
+        offline::{apply_response, ConnectionStatus},
+        ApiRequest, ApiResponse,
+    },
     config::Config,
     mode::Mode,
+    shared::{clipboard::copy_to_clipboard, utils::get_data_dir},
 };
 use color_eyre::eyre::Result;
-use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use crossterm::event::{KeyEvent, MouseEvent};
 use ratatui::prelude::*;
 use shared_lib::models::{
-    day::Day,
-    full_state::{FullState, TimeEntriesDiff},
+    copy_day::EntryCopy, day::Day, full_state::FullState, time_entry::TimeEntryVM,
+    time_segment::TimeRange,
 };
+use std::collections::HashMap;
+use strum::IntoEnumIterator;
 use tokio::sync::mpsc::UnboundedSender;
 
+// what the tooltip bar advertises in crud mode, in order
+const CRUD_TOOLTIPS: [(&str, Action); 10] = [
+    ("Quit", Action::UI(Quit)),
+    ("Add", Action::TT(TTAct::Command(Command::AddEntry))),
+    ("Delete", Action::TT(TTAct::Command(Command::DeleteEntry))),
+    (
+        "Play/Pause",
+        Action::TT(TTAct::Command(Command::ToggleTimer)),
+    ),
+    ("Code", Action::TT(TTAct::Command(Command::EditChargeCode))),
+    ("Time", Action::TT(TTAct::Command(Command::EditTime))),
+    ("Swap", Action::TT(TTAct::Command(Command::SwapTime))),
+    ("Range", Action::TT(TTAct::Command(Command::EditRange))),
+    ("Undo", Action::TT(TTAct::Command(Command::Undo))),
+    ("Redo", Action::TT(TTAct::Command(Command::Redo))),
+];
+
 pub struct Home<'a> {
     command_tx: Option<UnboundedSender<Action>>,
     config: Config,
@@ -39,23 +72,98 @@ pub struct Home<'a> {
     charge_code_modal: ChargeCodePickerModal,
     time_edit_modal: TimeEditModal,
     swap_time_modal: SwapTimeModal,
+    time_range_modal: TimeRangeModal,
+    split_modal: SplitModal,
+    copy_day_modal: CopyDayModal,
+    quick_add_modal: QuickAddModal,
+    confirm_modal: ConfirmModal,
+    timeline: Timeline,
     standup_container: StandupContainer,
+    week_container: WeekContainer,
+    status_bar: StatusBar,
+    operation_history: OperationHistory,
+    search_popup: SearchPopup,
+    trash_popup: TrashPopup,
+    tooltip_bar: TooltipBar,
+    // what each item of the tooltip bar runs, for clicks
+    tooltip_actions: Vec<Action>,
     // data
     full_state: FullState,
     current_day: Day,
     mode: Mode,
-    // handles undos and redos
-    state_history: ActionHistory,
+    show_timeline: bool,
+    // the entry to select once a jump's day change comes through
+    pending_jump: Option<i32>,
+}
+
+// the container tracks the selected day's entries live, everything else comes from the last state
+fn other_days_millis(full_state: &FullState, day: Day) -> i64 {
+    full_state.week_progress().worked_millis - full_state.day_progress(day).worked_millis
+}
+
+fn uncounted_millis(full_state: &FullState, day: Day) -> i64 {
+    full_state.get_vms_for_day(day).map_or(0, |entries| {
+        entries
+            .iter()
+            .filter(|entry| !full_state.counts_as_worked(entry.id))
+            .map(|entry| entry.total_time)
+            .sum()
+    })
+}
+
+// the last time the entry was worked, to start the range modal from
+fn last_range(full_state: &FullState, entry_id: i32) -> Option<TimeRange> {
+    full_state
+        .segments
+        .iter()
+        .filter(|segment| segment.time_entry_id == entry_id)
+        .max_by_key(|segment| segment.end_time)
+        .map(|segment| TimeRange {
+            start: segment.start_time,
+            end: segment.end_time,
+        })
+}
+
+// the shortest key bound to each command, for the hints drawn next to things
+fn command_keys(config: &Config, mode: Mode) -> HashMap<Command, String> {
+    Command::iter()
+        .filter_map(|command| {
+            let action = Action::TT(TTAct::Command(command));
+            let key = config
+                .keybindings
+                .keys_for(mode, &action)
+                .into_iter()
+                .next()?;
+            Some((command, key))
+        })
+        .collect()
 }
 
 impl Home<'_> {
     pub fn new(starting_state: FullState, config: &Config) -> Self {
         let current_day = Day::get_current_day();
         let current_entries = starting_state.get_current_time_entries();
-        let time_entry_container = TimeEntryContainer::new(current_entries, 0, current_day);
-        let charge_code_modal = ChargeCodePickerModal::new(starting_state.charge_codes.as_slice());
-
-        Self {
+        let mut time_entry_container = TimeEntryContainer::new(current_entries, 0, current_day);
+        time_entry_container.set_targets(
+            starting_state.targets.clone(),
+            other_days_millis(&starting_state, current_day),
+            uncounted_millis(&starting_state, current_day),
+        );
+        let mut charge_code_modal =
+            ChargeCodePickerModal::new(starting_state.charge_codes.as_slice());
+        charge_code_modal.set_budgets(starting_state.budgets.as_slice());
+        let mut week_container = WeekContainer::default();
+        week_container.set_state(&starting_state);
+        week_container.set_day(current_day);
+        let mut timeline = Timeline::default();
+        timeline.set_day(
+            starting_state
+                .get_vms_for_day(current_day)
+                .map_or(&[], Vec::as_slice),
+            &starting_state.segments,
+        );
+
+        let mut home = Self {
             command_tx: None,
             config: config.clone(),
             top_bar: TopBar::new(current_day),
@@ -63,54 +171,303 @@ impl Home<'_> {
             charge_code_modal,
             time_edit_modal: TimeEditModal::default(),
             swap_time_modal: SwapTimeModal::default(),
+            time_range_modal: TimeRangeModal::default(),
+            split_modal: SplitModal::default(),
+            copy_day_modal: CopyDayModal::default(),
+            quick_add_modal: QuickAddModal::default(),
+            confirm_modal: ConfirmModal::default(),
+            timeline,
             notes: NotesWrapper::new(config),
             full_state: starting_state,
             current_day,
             mode: Mode::default(),
+            show_timeline: true,
             standup_container: StandupContainer::default(),
-            state_history: ActionHistory::default(),
+            week_container,
+            status_bar: StatusBar::default(),
+            operation_history: OperationHistory::default(),
+            search_popup: SearchPopup::default(),
+            trash_popup: TrashPopup::default(),
+            tooltip_bar: TooltipBar::default(),
+            tooltip_actions: Vec::new(),
+            pending_jump: None,
+        };
+        home.set_key_hints();
+        home
+    }
+
+    fn set_key_hints(&mut self) {
+        let keys = command_keys(&self.config, self.mode);
+        self.notes
+            .set_edit_key(keys.get(&Command::EditNote).cloned().unwrap_or_default());
+        self.status_bar.set_history_key(
+            keys.get(&Command::ErrorHistory)
+                .cloned()
+                .unwrap_or_default(),
+        );
+        self.standup_container.set_keys(keys.clone());
+        self.top_bar.set_keys(keys);
+    }
+
+    fn run_command(&mut self, command: Command) -> Result<()> {
+        match command {
+            Command::ErrorHistory => self.status_bar.toggle_history(),
+            Command::Undo => self.send_request(ApiRequest::Undo),
+            Command::Redo => self.send_request(ApiRequest::Redo),
+            Command::History => self.operation_history.open(),
+            Command::Trash => self.trash_popup.open(),
+            Command::Search => self.search_popup.open(),
+            Command::CopyStandup => self.copy_standup(StandupFormat::Markdown),
+            Command::CopyStandupSlack => self.copy_standup(StandupFormat::Slack),
+            Command::SaveStandup => self.save_standup(),
+            Command::CrudMode if self.mode == Mode::Week => self.open_week_selection()?,
+            Command::NextDay
+            | Command::PreviousDay
+            | Command::Monday
+            | Command::Tuesday
+            | Command::Wednesday
+            | Command::Thursday
+            | Command::Friday
+            | Command::CrudMode
+            | Command::StandupMode
+            | Command::WeekMode => self.top_bar.handle_command(command)?,
+            Command::NextEntry | Command::PreviousEntry if self.mode == Mode::Week => {
+                self.week_container.handle_command(command)
+            }
+            Command::NextEntry | Command::PreviousEntry if self.mode == Mode::Standup => {
+                self.standup_container.handle_command(command)
+            }
+            // the rest act on the entries, which are only on screen in crud mode
+            _ if self.mode != Mode::Crud => {}
+            Command::EditNote => {
+                if !self.time_entry_container.is_empty() {
+                    self.notes.start_editing();
+                }
+            }
+            Command::EditNoteExternally => {
+                if let Some(entry) = self.selected_vm() {
+                    if let Some(tx) = &self.command_tx {
+                        tx.send(Action::UI(UIAct::EditNoteExternally {
+                            id: entry.id,
+                            note: entry.note,
+                        }))?;
+                    }
+                }
+            }
+            Command::EditRange => {
+                if let Some(entry) = self.time_entry_container.get_selected_entry() {
+                    let range = last_range(&self.full_state, entry.id);
+                    self.time_range_modal
+                        .open(Some(entry.id), self.current_day, range);
+                }
+            }
+            Command::AddRange => self.time_range_modal.open(None, self.current_day, None),
+            Command::QuickAdd => self.quick_add_modal.open(&self.full_state.charge_codes),
+            Command::ToggleTimeline => self.show_timeline = !self.show_timeline,
+            Command::SplitEntry => {
+                if let Some(entry) = self.selected_vm() {
+                    self.split_modal.open(&entry);
+                }
+            }
+            Command::MergeEntries => self.merge_selected_entry(),
+            Command::CopyDay => self.open_copy_day(),
+            Command::TogglePin => self.toggle_pin_for_selected_entry(),
+            Command::DeleteEntry => self.delete_selected_entry()?,
+            _ => self.time_entry_container.handle_command(command)?,
         }
+        Ok(())
     }
 
-    fn undo_action(&mut self) {
-        if let Some(previous_state) = self.state_history.undo() {
-            self.state_history.redo_stack.push(self.full_state.clone());
-            self.apply_diff(previous_state);
+    // the selected cell's entry in crud mode, or just its day when the code wasn't used that day
+    fn open_week_selection(&mut self) -> Result<()> {
+        let code_id = self.week_container.selected_code_id();
+        let index = self
+            .full_state
+            .get_vms_for_day(self.current_day)
+            .and_then(|entries| {
+                entries
+                    .iter()
+                    .position(|entry| entry.charge_code.as_ref().map(|code| code.id) == code_id)
+            })
+            .unwrap_or(0);
+        self.time_entry_container.set_index(index);
+        self.set_note_for_entry(self.time_entry_container.get_selected_entry());
+        self.top_bar.handle_command(Command::CrudMode)
+    }
+
+    // asks first when the config says to, though the entry only goes to the trash
+    fn delete_selected_entry(&mut self) -> Result<()> {
+        let Some(entry) = self
+            .selected_vm()
+            .filter(|entry| self.config.confirm_delete.asks_for(entry))
+        else {
+            return self
+                .time_entry_container
+                .handle_command(Command::DeleteEntry);
+        };
+        let name = entry
+            .charge_code
+            .as_ref()
+            .map_or("the entry".to_string(), |code| {
+                format!("the {} entry", code.alias)
+            });
+        let mut content = Vec::new();
+        if entry.real_total_time() > 0 {
+            content.push(format_hours_minutes(&entry.real_total_time()));
+        }
+        if !entry.note.trim().is_empty() {
+            content.push("a note".to_string());
         }
+        let message = match content.is_empty() {
+            true => format!("Delete {}?", name),
+            false => format!("Delete {} with {}?", name, content.join(" and ")),
+        };
+        self.confirm_modal
+            .open(message, ApiRequest::DeleteEntry { id: entry.id });
+        Ok(())
+    }
+
+    fn selected_vm(&self) -> Option<TimeEntryVM> {
+        let selected = self.time_entry_container.get_selected_entry()?;
+        self.full_state
+            .get_vms_for_day(self.current_day)?
+            .iter()
+            .find(|entry| entry.id == selected.id)
+            .cloned()
     }
 
-    fn redo_action(&mut self) {
-        if let Some(redo_state) = self.state_history.redo() {
-            self.state_history.undo_stack.push(self.full_state.clone());
-            self.apply_diff(redo_state);
+    // checked here too, the server's reasons don't make it back through the client
+    fn merge_selected_entry(&self) {
+        let Some(entry) = self.selected_vm() else {
+            return;
+        };
+        let has_duplicates = self
+            .full_state
+            .get_vms_for_day(self.current_day)
+            .is_some_and(|entries| {
+                entries.iter().any(|other| {
+                    other.id != entry.id
+                        && other.charge_code.is_some()
+                        && other.charge_code == entry.charge_code
+                })
+            });
+        let error = match (&entry.charge_code, has_duplicates) {
+            (None, _) => "Pick a charge code before merging".to_string(),
+            (Some(code), false) => format!("Nothing else uses {} that day", code.alias),
+            (Some(_), true) => return self.send_request(ApiRequest::MergeEntries { id: entry.id }),
+        };
+        if let Some(tx) = &self.command_tx {
+            tx.send(Action::UI(UIAct::Error(error))).unwrap()
         }
     }
 
-    fn save_state_before_action(&mut self) {
-        self.state_history.before_action(&self.full_state);
+    // the week as it is now, the modal asks the server for last week itself
+    fn open_copy_day(&mut self) {
+        let week = (0..5)
+            .map(Day::from)
+            .map(|day| {
+                let entries = self
+                    .full_state
+                    .get_vms_for_day(day)
+                    .map_or(Vec::new(), |entries| {
+                        entries.iter().map(EntryCopy::from).collect()
+                    });
+                (day, entries)
+            })
+            .collect();
+        self.copy_day_modal.open(self.current_day, week);
     }
 
-    fn apply_diff(&self, previous_state: FullState) {
-        let diff_entries = self.full_state.diff(&previous_state);
-        self.send_diff_updates(diff_entries);
+    // pins belong to the charge code, so every day gets an entry for it
+    fn toggle_pin_for_selected_entry(&mut self) {
+        let code_id = self
+            .selected_vm()
+            .and_then(|entry| entry.charge_code)
+            .map(|code| code.id);
+        let Some(code) = self
+            .full_state
+            .charge_codes
+            .iter_mut()
+            .find(|code| Some(code.id) == code_id)
+        else {
+            if let Some(tx) = &self.command_tx {
+                let error = "Pick a charge code before pinning".to_string();
+                tx.send(Action::UI(UIAct::Error(error))).unwrap()
+            }
+            return;
+        };
+        code.is_pinned = !code.is_pinned;
+        let request = ApiRequest::SetPinned {
+            charge_code_id: code.id,
+            is_pinned: code.is_pinned,
+        };
+        self.send_request(request);
+        self.set_time_entries();
     }
 
-    fn send_diff_updates(&self, diff: TimeEntriesDiff) {
-        if let Some(tx) = &self.command_tx {
-            for entry in diff.to_upsert {
-                let request = ApiRequest::FullEntryUpdate { entry };
-                tx.send(Action::api_request_action(request)).unwrap()
+    // the new half of a split gets picked a code straight away
+    fn select_split_entry(&mut self, new_id: i32) {
+        let index = self
+            .full_state
+            .get_time_entries_for_day(self.current_day)
+            .iter()
+            .position(|entry| entry.id == new_id);
+        if let Some(index) = index {
+            self.time_entry_container.set_index(index);
+            self.set_note_for_entry(self.time_entry_container.get_selected_entry());
+            if let Some(tx) = &self.command_tx {
+                tx.send(Action::TT(TTAct::EditChargeCode(new_id))).unwrap()
             }
-            for id in diff.to_delete {
-                let request = ApiRequest::DeleteEntry { id };
-                tx.send(Action::api_request_action(request)).unwrap()
+        }
+    }
+
+    // search results can be on any day and the entries are only listed in crud mode
+    fn jump_to_entry(&mut self, day: Day, id: i32) -> Result<()> {
+        if self.mode != Mode::Crud {
+            self.top_bar.handle_command(Command::CrudMode)?;
+        }
+        match day == self.current_day {
+            true => self.select_entry(id),
+            false => {
+                self.pending_jump = Some(id);
+                self.top_bar.select_day(day)?;
             }
         }
+        Ok(())
+    }
+
+    fn select_entry(&mut self, id: i32) {
+        let index = self
+            .full_state
+            .get_time_entries_for_day(self.current_day)
+            .iter()
+            .position(|entry| entry.id == id);
+        if let Some(index) = index {
+            self.time_entry_container.set_index(index);
+            self.set_note_for_entry(self.time_entry_container.get_selected_entry());
+        }
+    }
+
+    fn send_request(&self, request: ApiRequest) {
+        if let Some(tx) = &self.command_tx {
+            tx.send(Action::api_request_action(request)).unwrap()
+        }
     }
 
     fn set_time_entries(&mut self) {
         self.time_entry_container
             .set_time_entries(self.full_state.get_time_entries_for_day(self.current_day));
+        self.time_entry_container.set_targets(
+            self.full_state.targets.clone(),
+            other_days_millis(&self.full_state, self.current_day),
+            uncounted_millis(&self.full_state, self.current_day),
+        );
+        self.timeline.set_day(
+            self.full_state
+                .get_vms_for_day(self.current_day)
+                .map_or(&[], Vec::as_slice),
+            &self.full_state.segments,
+        );
 
         self.set_note_for_entry(self.time_entry_container.get_selected_entry());
     }
@@ -135,44 +492,66 @@ impl Home<'_> {
     }
 
     fn update_standup_for_current_day(&mut self) {
-        match self.full_state.get_vms_for_day(self.current_day) {
-            Some(current_days) => self.standup_container.aggregate_time_entries(
-                current_days.as_slice(),
-                self.full_state.charge_codes.as_slice(),
-            ),
-            None => self.standup_container.clear_entries(),
+        self.standup_container
+            .set_state(&self.full_state, self.current_day);
+    }
+
+    fn copy_standup(&mut self, format: StandupFormat) {
+        self.update_standup_for_current_day();
+        match copy_to_clipboard(&self.standup_container.summary(format)) {
+            Ok(()) => self
+                .status_bar
+                .push_info(format!("Copied the standup as {}", format)),
+            Err(error) => self
+                .status_bar
+                .push_error(format!("Couldn't copy the standup: {}", error)),
         }
     }
 
-    fn handle_response(&mut self, respo: ApiResponse) {
-        match respo {
-            ApiResponse::FullState(state) => self.full_state = state,
-            ApiResponse::DayEntriesUpdate(day_entries) => {
-                self.full_state
-                    .time_entries
-                    .insert(day_entries.day, day_entries.entries);
-            }
-            ApiResponse::TimeEntryUpdate(entry) => {
-                if let Some(entries) = self.full_state.time_entries.get_mut(&entry.day) {
-                    for existing_entry in entries.iter_mut() {
-                        if existing_entry.id == entry.id {
-                            *existing_entry = entry.clone();
-                            break;
-                        }
-                    }
-                }
-            }
+    // one file per day, so saving again later in the day replaces it
+    fn save_standup(&mut self) {
+        self.update_standup_for_current_day();
+        let directory = get_data_dir();
+        let path = directory.join(format!("standup-{}.md", self.current_day.into_date()));
+        let summary = self.standup_container.summary(StandupFormat::Markdown);
+        match std::fs::create_dir_all(&directory).and_then(|_| std::fs::write(&path, summary)) {
+            Ok(()) => self
+                .status_bar
+                .push_info(format!("Saved the standup to {}", path.display())),
+            Err(error) => self
+                .status_bar
+                .push_error(format!("Couldn't save the standup: {}", error)),
         }
     }
 
+    fn handle_response(&mut self, respo: ApiResponse) {
+        apply_response(&mut self.full_state, respo);
+    }
+
+    pub fn set_connection(&mut self, connection: ConnectionStatus) {
+        self.status_bar.set_connection(connection);
+    }
+
+    // the container flips timers and drops entries before the server answers,
+    // so put it back the way the last state has it
+    fn roll_back_optimistic_changes(&mut self) {
+        let selected_index = self.time_entry_container.selected_index();
+        self.set_time_entries();
+        self.time_entry_container.set_index(selected_index);
+        self.set_note_for_entry(self.time_entry_container.get_selected_entry());
+    }
+
     fn draw_crud_mode(&mut self, f: &mut Frame<'_>, area: Rect) -> Result<()> {
         let top_bar_height = 3;
         let bottom_bar_height = 3;
+        let status_bar_height = 1;
 
-        let remaining_space_height = area.height - top_bar_height - bottom_bar_height;
+        let remaining_space_height = area
+            .height
+            .saturating_sub(top_bar_height + bottom_bar_height + status_bar_height);
 
         let time_entry_container_height = (remaining_space_height as f32 * 0.75) as u16;
-        let notes_height = remaining_space_height - time_entry_container_height;
+        let notes_height = remaining_space_height.saturating_sub(time_entry_container_height);
 
         let layout = Layout::new(
             Direction::Vertical,
@@ -181,12 +560,24 @@ impl Home<'_> {
                 Constraint::Length(time_entry_container_height), // 75% of remaining space for time_entry_container
                 Constraint::Length(notes_height),                // Remaining space for notes
                 Constraint::Length(bottom_bar_height),           // Fixed height for bottom bar
+                Constraint::Length(status_bar_height),
             ],
         )
         .split(area);
 
         self.top_bar.draw(f, layout[0])?;
-        self.time_entry_container.draw(f, layout[1])?;
+        // the timeline sits beside the entries when there's room for both
+        if self.show_timeline && area.width >= 90 {
+            let columns = Layout::new(
+                Direction::Horizontal,
+                [Constraint::Min(0), Constraint::Length(32)],
+            )
+            .split(layout[1]);
+            self.time_entry_container.draw(f, columns[0])?;
+            self.timeline.draw(f, columns[1])?;
+        } else {
+            self.time_entry_container.draw(f, layout[1])?;
+        }
 
         // Draw the modals over the time entry container if they are active
         if self.charge_code_modal.is_active {
@@ -198,21 +589,36 @@ impl Home<'_> {
         if self.swap_time_modal.is_active {
             self.swap_time_modal.draw(f, layout[1])?;
         }
+        if self.time_range_modal.is_active {
+            self.time_range_modal.draw(f, layout[1])?;
+        }
+        if self.split_modal.is_active {
+            self.split_modal.draw(f, layout[1])?;
+        }
+        if self.copy_day_modal.is_active {
+            self.copy_day_modal.draw(f, layout[1])?;
+        }
+        if self.quick_add_modal.is_active {
+            self.quick_add_modal.draw(f, layout[1])?;
+        }
+        if self.confirm_modal.is_active {
+            self.confirm_modal.draw(f, layout[1])?;
+        }
 
         self.notes.draw(f, layout[2])?;
 
-        let tooltips = vec![
-            "Quit [q]",
-            "Add [a]",
-            "Delete [d]",
-            "Play/Pause [Space]",
-            "Code [c]",
-            "Time [t]",
-            "Swap [s]",
-            "Undo [u]",
-            "Redo [ctrl+r]",
-        ];
-        draw_tooltip_bar(f, layout[3], &tooltips);
+        let (tooltips, actions): (Vec<String>, Vec<Action>) = CRUD_TOOLTIPS
+            .iter()
+            .filter_map(|(label, action)| {
+                let keys = self.config.keybindings.keys_for(Mode::Crud, action);
+                keys.first()
+                    .map(|key| (format!("{} [{}]", label, key), action.clone()))
+            })
+            .unzip();
+        let tooltips: Vec<&str> = tooltips.iter().map(String::as_str).collect();
+        self.tooltip_bar.draw(f, layout[3], &tooltips);
+        self.tooltip_actions = actions;
+        self.status_bar.draw(f, layout[4])?;
 
         Ok(())
     }
@@ -224,12 +630,31 @@ impl Home<'_> {
             [
                 Constraint::Length(top_bar_height),
                 Constraint::Min(0), // Remaining space for standup mode content
+                Constraint::Length(1),
             ],
         )
         .split(area);
 
         self.top_bar.draw(f, layout[0])?;
         self.standup_container.draw(f, layout[1])?;
+        self.status_bar.draw(f, layout[2])?;
+        Ok(())
+    }
+
+    fn draw_week_mode(&mut self, f: &mut Frame<'_>, area: Rect) -> Result<()> {
+        let layout = Layout::new(
+            Direction::Vertical,
+            [
+                Constraint::Length(3),
+                Constraint::Min(0),
+                Constraint::Length(1),
+            ],
+        )
+        .split(area);
+
+        self.top_bar.draw(f, layout[0])?;
+        self.week_container.draw(f, layout[1])?;
+        self.status_bar.draw(f, layout[2])?;
         Ok(())
     }
 }
@@ -244,6 +669,14 @@ impl Component for Home<'_> {
         self.charge_code_modal.register_action_handler(tx.clone())?;
         self.time_edit_modal.register_action_handler(tx.clone())?;
         self.swap_time_modal.register_action_handler(tx.clone())?;
+        self.time_range_modal.register_action_handler(tx.clone())?;
+        self.split_modal.register_action_handler(tx.clone())?;
+        self.copy_day_modal.register_action_handler(tx.clone())?;
+        self.quick_add_modal.register_action_handler(tx.clone())?;
+        self.operation_history.register_action_handler(tx.clone())?;
+        self.search_popup.register_action_handler(tx.clone())?;
+        self.trash_popup.register_action_handler(tx.clone())?;
+        self.confirm_modal.register_action_handler(tx.clone())?;
 
         // hacky: this initalizes the system with the right entry selected
         self.time_entry_container.send_index_action();
@@ -253,14 +686,24 @@ impl Component for Home<'_> {
 
     fn register_config_handler(&mut self, config: Config) -> Result<()> {
         self.config = config;
+        self.set_key_hints();
         Ok(())
     }
 
     fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        self.status_bar.update(action.clone())?;
+        self.operation_history.update(action.clone())?;
+        self.search_popup.update(action.clone())?;
+        self.trash_popup.update(action.clone())?;
+        self.copy_day_modal.update(action.clone())?;
         match action {
             Action::UI(ui_action) => {
                 if ui_action == UIAct::Tick {
                     self.time_entry_container.update(Action::UI(ui_action))?;
+                    // keeps a running timer's cell current
+                    if self.mode == Mode::Week {
+                        self.week_container.set_state(&self.full_state);
+                    }
                 }
             }
             Action::TT(tt_action) => match tt_action {
@@ -271,32 +714,38 @@ impl Component for Home<'_> {
                     self.set_time_entries();
                     self.time_entry_container.set_index(0);
                     self.set_note_for_entry(self.time_entry_container.get_selected_entry());
+                    if let Some(id) = self.pending_jump.take() {
+                        self.select_entry(id);
+                    }
                     self.time_entry_container.set_day(day);
+                    self.week_container.set_day(day);
 
                     // standup stuff
                     self.update_standup_for_current_day();
                 }
                 TTAct::UpdateSelectedEntry => {
-                    self.save_state_before_action();
                     self.set_note_for_entry(self.time_entry_container.get_selected_entry());
                 }
                 TTAct::EditChargeCode(id) => {
-                    self.save_state_before_action();
                     self.charge_code_modal.set_charge_code_id(id);
+                    self.charge_code_modal
+                        .set_charge_codes(&self.full_state.charge_codes);
+                    self.charge_code_modal
+                        .set_budgets(self.full_state.budgets.as_slice());
                     self.charge_code_modal.toggle();
                 }
                 TTAct::EditTime(time_action) => {
-                    self.save_state_before_action();
                     self.time_edit_modal.set_time(time_action.millis);
                     self.time_edit_modal.set_entry_id(time_action.id);
                     self.time_edit_modal.toggle();
                 }
                 TTAct::UpdateMode(mode) => {
                     self.update_standup_for_current_day();
+                    self.week_container.set_state(&self.full_state);
                     self.mode = mode;
+                    self.set_key_hints();
                 }
                 TTAct::SwapTime(id) => {
-                    self.save_state_before_action();
                     self.swap_time_modal.set_swap_from_id(id);
 
                     let other_entries: Vec<TimeEntry> = self
@@ -309,20 +758,50 @@ impl Component for Home<'_> {
                     self.swap_time_modal.set_other_entries(other_entries);
                     self.swap_time_modal.toggle();
                 }
-                TTAct::SaveState => self.save_state_before_action(),
+                TTAct::JumpToEntry(day, id) => self.jump_to_entry(day, id)?,
+                TTAct::Command(command) => self.run_command(command)?,
+                TTAct::Confirmed(ApiRequest::DeleteEntry { id }) => {
+                    self.time_entry_container.delete_entry(id)?
+                }
+                TTAct::Confirmed(request) => self.send_request(request),
             },
-            Action::Api(api_action) => {
-                // only handle the responses here
-                if let ApiAct::Response(respo) = api_action {
+            Action::Api(api_action) => match api_action {
+                ApiAct::Response(ApiResponse::Split(day_entries)) => {
+                    let new_id = day_entries.entries.iter().map(|entry| entry.id).max();
+                    self.handle_response(ApiResponse::Split(day_entries));
+                    self.set_time_entries();
+                    if let Some(new_id) = new_id {
+                        self.select_split_entry(new_id);
+                    }
+                }
+                ApiAct::Response(respo) => {
                     self.handle_response(respo);
                     self.set_time_entries();
+                    if self.mode == Mode::Standup {
+                        self.update_standup_for_current_day();
+                    }
                 }
-            }
+                ApiAct::Error(_) => self.roll_back_optimistic_changes(),
+                // requests are handled by the api client
+                _ => {}
+            },
         }
         Ok(None)
     }
 
     fn handle_key_events(&mut self, key: KeyEvent) -> Result<Option<Action>> {
+        if self.status_bar.is_history_open {
+            return self.status_bar.handle_key_events(key);
+        }
+        if self.operation_history.is_open {
+            return self.operation_history.handle_key_events(key);
+        }
+        if self.search_popup.is_open {
+            return self.search_popup.handle_key_events(key);
+        }
+        if self.trash_popup.is_open {
+            return self.trash_popup.handle_key_events(key);
+        }
         if self.notes.is_edit_mode() {
             return self.notes.handle_key_events(key);
         }
@@ -335,37 +814,104 @@ impl Component for Home<'_> {
         if self.swap_time_modal.is_active {
             return self.swap_time_modal.handle_key_events(key);
         }
+        if self.time_range_modal.is_active {
+            return self.time_range_modal.handle_key_events(key);
+        }
+        if self.split_modal.is_active {
+            return self.split_modal.handle_key_events(key);
+        }
+        if self.copy_day_modal.is_active {
+            return self.copy_day_modal.handle_key_events(key);
+        }
+        if self.quick_add_modal.is_active {
+            return self.quick_add_modal.handle_key_events(key);
+        }
+        if self.confirm_modal.is_active {
+            return self.confirm_modal.handle_key_events(key);
+        }
 
-        // Handling global shortcuts
-        match (key.code, key.modifiers) {
-            (KeyCode::Char('r'), KeyModifiers::CONTROL) => {
-                self.redo_action();
-                return Ok(None);
-            }
-            (KeyCode::Char('u'), KeyModifiers::NONE) => {
-                self.undo_action();
-                return Ok(None);
-            }
-            (KeyCode::Char('q'), KeyModifiers::NONE) => {
-                return Ok(Some(Action::UI(Quit)));
-            }
-            _ => {}
+        // everything else comes through the keybindings as commands
+        Ok(None)
+    }
+
+    // popups get the mouse first, like the keys, and the clicks outside them are dropped
+    fn handle_mouse_events(&mut self, mouse: MouseEvent) -> Result<Option<Action>> {
+        if self.status_bar.is_history_open {
+            return self.status_bar.handle_mouse_events(mouse);
+        }
+        if self.operation_history.is_open {
+            return self.operation_history.handle_mouse_events(mouse);
+        }
+        if self.search_popup.is_open {
+            return self.search_popup.handle_mouse_events(mouse);
+        }
+        if self.trash_popup.is_open {
+            return self.trash_popup.handle_mouse_events(mouse);
+        }
+        if self.notes.is_edit_mode() {
+            return Ok(None);
+        }
+        if self.charge_code_modal.is_active {
+            return self.charge_code_modal.handle_mouse_events(mouse);
+        }
+        if self.time_edit_modal.is_active {
+            return self.time_edit_modal.handle_mouse_events(mouse);
+        }
+        if self.swap_time_modal.is_active {
+            return self.swap_time_modal.handle_mouse_events(mouse);
+        }
+        if self.time_range_modal.is_active {
+            return self.time_range_modal.handle_mouse_events(mouse);
+        }
+        if self.split_modal.is_active {
+            return self.split_modal.handle_mouse_events(mouse);
+        }
+        if self.copy_day_modal.is_active {
+            return self.copy_day_modal.handle_mouse_events(mouse);
+        }
+        if self.quick_add_modal.is_active {
+            return self.quick_add_modal.handle_mouse_events(mouse);
+        }
+        if self.confirm_modal.is_active {
+            return self.confirm_modal.handle_mouse_events(mouse);
         }
 
-        // If no modals are active and no global shortcuts are pressed, delegate to other components
-        self.top_bar.handle_key_events(key)?;
-        self.time_entry_container.handle_key_events(key)?;
-        if !self.time_entry_container.is_empty(){
-            self.notes.handle_key_events(key)?;
+        if let Some(action) = self.top_bar.handle_mouse_events(mouse)? {
+            return Ok(Some(action));
+        }
+        if self.mode != Mode::Crud {
+            return Ok(None);
         }
+        match self.tooltip_bar.clicked(mouse) {
+            Some(index) => Ok(self.tooltip_actions.get(index).cloned()),
+            None => self.time_entry_container.handle_mouse_events(mouse),
+        }
+    }
 
-        Ok(None)
+    fn is_capturing_input(&self) -> bool {
+        self.status_bar.is_history_open
+            || self.operation_history.is_open
+            || self.search_popup.is_open
+            || self.trash_popup.is_open
+            || self.notes.is_edit_mode()
+            || self.charge_code_modal.is_active
+            || self.time_edit_modal.is_active
+            || self.swap_time_modal.is_active
+            || self.time_range_modal.is_active
+            || self.split_modal.is_active
+            || self.copy_day_modal.is_active
+            || self.quick_add_modal.is_active
+            || self.confirm_modal.is_active
     }
 
     fn draw(&mut self, f: &mut Frame<'_>, area: Rect) -> Result<()> {
         match self.mode {
-            Mode::Crud => self.draw_crud_mode(f, area),
-            Mode::Standup => self.draw_standup_mode(f, area),
+            Mode::Crud => self.draw_crud_mode(f, area)?,
+            Mode::Standup => self.draw_standup_mode(f, area)?,
+            Mode::Week => self.draw_week_mode(f, area)?,
         }
+        self.operation_history.draw(f, area)?;
+        self.trash_popup.draw(f, area)?;
+        self.search_popup.draw(f, area)
     }
 }
@@ -13,9 +13,14 @@ pub mod component_utils;
 pub mod fps;
 pub mod home;
 pub mod modals;
+pub mod operation_history;
+pub mod search_popup;
 pub mod standup;
+pub mod status_bar;
 pub mod time_entry;
 pub mod top_bar;
+pub mod trash_popup;
+pub mod week;
 pub mod notes;
 
 /// `Component` is a trait that represents a visual and interactive element of the user interface.
@@ -90,6 +95,15 @@ pub trait Component {
     fn handle_key_events(&mut self, key: KeyEvent) -> Result<Option<Action>> {
         Ok(None)
     }
+    /// Whether the component is taking raw key input, like a modal or an editor.
+    /// Keybindings from the config are mostly skipped while this is true.
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - True if keys should go to the component as typed.
+    fn is_capturing_input(&self) -> bool {
+        false
+    }
     /// Handle mouse events and produce actions if necessary.
     ///
     /// # Arguments
@@ -1,20 +1,29 @@
-use crate::components::component_utils::draw_tooltip_bar;
+use crate::components::component_utils::TooltipBar;
+use crate::components::time_entry::time_utils::format_hours_minutes;
 use crate::components::Component;
-use crate::{action::Action, api_client::ApiRequest::UpdateChargeCode};
+use crate::theme::{self, StyleName};
+use crate::{
+    action::Action,
+    api_client::ApiRequest::{SetFavorite, UpdateChargeCode},
+};
 use color_eyre::eyre::Result;
-use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseEvent, MouseEventKind};
 use fuzzy_matcher::skim::SkimMatcherV2;
 use fuzzy_matcher::FuzzyMatcher;
 use ratatui::layout::Alignment;
 use ratatui::{
     layout::{Margin, Rect},
-    style::{Color, Style},
+    text::{Line, Span},
     widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph, Wrap},
     Frame,
 };
-use shared_lib::models::charge_code::ChargeCode;
+use shared_lib::models::{budget::BudgetUsage, charge_code::ChargeCode};
+use std::{cmp::Reverse, collections::HashMap};
 use tokio::sync::mpsc::UnboundedSender;
 
+// how many of the last used codes are listed after the favorites
+const RECENT_COUNT: usize = 5;
+
 pub struct ChargeCodePickerModal {
     command_tx: Option<UnboundedSender<Action>>,
     matcher: SkimMatcherV2,
@@ -24,22 +33,100 @@ pub struct ChargeCodePickerModal {
     pub is_active: bool,
     pub entry_id: Option<i32>,
     pub list_state: ListState,
+    // remaining budget in minutes, by charge code id
+    remaining_budgets: HashMap<i32, i64>,
+    tooltip_bar: TooltipBar,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum CodeGroup {
+    Favorite,
+    Recent,
+    Other,
 }
 
 pub struct ChargeCodeRef {
     alias: String,
+    code: String,
+    is_nc: bool,
+    group: CodeGroup,
     id: i32,
 }
 
+// favorites, then the most recently used, then the rest in database order
+fn code_groups(charge_codes: &[ChargeCode]) -> HashMap<i32, CodeGroup> {
+    let mut recent: Vec<&ChargeCode> = charge_codes
+        .iter()
+        .filter(|code| !code.is_favorite && code.last_used_at.is_some())
+        .collect();
+    recent.sort_by_key(|code| Reverse(code.last_used_at));
+    recent.truncate(RECENT_COUNT);
+
+    charge_codes
+        .iter()
+        .map(|code| {
+            let is_recent = recent.iter().any(|recent| recent.id == code.id);
+            let group = match (code.is_favorite, is_recent) {
+                (true, _) => CodeGroup::Favorite,
+                (false, true) => CodeGroup::Recent,
+                (false, false) => CodeGroup::Other,
+            };
+            (code.id, group)
+        })
+        .collect()
+}
+
+// what the input is matched against, so a code can be found by its number or by typing "nc"
+fn search_text(code: &ChargeCode) -> String {
+    match code.is_nc {
+        true => format!("{} {} nc", code.alias, code.code),
+        false => format!("{} {}", code.alias, code.code),
+    }
+}
+
+/// The codes matching `input`, best match first. Favorites and recents win ties,
+/// so they come first when nothing has been typed yet.
+fn rank_codes(
+    matcher: &SkimMatcherV2,
+    charge_codes: &[ChargeCode],
+    input: &str,
+) -> Vec<ChargeCodeRef> {
+    let groups = code_groups(charge_codes);
+    let mut scored_codes: Vec<(i64, ChargeCodeRef, Option<Reverse<_>>)> = charge_codes
+        .iter()
+        .filter_map(|code| {
+            let score = matcher.fuzzy_match(&search_text(code), input)?;
+            let code_ref = ChargeCodeRef {
+                alias: code.alias.clone(),
+                code: code.code.clone(),
+                is_nc: code.is_nc,
+                group: groups[&code.id],
+                id: code.id,
+            };
+            Some((score, code_ref, code.last_used_at.map(Reverse)))
+        })
+        .collect();
+
+    scored_codes.sort_by(|a, b| {
+        b.0.cmp(&a.0)
+            .then(a.1.group.cmp(&b.1.group))
+            .then_with(|| match a.1.group {
+                CodeGroup::Favorite => a.1.alias.to_lowercase().cmp(&b.1.alias.to_lowercase()),
+                CodeGroup::Recent => a.2.cmp(&b.2),
+                CodeGroup::Other => std::cmp::Ordering::Equal,
+            })
+    });
+
+    scored_codes
+        .into_iter()
+        .map(|(_, code_ref, _)| code_ref)
+        .collect()
+}
+
 impl ChargeCodePickerModal {
     pub fn new(charge_codes: &[ChargeCode]) -> Self {
-        let charge_code_names = charge_codes
-            .iter()
-            .map(|x| ChargeCodeRef {
-                alias: x.alias.clone(),
-                id: x.id,
-            })
-            .collect();
+        let matcher = SkimMatcherV2::default();
+        let filtered_codes = rank_codes(&matcher, charge_codes, "");
         let mut list_state = ListState::default();
         if !charge_codes.is_empty() {
             list_state.select(Some(0))
@@ -48,14 +135,70 @@ impl ChargeCodePickerModal {
             command_tx: None,
             input: String::new(),
             charge_codes: charge_codes.to_vec(),
-            filtered_codes: charge_code_names,
+            filtered_codes,
             is_active: false,
             entry_id: None,
             list_state,
-            matcher: SkimMatcherV2::default(),
+            matcher,
+            remaining_budgets: HashMap::new(),
+            tooltip_bar: TooltipBar::default(),
         }
     }
 
+    pub fn set_charge_codes(&mut self, charge_codes: &[ChargeCode]) {
+        self.charge_codes = charge_codes.to_vec();
+        self.update_input(self.input.clone());
+    }
+
+    // pinned right away, the server's copy follows
+    fn toggle_favorite(&mut self) {
+        let Some(id) = self.get_selected_charge_code_id() else {
+            return;
+        };
+        let Some(code) = self.charge_codes.iter_mut().find(|code| code.id == id) else {
+            return;
+        };
+        code.is_favorite = !code.is_favorite;
+        if let Some(tx) = &self.command_tx {
+            if let Err(error) = tx.send(Action::api_request_action(SetFavorite {
+                charge_code_id: id,
+                is_favorite: code.is_favorite,
+            })) {
+                log::error!("Failed to send action: {error:?}");
+            }
+        }
+
+        self.filtered_codes = rank_codes(&self.matcher, &self.charge_codes, &self.input);
+        let position = self.filtered_codes.iter().position(|code| code.id == id);
+        self.list_state.select(position.or(Some(0)));
+    }
+
+    pub fn set_budgets(&mut self, budgets: &[BudgetUsage]) {
+        self.remaining_budgets = budgets
+            .iter()
+            .map(|budget| (budget.charge_code_id, budget.remaining_minutes()))
+            .collect();
+    }
+
+    fn budget_span(&self, charge_code_id: i32) -> Option<Span<'static>> {
+        let remaining = *self.remaining_budgets.get(&charge_code_id)?;
+        let span = if remaining < 0 {
+            Span::styled(
+                format!(
+                    "  {} over budget",
+                    format_hours_minutes(&(-remaining * 60_000))
+                ),
+                theme::style(StyleName::Error),
+            )
+        } else {
+            Span::styled(
+                format!("  {} left", format_hours_minutes(&(remaining * 60_000))),
+                theme::style(StyleName::Hint),
+            )
+        };
+        Some(span)
+    }
+
     pub fn set_charge_code_id(&mut self, id: i32) {
         self.entry_id = Some(id);
     }
@@ -74,24 +217,7 @@ impl ChargeCodePickerModal {
 
     pub fn update_input(&mut self, input: String) {
         self.input = input;
-
-        let mut scored_codes: Vec<(i64, String, i32)> = self
-            .charge_codes
-            .iter()
-            .filter_map(|code| {
-                self.matcher
-                    .fuzzy_match(&code.alias, &self.input)
-                    .map(|score| (score, code.alias.clone(), code.id))
-            })
-            .collect();
-
-        scored_codes.sort_by(|a, b| b.0.cmp(&a.0));
-
-        self.filtered_codes = scored_codes
-            .into_iter()
-            .map(|(_, alias, id)| ChargeCodeRef { alias, id })
-            .collect();
-
+        self.filtered_codes = rank_codes(&self.matcher, &self.charge_codes, &self.input);
         self.update_selection_to_first();
     }
 
@@ -179,7 +305,7 @@ impl Component for ChargeCodePickerModal {
             let block = Block::default()
                 .title("Charge Code Selection")
                 .borders(Borders::ALL)
-                .border_style(Style::default().fg(Color::Yellow));
+                .border_style(theme::style(StyleName::ModalBorder));
 
             // Render the block around the modal area
             f.render_widget(block, modal_area);
@@ -204,24 +330,45 @@ impl Component for ChargeCodePickerModal {
 
             // Create and draw the list of charge codes
             let list_block = Block::default().borders(Borders::ALL);
-            let item_style = Style::default().fg(Color::DarkGray);
+            let item_style = theme::style(StyleName::ListItem);
             let list_items: Vec<ListItem> = self
                 .filtered_codes
                 .iter()
-                .map(|code_ref| ListItem::new(code_ref.alias.clone()).style(item_style))
+                .map(|code_ref| {
+                    let marker = match code_ref.group {
+                        CodeGroup::Favorite => {
+                            Span::styled("★ ", theme::style(StyleName::Favorite))
+                        }
+                        CodeGroup::Recent => Span::raw("· "),
+                        CodeGroup::Other => Span::raw("  "),
+                    };
+                    let mut spans = vec![
+                        marker,
+                        Span::raw(code_ref.alias.clone()),
+                        Span::styled(
+                            format!("  {}", code_ref.code),
+                            theme::style(StyleName::Hint),
+                        ),
+                    ];
+                    if code_ref.is_nc {
+                        spans.push(Span::styled("  NC", theme::style(StyleName::NonChargeable)));
+                    }
+                    spans.extend(self.budget_span(code_ref.id));
+                    ListItem::new(Line::from(spans)).style(item_style)
+                })
                 .collect();
 
             let list = List::new(list_items)
                 .block(list_block)
-                .style(Style::default().fg(Color::Yellow))
-                .highlight_style(Style::default().fg(Color::White))
+                .style(theme::style(StyleName::ActivePanel))
+                .highlight_style(theme::style(StyleName::ListHighlight))
                 .repeat_highlight_symbol(true)
                 .highlight_symbol(">");
 
             f.render_stateful_widget(list, list_area, &mut self.list_state);
 
-            let tooltips = vec!["Select [Enter]", "Back [Esc]"];
-            draw_tooltip_bar(f, bottom_bar_area, &tooltips);
+            let tooltips = vec!["Select [Enter]", "Favorite [Ctrl-f]", "Back [Esc]"];
+            self.tooltip_bar.draw(f, bottom_bar_area, &tooltips);
 
             Ok(())
         }
@@ -231,6 +378,8 @@ impl Component for ChargeCodePickerModal {
         if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('w') {
             self.delete_previous_word();
             self.update_input(self.input.clone());
+        } else if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('f') {
+            self.toggle_favorite();
         } else if key.modifiers.contains(KeyModifiers::SHIFT) && key.code == KeyCode::BackTab {
             self.previous();
         } else {
@@ -270,4 +419,58 @@ impl Component for ChargeCodePickerModal {
 
         Ok(None)
     }
+
+    fn handle_mouse_events(&mut self, mouse: MouseEvent) -> Result<Option<Action>> {
+        match mouse.kind {
+            MouseEventKind::ScrollDown => self.next(),
+            MouseEventKind::ScrollUp => self.previous(),
+            _ => {
+                if let Some(key) = self.tooltip_bar.clicked_key(mouse) {
+                    return self.handle_key_events(key);
+                }
+            }
+        }
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, Utc};
+
+    fn code(id: i32, alias: &str, is_nc: bool) -> ChargeCode {
+        ChargeCode {
+            id,
+            alias: alias.to_string(),
+            code: format!("CODE.{}", id),
+            is_nc,
+            ..Default::default()
+        }
+    }
+
+    fn ranked_ids(charge_codes: &[ChargeCode], input: &str) -> Vec<i32> {
+        rank_codes(&SkimMatcherV2::default(), charge_codes, input)
+            .iter()
+            .map(|code| code.id)
+            .collect()
+    }
+
+    #[test]
+    fn favorites_and_recents_come_first() {
+        let now = Utc::now();
+        let mut codes = vec![
+            code(1, "Ops", false),
+            code(2, "Design", false),
+            code(3, "Training", true),
+            code(4, "Admin", false),
+        ];
+        codes[3].is_favorite = true;
+        codes[0].last_used_at = Some(now - Duration::days(2));
+        codes[2].last_used_at = Some(now);
+
+        assert_eq!(ranked_ids(&codes, ""), vec![4, 3, 1, 2]);
+        assert_eq!(ranked_ids(&codes, "code.2"), vec![2]);
+        assert_eq!(ranked_ids(&codes, "nc")[0], 3);
+    }
 }
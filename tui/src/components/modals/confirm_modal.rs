@@ -0,0 +1,99 @@
+use crate::action::{Action, TTAct};
+use crate::api_client::ApiRequest;
+use crate::components::component_utils::TooltipBar;
+use crate::components::Component;
+use crate::theme::{self, StyleName};
+use color_eyre::eyre::Result;
+use crossterm::event::{KeyCode, KeyEvent, MouseEvent};
+use ratatui::{
+    prelude::*,
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+};
+use tokio::sync::mpsc::UnboundedSender;
+
+/// Asks before a request is sent, and sends it on as confirmed if the answer is yes
+#[derive(Default)]
+pub struct ConfirmModal {
+    pub is_active: bool,
+    message: String,
+    request: Option<ApiRequest>,
+    command_tx: Option<UnboundedSender<Action>>,
+    tooltip_bar: TooltipBar,
+}
+
+impl ConfirmModal {
+    pub fn open(&mut self, message: String, request: ApiRequest) {
+        self.message = message;
+        self.request = Some(request);
+        self.is_active = true;
+    }
+
+    fn confirm(&mut self) -> Result<()> {
+        if let (Some(request), Some(tx)) = (self.request.take(), &self.command_tx) {
+            tx.send(Action::TT(TTAct::Confirmed(request)))?;
+        }
+        self.is_active = false;
+        Ok(())
+    }
+}
+
+impl Component for ConfirmModal {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
+        self.command_tx = Some(tx);
+        Ok(())
+    }
+
+    fn handle_key_events(&mut self, key: KeyEvent) -> Result<Option<Action>> {
+        match key.code {
+            KeyCode::Enter | KeyCode::Char('y') => self.confirm()?,
+            KeyCode::Esc | KeyCode::Char('n') => {
+                self.request = None;
+                self.is_active = false;
+            }
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn handle_mouse_events(&mut self, mouse: MouseEvent) -> Result<Option<Action>> {
+        match self.tooltip_bar.clicked_key(mouse) {
+            Some(key) => self.handle_key_events(key),
+            None => Ok(None),
+        }
+    }
+
+    fn draw(&mut self, f: &mut Frame<'_>, area: Rect) -> Result<()> {
+        if !self.is_active {
+            return Ok(());
+        }
+
+        let modal_area = area.inner(&Margin {
+            horizontal: (area.width as f32 * 0.2) as u16,
+            vertical: area.height.saturating_sub(8) / 2,
+        });
+        f.render_widget(Clear, modal_area);
+
+        let block = Block::default()
+            .title("Are you sure?")
+            .borders(Borders::ALL)
+            .border_style(theme::style(StyleName::ModalBorder));
+        let inner_area = block.inner(modal_area);
+        f.render_widget(block, modal_area);
+
+        let layout = Layout::new(
+            Direction::Vertical,
+            [Constraint::Min(0), Constraint::Length(3)],
+        )
+        .split(inner_area);
+
+        f.render_widget(
+            Paragraph::new(self.message.clone())
+                .alignment(Alignment::Center)
+                .wrap(Wrap { trim: true }),
+            layout[0],
+        );
+        self.tooltip_bar.draw(f, layout[1], &["Yes [y]", "No [n]"]);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,372 @@
+use crate::components::component_utils::TooltipBar;
+use crate::components::time_entry::time_utils::format_hours_minutes;
+use crate::components::Component;
+use crate::theme::{self, StyleName};
+use crate::{
+    action::{Action, ApiAct},
+    api_client::{ApiRequest, ApiResponse},
+};
+use color_eyre::eyre::Result;
+use crossterm::event::{KeyCode, KeyEvent, MouseEvent};
+use ratatui::{
+    prelude::*,
+    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph},
+};
+use shared_lib::models::{
+    copy_day::{CopyDayRequest, EntryCopy},
+    day::Day,
+};
+use tokio::sync::mpsc::UnboundedSender;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum CopySource {
+    Day(Day),
+    // the same weekday a week back, which has to come from the server
+    LastWeek,
+}
+
+/// Recreates entries from another day on the current one. First a day is picked, then
+/// which of its entries to copy and whether their time and notes come along.
+#[derive(Default)]
+pub struct CopyDayModal {
+    pub is_active: bool,
+    target_day: Day,
+    // the other days with entries, as they were when the modal opened
+    week: Vec<(Day, Vec<EntryCopy>)>,
+    // `None` while the day is being picked
+    source: Option<CopySource>,
+    source_state: ListState,
+    entries: Vec<EntryCopy>,
+    picked: Vec<bool>,
+    entry_state: ListState,
+    with_time: bool,
+    with_notes: bool,
+    is_loading: bool,
+    command_tx: Option<UnboundedSender<Action>>,
+    tooltip_bar: TooltipBar,
+}
+
+impl CopyDayModal {
+    pub fn open(&mut self, target_day: Day, week: Vec<(Day, Vec<EntryCopy>)>) {
+        self.target_day = target_day;
+        self.week = week
+            .into_iter()
+            .filter(|(day, entries)| *day != target_day && !entries.is_empty())
+            .collect();
+        self.source = None;
+        self.source_state.select(Some(0));
+        self.set_entries(Vec::new());
+        self.with_time = false;
+        self.with_notes = false;
+        self.is_loading = false;
+        self.is_active = true;
+    }
+
+    fn sources(&self) -> Vec<CopySource> {
+        self.week
+            .iter()
+            .map(|(day, _)| CopySource::Day(*day))
+            .chain([CopySource::LastWeek])
+            .collect()
+    }
+
+    fn source_label(&self, source: CopySource) -> String {
+        match source {
+            CopySource::Day(day) => {
+                let count = self
+                    .week
+                    .iter()
+                    .find(|(other, _)| *other == day)
+                    .map_or(0, |(_, entries)| entries.len());
+                format!("{:?} ({} entries)", day, count)
+            }
+            CopySource::LastWeek => format!("{:?} last week", self.target_day),
+        }
+    }
+
+    fn set_entries(&mut self, entries: Vec<EntryCopy>) {
+        self.picked = vec![true; entries.len()];
+        self.entries = entries;
+        self.entry_state.select(Some(0));
+    }
+
+    fn pick_source(&mut self) -> Result<()> {
+        let sources = self.sources();
+        let Some(source) = sources.get(self.source_state.selected().unwrap_or(0)) else {
+            return Ok(());
+        };
+        self.source = Some(*source);
+        match source {
+            CopySource::Day(day) => {
+                let entries = self
+                    .week
+                    .iter()
+                    .find(|(other, _)| other == day)
+                    .map(|(_, entries)| entries.clone())
+                    .unwrap_or_default();
+                self.set_entries(entries);
+            }
+            CopySource::LastWeek => {
+                self.set_entries(Vec::new());
+                self.is_loading = true;
+                if let Some(tx) = &self.command_tx {
+                    tx.send(Action::api_request_action(
+                        ApiRequest::GetLastWeeksEntries {
+                            day: self.target_day.into(),
+                        },
+                    ))?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn submit(&mut self) -> Result<()> {
+        let entries: Vec<EntryCopy> = self
+            .entries
+            .iter()
+            .zip(&self.picked)
+            .filter(|(_, picked)| **picked)
+            .map(|(entry, _)| entry.clone())
+            .collect();
+        if entries.is_empty() {
+            return Ok(());
+        }
+        if let Some(tx) = &self.command_tx {
+            let request = CopyDayRequest {
+                entries,
+                with_time: self.with_time,
+                with_notes: self.with_notes,
+            };
+            tx.send(Action::api_request_action(ApiRequest::CopyEntries {
+                day: self.target_day.into(),
+                request,
+            }))?;
+        }
+        self.is_active = false;
+        Ok(())
+    }
+
+    fn toggle_selected(&mut self) {
+        if let Some(picked) = self
+            .entry_state
+            .selected()
+            .and_then(|index| self.picked.get_mut(index))
+        {
+            *picked = !*picked;
+        }
+    }
+
+    // everything goes back on unless everything already was
+    fn toggle_all(&mut self) {
+        let pick = !self.picked.iter().all(|picked| *picked);
+        self.picked.iter_mut().for_each(|picked| *picked = pick);
+    }
+
+    fn select(state: &mut ListState, len: usize, offset: isize) {
+        if len == 0 {
+            return;
+        }
+        let selected = state.selected().unwrap_or(0) as isize;
+        state.select(Some((selected + offset).clamp(0, len as isize - 1) as usize));
+    }
+
+    fn handle_source_keys(&mut self, key: KeyEvent) -> Result<()> {
+        let len = self.sources().len();
+        match key.code {
+            KeyCode::Esc => self.is_active = false,
+            KeyCode::Enter => self.pick_source()?,
+            KeyCode::Char('j') | KeyCode::Down => Self::select(&mut self.source_state, len, 1),
+            KeyCode::Char('k') | KeyCode::Up => Self::select(&mut self.source_state, len, -1),
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn handle_entry_keys(&mut self, key: KeyEvent) -> Result<()> {
+        let len = self.entries.len();
+        match key.code {
+            KeyCode::Esc => {
+                self.source = None;
+                self.is_loading = false;
+            }
+            KeyCode::Enter => self.submit()?,
+            KeyCode::Char(' ') => self.toggle_selected(),
+            KeyCode::Char('a') => self.toggle_all(),
+            KeyCode::Char('t') => self.with_time = !self.with_time,
+            KeyCode::Char('n') => self.with_notes = !self.with_notes,
+            KeyCode::Char('j') | KeyCode::Down => Self::select(&mut self.entry_state, len, 1),
+            KeyCode::Char('k') | KeyCode::Up => Self::select(&mut self.entry_state, len, -1),
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn entry_item(&self, entry: &EntryCopy, picked: bool) -> ListItem<'static> {
+        let included = |copied: bool| match copied {
+            true => theme::style(StyleName::Hint),
+            false => theme::style(StyleName::Faint).add_modifier(Modifier::CROSSED_OUT),
+        };
+        let check = match picked {
+            true => "[x] ",
+            false => "[ ] ",
+        };
+        let alias = entry
+            .charge_code
+            .as_ref()
+            .map_or("(no charge code)".to_string(), |code| code.alias.clone());
+        let mut spans = vec![
+            Span::raw(check),
+            Span::raw(alias),
+            Span::styled(
+                format!("  {}", format_hours_minutes(&entry.total_time)),
+                included(self.with_time),
+            ),
+        ];
+        if let Some(line) = entry.note.lines().find(|line| !line.trim().is_empty()) {
+            spans.push(Span::styled(
+                format!("  {}", line.trim()),
+                included(self.with_notes),
+            ));
+        }
+        ListItem::new(Line::from(spans))
+    }
+
+    fn status_line(&self) -> Line<'static> {
+        if self.is_loading {
+            return Line::styled("Loading last week...", theme::style(StyleName::Hint));
+        }
+        if self.source.is_none() {
+            return Line::default();
+        }
+        if self.entries.is_empty() {
+            return Line::styled(
+                "Nothing was tracked that day",
+                theme::style(StyleName::Hint),
+            );
+        }
+        let copied = |copied: bool| match copied {
+            true => "copied",
+            false => "left out",
+        };
+        Line::from(format!(
+            "Time: {}   Notes: {}",
+            copied(self.with_time),
+            copied(self.with_notes)
+        ))
+    }
+}
+
+impl Component for CopyDayModal {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
+        self.command_tx = Some(tx);
+        Ok(())
+    }
+
+    fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        match action {
+            Action::Api(ApiAct::Response(ApiResponse::LastWeeksEntries(entries)))
+                if self.is_loading =>
+            {
+                self.is_loading = false;
+                self.set_entries(entries);
+            }
+            // the status bar shows what went wrong
+            Action::Api(ApiAct::Error(_)) => self.is_loading = false,
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn handle_key_events(&mut self, key: KeyEvent) -> Result<Option<Action>> {
+        match self.source {
+            None => self.handle_source_keys(key)?,
+            Some(_) => self.handle_entry_keys(key)?,
+        }
+        Ok(None)
+    }
+
+    fn handle_mouse_events(&mut self, mouse: MouseEvent) -> Result<Option<Action>> {
+        match self.tooltip_bar.clicked_key(mouse) {
+            Some(key) => self.handle_key_events(key),
+            None => Ok(None),
+        }
+    }
+
+    fn draw(&mut self, f: &mut Frame<'_>, area: Rect) -> Result<()> {
+        if !self.is_active {
+            return Ok(());
+        }
+
+        let modal_area = area.inner(&Margin {
+            horizontal: (area.width as f32 * 0.1) as u16,
+            vertical: (area.height as f32 * 0.1) as u16,
+        });
+        f.render_widget(Clear, modal_area);
+
+        let title = match self.source {
+            None => format!("Copy into {:?} from", self.target_day),
+            Some(source) => format!(
+                "Copy into {:?} from {}",
+                self.target_day,
+                self.source_label(source)
+            ),
+        };
+        let block = Block::default()
+            .title(title)
+            .borders(Borders::ALL)
+            .border_style(theme::style(StyleName::ModalBorder));
+        let inner_area = block.inner(modal_area);
+        f.render_widget(block, modal_area);
+
+        let layout = Layout::new(
+            Direction::Vertical,
+            [
+                Constraint::Min(0),
+                Constraint::Length(1),
+                Constraint::Length(3),
+            ],
+        )
+        .split(inner_area);
+
+        let items: Vec<ListItem> = match self.source {
+            None => self
+                .sources()
+                .into_iter()
+                .map(|source| ListItem::new(self.source_label(source)))
+                .collect(),
+            Some(_) => self
+                .entries
+                .iter()
+                .zip(&self.picked)
+                .map(|(entry, picked)| self.entry_item(entry, *picked))
+                .collect(),
+        };
+        let (state, tooltips): (&mut ListState, &[&str]) = match self.source {
+            None => (&mut self.source_state, &["Pick [Enter]", "Close [Esc]"]),
+            Some(_) => (
+                &mut self.entry_state,
+                &[
+                    "Copy [Enter]",
+                    "Pick [space]",
+                    "All [a]",
+                    "Time [t]",
+                    "Notes [n]",
+                    "Back [Esc]",
+                ],
+            ),
+        };
+        let list = List::new(items)
+            .highlight_style(theme::style(StyleName::ListHighlight))
+            .highlight_symbol(">");
+        f.render_stateful_widget(list, layout[0], state);
+
+        f.render_widget(
+            Paragraph::new(self.status_line()).alignment(Alignment::Center),
+            layout[1],
+        );
+        self.tooltip_bar.draw(f, layout[2], tooltips);
+
+        Ok(())
+    }
+}
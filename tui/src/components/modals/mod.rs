@@ -1,3 +1,8 @@
 pub mod charge_code_picker;
+pub mod confirm_modal;
+pub mod copy_day_modal;
+pub mod quick_add_modal;
+pub mod split_modal;
 pub mod time_edit_modal;
+pub mod time_range_modal;
 pub mod swap_time_modal;
@@ -0,0 +1,176 @@
+use crate::components::component_utils::TooltipBar;
+use crate::components::time_entry::time_utils::format_hours_minutes;
+use crate::components::Component;
+use crate::theme::{self, StyleName};
+use crate::{
+    action::{Action, TTAct},
+    api_client::ApiRequest,
+};
+use chrono::Utc;
+use color_eyre::eyre::Result;
+use crossterm::event::{KeyCode, KeyEvent, MouseEvent};
+use ratatui::{
+    prelude::*,
+    widgets::{Block, Borders, Clear, Paragraph},
+};
+use shared_lib::{
+    models::{charge_code::ChargeCode, quick_entry::QuickEntry},
+    utils::{quick_add::parse_quick_entry, time_zone::user_time_zone},
+};
+use tokio::sync::mpsc::UnboundedSender;
+
+/// Adds a whole entry from one typed line, like `1h15 ops "patching servers" yesterday`
+#[derive(Default)]
+pub struct QuickAddModal {
+    pub is_active: bool,
+    input: String,
+    charge_codes: Vec<ChargeCode>,
+    error: Option<String>,
+    command_tx: Option<UnboundedSender<Action>>,
+    tooltip_bar: TooltipBar,
+}
+
+impl QuickAddModal {
+    pub fn open(&mut self, charge_codes: &[ChargeCode]) {
+        self.input.clear();
+        self.charge_codes = charge_codes.to_vec();
+        self.error = None;
+        self.is_active = true;
+    }
+
+    fn parsed(&self) -> Result<QuickEntry, String> {
+        parse_quick_entry(
+            &self.input,
+            &self.charge_codes,
+            user_time_zone(),
+            Utc::now(),
+        )
+    }
+
+    fn submit(&mut self) -> Result<()> {
+        let entry = match self.parsed() {
+            Ok(entry) => entry,
+            Err(error) => {
+                self.error = Some(error);
+                return Ok(());
+            }
+        };
+        if let Some(tx) = &self.command_tx {
+            // show the day the entry went on
+            tx.send(Action::TT(TTAct::ChangeDay(entry.day)))?;
+            tx.send(Action::api_request_action(ApiRequest::QuickAdd { entry }))?;
+        }
+        self.is_active = false;
+        Ok(())
+    }
+
+    // the entry as it'll be added, or a hint while there's nothing sensible yet
+    fn preview(&self) -> Line<'static> {
+        if let Some(error) = &self.error {
+            return Line::styled(error.clone(), theme::style(StyleName::Error));
+        }
+        match self.parsed() {
+            Ok(entry) => {
+                let alias = entry
+                    .charge_code
+                    .as_ref()
+                    .map_or("(no charge code)".to_string(), |code| code.alias.clone());
+                let mut spans = vec![
+                    Span::raw(format!("{:?}  ", entry.day)),
+                    Span::styled(
+                        format_hours_minutes(&entry.millis()),
+                        theme::style(StyleName::Digit),
+                    ),
+                    Span::raw(format!("  {}", alias)),
+                ];
+                if !entry.note.is_empty() {
+                    spans.push(Span::styled(
+                        format!("  {}", entry.note),
+                        theme::style(StyleName::Notes),
+                    ));
+                }
+                Line::from(spans)
+            }
+            Err(_) => Line::styled(
+                r#"like 1h15 ops "patching servers" yesterday or 10:00-11:30 design review"#,
+                theme::style(StyleName::Hint),
+            ),
+        }
+    }
+}
+
+impl Component for QuickAddModal {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
+        self.command_tx = Some(tx);
+        Ok(())
+    }
+
+    fn handle_key_events(&mut self, key: KeyEvent) -> Result<Option<Action>> {
+        match key.code {
+            KeyCode::Esc => self.is_active = false,
+            KeyCode::Enter => self.submit()?,
+            KeyCode::Backspace => {
+                self.input.pop();
+                self.error = None;
+            }
+            KeyCode::Char(c) => {
+                self.input.push(c);
+                self.error = None;
+            }
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn handle_mouse_events(&mut self, mouse: MouseEvent) -> Result<Option<Action>> {
+        match self.tooltip_bar.clicked_key(mouse) {
+            Some(key) => self.handle_key_events(key),
+            None => Ok(None),
+        }
+    }
+
+    fn draw(&mut self, f: &mut Frame<'_>, area: Rect) -> Result<()> {
+        if !self.is_active {
+            return Ok(());
+        }
+
+        let modal_area = area.inner(&Margin {
+            horizontal: (area.width as f32 * 0.1) as u16,
+            vertical: area.height.saturating_sub(9) / 2,
+        });
+        f.render_widget(Clear, modal_area);
+
+        let block = Block::default()
+            .title("Quick Add")
+            .borders(Borders::ALL)
+            .border_style(theme::style(StyleName::ModalBorder));
+        let inner_area = block.inner(modal_area);
+        f.render_widget(block, modal_area);
+
+        let layout = Layout::new(
+            Direction::Vertical,
+            [
+                Constraint::Length(3),
+                Constraint::Length(1),
+                Constraint::Min(0),
+                Constraint::Length(3),
+            ],
+        )
+        .split(inner_area);
+
+        let input = Paragraph::new(format!("{}_", self.input)).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(theme::style(StyleName::FocusedInput)),
+        );
+        f.render_widget(input, layout[0]);
+        f.render_widget(
+            Paragraph::new(self.preview()).alignment(Alignment::Center),
+            layout[1],
+        );
+        self.tooltip_bar
+            .draw(f, layout[3], &["Add [Enter]", "Back [Esc]"]);
+
+        Ok(())
+    }
+}
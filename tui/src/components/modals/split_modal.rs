@@ -0,0 +1,189 @@
+use crate::components::component_utils::TooltipBar;
+use crate::components::time_entry::time_utils::format_hours_minutes;
+use crate::components::Component;
+use crate::theme::{self, StyleName};
+use crate::{action::Action, api_client::ApiRequest::SplitEntry};
+use color_eyre::eyre::Result;
+use crossterm::event::{KeyCode, KeyEvent, MouseEvent};
+use ratatui::layout::{Alignment, Constraint, Direction, Layout};
+use ratatui::text::Line;
+use ratatui::{
+    layout::{Margin, Rect},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+use shared_lib::models::{
+    split::{NoteSplit, SplitAmount, SplitRequest},
+    time_entry::TimeEntryVM,
+};
+use tokio::sync::mpsc::UnboundedSender;
+
+/// Moves some of an entry's time, by duration or percentage, onto a new entry
+#[derive(Default)]
+pub struct SplitModal {
+    amount: String,
+    note: NoteSplit,
+    entry_id: i32,
+    total_time: i64,
+    is_running: bool,
+    has_note: bool,
+    error: Option<String>,
+    pub is_active: bool,
+    command_tx: Option<UnboundedSender<Action>>,
+    tooltip_bar: TooltipBar,
+}
+
+impl SplitModal {
+    pub fn open(&mut self, entry: &TimeEntryVM) {
+        self.amount = "50%".to_string();
+        self.note = NoteSplit::default();
+        self.entry_id = entry.id;
+        self.total_time = entry.total_time;
+        self.is_running = entry.is_active;
+        self.has_note = !entry.note.trim().is_empty();
+        self.error = None;
+        self.is_active = true;
+    }
+
+    // the typed amount and how much that moves, or why the split can't happen
+    fn amount(&self) -> Result<(SplitAmount, i64), String> {
+        if self.is_running {
+            return Err("Pause the timer before splitting the entry".to_string());
+        }
+        let amount = SplitAmount::parse(&self.amount)?;
+        Ok((amount, amount.moved_millis(self.total_time)?))
+    }
+
+    fn submit(&mut self) -> Result<()> {
+        let amount = match self.amount() {
+            Ok((amount, _)) => amount,
+            Err(error) => {
+                self.error = Some(error);
+                return Ok(());
+            }
+        };
+        if let Some(tx) = &self.command_tx {
+            let request = SplitRequest {
+                amount,
+                charge_code_id: None,
+                note: self.note,
+            };
+            tx.send(Action::api_request_action(SplitEntry {
+                id: self.entry_id,
+                request,
+            }))?;
+        }
+        self.is_active = false;
+        Ok(())
+    }
+
+    fn toggle_note(&mut self) {
+        self.note = match self.note {
+            NoteSplit::Copy => NoteSplit::Divide,
+            NoteSplit::Divide => NoteSplit::Copy,
+        };
+    }
+}
+
+impl Component for SplitModal {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
+        self.command_tx = Some(tx);
+        Ok(())
+    }
+
+    fn handle_key_events(&mut self, key: KeyEvent) -> Result<Option<Action>> {
+        match key.code {
+            KeyCode::Esc => self.is_active = false,
+            KeyCode::Enter => self.submit()?,
+            KeyCode::Tab | KeyCode::BackTab => self.toggle_note(),
+            KeyCode::Backspace => {
+                self.amount.pop();
+                self.error = None;
+            }
+            KeyCode::Char(c) if c.is_ascii_alphanumeric() || ":.% ".contains(c) => {
+                self.amount.push(c);
+                self.error = None;
+            }
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn handle_mouse_events(&mut self, mouse: MouseEvent) -> Result<Option<Action>> {
+        match self.tooltip_bar.clicked_key(mouse) {
+            Some(key) => self.handle_key_events(key),
+            None => Ok(None),
+        }
+    }
+
+    fn draw(&mut self, f: &mut Frame<'_>, area: Rect) -> Result<()> {
+        if !self.is_active {
+            return Ok(());
+        }
+
+        let modal_area = area.inner(&Margin {
+            horizontal: (area.width as f32 * 0.2) as u16,
+            vertical: (area.height as f32 * 0.2) as u16,
+        });
+        f.render_widget(Clear, modal_area);
+
+        let block = Block::default()
+            .title(format!("Split {}", format_hours_minutes(&self.total_time)))
+            .borders(Borders::ALL)
+            .border_style(theme::style(StyleName::ModalBorder));
+        let inner_area = block.inner(modal_area);
+        f.render_widget(block, modal_area);
+
+        let layout = Layout::new(
+            Direction::Vertical,
+            [
+                Constraint::Length(3),
+                Constraint::Length(1),
+                Constraint::Length(1),
+                Constraint::Min(0),
+                Constraint::Length(3),
+            ],
+        )
+        .split(inner_area);
+
+        let amount = Paragraph::new(format!("{}_", self.amount))
+            .alignment(Alignment::Center)
+            .block(
+                Block::default()
+                    .title("Move to a new entry")
+                    .borders(Borders::ALL)
+                    .border_style(theme::style(StyleName::FocusedInput)),
+            );
+        f.render_widget(amount, layout[0]);
+
+        // what each side ends up with, or why it can't be split yet
+        let message = match (&self.error, self.amount()) {
+            (Some(error), _) => Line::styled(error.clone(), theme::style(StyleName::Error)),
+            (None, Ok((_, moved))) => Line::from(format!(
+                "keeps {}, the new entry gets {}",
+                format_hours_minutes(&(self.total_time - moved)),
+                format_hours_minutes(&moved)
+            )),
+            (None, Err(_)) => {
+                Line::styled("like 1h30m, 0:45 or 40%", theme::style(StyleName::Hint))
+            }
+        };
+        f.render_widget(
+            Paragraph::new(message).alignment(Alignment::Center),
+            layout[1],
+        );
+
+        if self.has_note {
+            let note = match self.note {
+                NoteSplit::Copy => "Note: copied to both",
+                NoteSplit::Divide => "Note: lines divided by time",
+            };
+            f.render_widget(Paragraph::new(note).alignment(Alignment::Center), layout[2]);
+        }
+
+        self.tooltip_bar
+            .draw(f, layout[4], &["Split [Enter]", "Note [Tab]", "Back [Esc]"]);
+
+        Ok(())
+    }
+}
@@ -2,14 +2,14 @@ use super::{swap_entry_list::SwapEntryList, swap_time_edit_timer::SwapTimeEdit};
 use crate::{
     action::Action,
     api_client::ApiRequest::AddTime,
-    components::{component_utils::draw_tooltip_bar, time_entry::entry::TimeEntry, Component},
+    components::{component_utils::TooltipBar, time_entry::entry::TimeEntry, Component},
+    theme::{self, StyleName},
     tui::Frame,
 };
 use color_eyre::eyre::Result;
-use crossterm::event::{KeyCode, KeyEvent};
+use crossterm::event::{KeyCode, KeyEvent, MouseEvent};
 use ratatui::{
     layout::{Constraint, Direction, Layout, Margin, Rect},
-    style::{Color, Style},
     widgets::{Block, Borders, Clear},
 };
 use tokio::sync::mpsc::UnboundedSender;
@@ -20,6 +20,7 @@ pub struct SwapTimeModal {
     pub time_edit_component: SwapTimeEdit,
     pub is_active: bool,
     pub command_tx: Option<UnboundedSender<Action>>,
+    tooltip_bar: TooltipBar,
     swap_from_id: i32,
     active_area: ActiveArea,
     // other_time_entries: Vec<TimeEntry>,
@@ -127,7 +128,7 @@ impl Component for SwapTimeModal {
         let block = Block::default()
             .title("Swap Time")
             .borders(Borders::ALL)
-            .border_style(Style::default().fg(Color::Yellow));
+            .border_style(theme::style(StyleName::ModalBorder));
         f.render_widget(block, modal_area);
 
         // Define areas for the time entry and list components
@@ -152,7 +153,7 @@ impl Component for SwapTimeModal {
             "Back [Esc]",
             "Switch Focus [Tab/Backtab]",
         ];
-        draw_tooltip_bar(f, chunks[2], &tooltips);
+        self.tooltip_bar.draw(f, chunks[2], &tooltips);
 
         Ok(())
     }
@@ -198,4 +199,11 @@ impl Component for SwapTimeModal {
 
         Ok(None)
     }
+
+    fn handle_mouse_events(&mut self, mouse: MouseEvent) -> Result<Option<Action>> {
+        match self.tooltip_bar.clicked_key(mouse) {
+            Some(key) => self.handle_key_events(key),
+            None => Ok(None),
+        }
+    }
 }
@@ -1,12 +1,13 @@
 use crate::{
     action::Action,
     components::{time_entry::entry::TimeEntry, Component},
+    theme::{self, StyleName},
 };
 use color_eyre::eyre::Result;
 use crossterm::event::{KeyCode, KeyEvent};
 use ratatui::{
     layout::Rect,
-    style::{Color, Style},
+    style::Style,
     widgets::{Block, Borders, List, ListItem, ListState},
     Frame,
 };
@@ -70,8 +71,8 @@ impl SwapEntryList {
 
     fn get_activity_style(&self) -> Style {
         match self.is_active {
-            true => Style::default().fg(Color::Yellow),
-            false => Style::default().fg(Color::DarkGray),
+            true => theme::style(StyleName::ActivePanel),
+            false => theme::style(StyleName::Panel),
         }
     }
 }
@@ -84,7 +85,7 @@ impl Component for SwapEntryList {
             .border_style(self.get_activity_style());
 
         // Map the TimeEntry data to ListItem
-        let item_style = Style::default().fg(Color::DarkGray);
+        let item_style = theme::style(StyleName::ListItem);
         let list_items: Vec<ListItem> = self
             .time_entries
             .iter()
@@ -106,7 +107,7 @@ impl Component for SwapEntryList {
         let list = List::new(list_items)
             .block(list_block)
             .style(self.get_activity_style())
-            .highlight_style(Style::default().fg(Color::White))
+            .highlight_style(theme::style(StyleName::ListHighlight))
             .repeat_highlight_symbol(true)
             .highlight_symbol(">");
 
@@ -1,9 +1,13 @@
-use crate::{action::Action, components::Component};
+use crate::{
+    action::Action,
+    components::Component,
+    theme::{self, StyleName},
+};
 use color_eyre::eyre::Result;
 use crossterm::event::{KeyCode, KeyEvent};
 use ratatui::{
     layout::{Alignment, Constraint, Direction, Layout, Rect},
-    style::{Color, Style, Stylize},
+    style::Style,
     text::{Line, Span},
     widgets::{Block, Borders, Paragraph},
     Frame,
@@ -53,8 +57,8 @@ impl SwapTimeEdit {
 
     fn get_activity_style(&self) -> Style {
         match self.is_active {
-            true => Style::default().fg(Color::Yellow),
-            false => Style::default().fg(Color::DarkGray),
+            true => theme::style(StyleName::ActivePanel),
+            false => theme::style(StyleName::Panel),
         }
     }
 
@@ -162,8 +166,8 @@ impl SwapTimeEdit {
         let mut spans = Vec::new();
         for (i, digit) in value.chars().enumerate() {
             let style = match self.is_active_digit(i, &unit) {
-                true => Style::default().dim().fg(Color::Black).bg(Color::Cyan),
-                false => Style::default().dim().fg(Color::White),
+                true => theme::style(StyleName::ActiveDigit),
+                false => theme::style(StyleName::Digit),
             };
 
             // TODO: use this crate: https://docs.rs/tui-big-text/0.3.2/tui_big_text/
@@ -172,8 +176,8 @@ impl SwapTimeEdit {
         }
 
         let border_style = match self.is_component_active(&unit) {
-            true => Style::default().fg(Color::Cyan),
-            false => Style::default().fg(Color::White),
+            true => theme::style(StyleName::FocusedInput),
+            false => theme::style(StyleName::Input),
         };
 
         let paragraph = Paragraph::new(Line::from(spans))
@@ -1,14 +1,13 @@
-use crate::components::component_utils::draw_tooltip_bar;
+use crate::components::component_utils::TooltipBar;
 use crate::components::Component;
+use crate::theme::{self, StyleName};
 use crate::{action::Action, api_client::ApiRequest::SetTime};
 use color_eyre::eyre::Result;
-use crossterm::event::{KeyCode, KeyEvent};
+use crossterm::event::{KeyCode, KeyEvent, MouseEvent};
 use ratatui::layout::{Alignment, Constraint, Direction, Layout};
-use ratatui::style::Stylize;
 use ratatui::text::{Line, Span};
 use ratatui::{
     layout::{Margin, Rect},
-    style::{Color, Style},
     widgets::{Block, Borders, Clear, Paragraph},
     Frame,
 };
@@ -20,6 +19,7 @@ pub struct TimeEditModal {
     pub is_active: bool,
     pub entry_id: i32,
     command_tx: Option<UnboundedSender<Action>>,
+    tooltip_bar: TooltipBar,
 }
 
 impl Default for TimeEditModal {
@@ -30,6 +30,7 @@ impl Default for TimeEditModal {
             is_active: false,
             entry_id: -1,
             command_tx: None,
+            tooltip_bar: TooltipBar::default(),
         }
     }
 }
@@ -170,8 +171,8 @@ impl TimeEditModal {
         let mut spans = Vec::new();
         for (i, digit) in value.chars().enumerate() {
             let style = match self.is_active_digit(i, &unit) {
-                true => Style::default().dim().fg(Color::Black).bg(Color::Cyan),
-                false => Style::default().dim().fg(Color::White),
+                true => theme::style(StyleName::ActiveDigit),
+                false => theme::style(StyleName::Digit),
             };
 
             // TODO: use this crate: https://docs.rs/tui-big-text/0.3.2/tui_big_text/
@@ -180,8 +181,8 @@ impl TimeEditModal {
         }
 
         let border_style = match self.is_component_active(&unit) {
-            true => Style::default().fg(Color::Cyan),
-            false => Style::default().fg(Color::White),
+            true => theme::style(StyleName::FocusedInput),
+            false => theme::style(StyleName::Input),
         };
 
         let paragraph = Paragraph::new(Line::from(spans))
@@ -218,7 +219,7 @@ impl Component for TimeEditModal {
             let block = Block::default()
                 .title("Edit Time")
                 .borders(Borders::ALL)
-                .border_style(Style::default().fg(Color::Yellow));
+                .border_style(theme::style(StyleName::ModalBorder));
             f.render_widget(block, modal_area);
 
             // Define the area for the bottom bar within the modal
@@ -231,7 +232,7 @@ impl Component for TimeEditModal {
 
             // Draw the tooltips in the bottom bar
             let tooltips = vec!["Set Time [Enter]", "Back [Esc]"];
-            draw_tooltip_bar(f, bottom_bar_area, &tooltips);
+            self.tooltip_bar.draw(f, bottom_bar_area, &tooltips);
 
             let horizontal_margin = (modal_area.width as f32 * 0.1) as u16;
             let vertical_margin = (modal_area.height as f32 * 0.2) as u16;
@@ -299,6 +300,13 @@ impl Component for TimeEditModal {
         Ok(None)
     }
 
+    fn handle_mouse_events(&mut self, mouse: MouseEvent) -> Result<Option<Action>> {
+        match self.tooltip_bar.clicked_key(mouse) {
+            Some(key) => self.handle_key_events(key),
+            None => Ok(None),
+        }
+    }
+
     fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
         self.command_tx = Some(tx.clone());
 
@@ -0,0 +1,214 @@
+use crate::components::component_utils::TooltipBar;
+use crate::components::time_entry::time_utils::format_hours_minutes;
+use crate::components::Component;
+use crate::theme::{self, StyleName};
+use crate::{
+    action::Action,
+    api_client::ApiRequest::{CreateTimeRange, SetTimeRange},
+};
+use chrono::{DateTime, Utc};
+use color_eyre::eyre::Result;
+use crossterm::event::{KeyCode, KeyEvent, MouseEvent};
+use ratatui::layout::{Alignment, Constraint, Direction, Layout};
+use ratatui::text::Line;
+use ratatui::{
+    layout::{Margin, Rect},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+use shared_lib::{
+    models::{day::Day, time_segment::TimeRange},
+    utils::{clock::clock_range, time_zone::user_time_zone},
+};
+use tokio::sync::mpsc::UnboundedSender;
+
+/// Sets an entry's time from clock times like "9:00" to "10:30", or adds a new entry that way
+#[derive(Default)]
+pub struct TimeRangeModal {
+    start: String,
+    end: String,
+    is_editing_end: bool,
+    // `None` makes a new entry
+    entry_id: Option<i32>,
+    day: Day,
+    error: Option<String>,
+    pub is_active: bool,
+    command_tx: Option<UnboundedSender<Action>>,
+    tooltip_bar: TooltipBar,
+}
+
+impl TimeRangeModal {
+    /// Starts out showing `range`, usually the last time the entry was worked
+    pub fn open(&mut self, entry_id: Option<i32>, day: Day, range: Option<TimeRange>) {
+        let format = |time: DateTime<Utc>| time.with_timezone(&user_time_zone()).format("%H:%M");
+        self.start = range.map_or(String::new(), |range| format(range.start).to_string());
+        self.end = range.map_or(String::new(), |range| format(range.end).to_string());
+        self.is_editing_end = false;
+        self.entry_id = entry_id;
+        self.day = day;
+        self.error = None;
+        self.is_active = true;
+    }
+
+    fn range(&self) -> Result<TimeRange, String> {
+        clock_range(
+            self.day,
+            &self.start,
+            &self.end,
+            user_time_zone(),
+            Utc::now(),
+        )
+    }
+
+    fn focused_input(&mut self) -> &mut String {
+        match self.is_editing_end {
+            true => &mut self.end,
+            false => &mut self.start,
+        }
+    }
+
+    fn submit(&mut self) -> Result<()> {
+        let range = match self.range() {
+            Ok(range) => range,
+            Err(error) => {
+                self.error = Some(error);
+                return Ok(());
+            }
+        };
+        if let Some(tx) = &self.command_tx {
+            let request = match self.entry_id {
+                Some(id) => SetTimeRange { id, range },
+                None => CreateTimeRange {
+                    day: self.day.into(),
+                    range,
+                },
+            };
+            tx.send(Action::api_request_action(request))?;
+        }
+        self.is_active = false;
+        Ok(())
+    }
+
+    fn draw_input(
+        &self,
+        f: &mut Frame<'_>,
+        area: Rect,
+        title: &str,
+        value: &str,
+        is_focused: bool,
+    ) {
+        let border_style = match is_focused {
+            true => theme::style(StyleName::FocusedInput),
+            false => theme::style(StyleName::Input),
+        };
+        let text = match is_focused {
+            true => format!("{}_", value),
+            false => value.to_string(),
+        };
+        let paragraph = Paragraph::new(text).alignment(Alignment::Center).block(
+            Block::default()
+                .title(title)
+                .borders(Borders::ALL)
+                .border_style(border_style),
+        );
+        f.render_widget(paragraph, area);
+    }
+}
+
+impl Component for TimeRangeModal {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
+        self.command_tx = Some(tx);
+        Ok(())
+    }
+
+    fn handle_key_events(&mut self, key: KeyEvent) -> Result<Option<Action>> {
+        match key.code {
+            KeyCode::Esc => self.is_active = false,
+            KeyCode::Enter => self.submit()?,
+            KeyCode::Tab | KeyCode::BackTab | KeyCode::Up | KeyCode::Down => {
+                self.is_editing_end = !self.is_editing_end
+            }
+            KeyCode::Backspace => {
+                self.focused_input().pop();
+                self.error = None;
+            }
+            KeyCode::Char(c) if c.is_ascii_alphanumeric() || c == ':' || c == ' ' => {
+                self.focused_input().push(c);
+                self.error = None;
+            }
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn handle_mouse_events(&mut self, mouse: MouseEvent) -> Result<Option<Action>> {
+        match self.tooltip_bar.clicked_key(mouse) {
+            Some(key) => self.handle_key_events(key),
+            None => Ok(None),
+        }
+    }
+
+    fn draw(&mut self, f: &mut Frame<'_>, area: Rect) -> Result<()> {
+        if !self.is_active {
+            return Ok(());
+        }
+
+        let modal_area = area.inner(&Margin {
+            horizontal: (area.width as f32 * 0.2) as u16,
+            vertical: (area.height as f32 * 0.2) as u16,
+        });
+        f.render_widget(Clear, modal_area);
+
+        let title = match self.entry_id {
+            Some(_) => format!("Start and End on {:?}", self.day),
+            None => format!("New Entry on {:?}", self.day),
+        };
+        let block = Block::default()
+            .title(title)
+            .borders(Borders::ALL)
+            .border_style(theme::style(StyleName::ModalBorder));
+        let inner_area = block.inner(modal_area);
+        f.render_widget(block, modal_area);
+
+        let layout = Layout::new(
+            Direction::Vertical,
+            [
+                Constraint::Length(3),
+                Constraint::Length(1),
+                Constraint::Min(0),
+                Constraint::Length(3),
+            ],
+        )
+        .split(inner_area);
+        let inputs = Layout::new(
+            Direction::Horizontal,
+            [Constraint::Percentage(50), Constraint::Percentage(50)],
+        )
+        .split(layout[0]);
+        self.draw_input(f, inputs[0], "Start", &self.start, !self.is_editing_end);
+        self.draw_input(f, inputs[1], "End", &self.end, self.is_editing_end);
+
+        // what the entry's total will be, or why it can't be saved yet
+        let message = match (&self.error, self.range()) {
+            (Some(error), _) => Line::styled(error.clone(), theme::style(StyleName::Error)),
+            (None, Ok(range)) => {
+                Line::from(format!("{} total", format_hours_minutes(&range.millis())))
+            }
+            (None, Err(_)) => {
+                Line::styled("like 9:00, 0930 or 1:30pm", theme::style(StyleName::Hint))
+            }
+        };
+        f.render_widget(
+            Paragraph::new(message).alignment(Alignment::Center),
+            layout[1],
+        );
+
+        self.tooltip_bar.draw(
+            f,
+            layout[3],
+            &["Save [Enter]", "Start/End [Tab]", "Back [Esc]"],
+        );
+
+        Ok(())
+    }
+}
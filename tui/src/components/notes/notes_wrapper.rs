@@ -33,6 +33,18 @@ impl NotesWrapper<'_> {
         }
     }
 
+    pub fn start_editing(&mut self) {
+        match self.use_vim_mode {
+            true => self.vim_notes.start_editing(),
+            false => self.regular_notes.start_editing(),
+        }
+    }
+
+    pub fn set_edit_key(&mut self, key: String) {
+        self.vim_notes.edit_key = key.clone();
+        self.regular_notes.edit_key = key;
+    }
+
     pub fn is_edit_mode(&self) -> bool {
         match self.use_vim_mode {
             true => self.vim_notes.is_edit_mode,
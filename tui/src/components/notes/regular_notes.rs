@@ -1,10 +1,10 @@
 use crate::api_client::ApiRequest::UpdateEntryNote;
 use crate::components::Component;
+use crate::theme::{self, StyleName};
 use crate::{action::Action, tui::Frame};
 use color_eyre::eyre::Result;
 use crossterm::event::{KeyCode, KeyEvent};
 use ratatui::layout::Rect;
-use ratatui::style::{Color, Style};
 use ratatui::widgets::{block::Block, Borders};
 use tokio::sync::mpsc::UnboundedSender;
 use tui_textarea::TextArea;
@@ -13,6 +13,8 @@ use tui_textarea::TextArea;
 pub struct RegularNotes<'a> {
     pub editor: TextArea<'a>,
     pub is_edit_mode: bool,
+    // whatever EditNote is bound to, for the title
+    pub edit_key: String,
     entry_id: i32,
     command_tx: Option<UnboundedSender<Action>>,
 }
@@ -33,14 +35,18 @@ impl RegularNotes<'_> {
     pub fn get_text(&self) -> String {
         self.editor.lines().join("\n")
     }
+
+    pub fn start_editing(&mut self) {
+        self.is_edit_mode = true;
+    }
 }
 
 impl Component for RegularNotes<'_> {
     fn draw(&mut self, f: &mut Frame<'_>, area: Rect) -> Result<()> {
         let title = if self.is_edit_mode {
-            "'ESC' to stop editing"
+            "'ESC' to stop editing".to_string()
         } else {
-            "'/' to edit"
+            format!("'{}' to edit", self.edit_key)
         };
 
         self.editor.set_block(
@@ -48,8 +54,8 @@ impl Component for RegularNotes<'_> {
                 .borders(Borders::ALL)
                 .title(title)
                 .border_style(match self.is_edit_mode {
-                    true => Style::default().fg(Color::Yellow),
-                    false => Style::default().fg(Color::White),
+                    true => theme::style(StyleName::NotesEditing),
+                    false => theme::style(StyleName::Notes),
                 }),
         );
 
@@ -78,8 +84,6 @@ impl Component for RegularNotes<'_> {
                     self.editor.input(tui_key);
                 }
             }
-        } else if key.code == KeyCode::Char('/') {
-            self.is_edit_mode = true;
         }
 
         Ok(None)
@@ -1,5 +1,8 @@
-use ratatui::style::{Color, Modifier, Style};
+use crate::shared::clipboard::copy_to_clipboard;
+use crate::theme::{self, StyleName};
+use ratatui::style::{Modifier, Style};
 use ratatui::widgets::{Block, Borders};
+use std::collections::HashMap;
 use std::fmt;
 use tui_textarea::{CursorMove, Input, Key, Scrolling, TextArea};
 
@@ -10,6 +13,8 @@ pub enum Mode {
     Insert,
     Visual,
     Operator(char),
+    // typing a search after / or an ex command after :
+    Command(char),
 }
 
 impl Mode {
@@ -19,19 +24,29 @@ impl Mode {
             Self::Insert => "type Esc to back to normal mode",
             Self::Visual => "type y to yank, type d to delete, type Esc to back to normal mode",
             Self::Operator(_) => "move cursor to apply operator",
+            Self::Command(_) => "type Enter to run, type Esc to cancel",
         };
         let title = format!("{} MODE ({})", self, help);
         Block::default().borders(Borders::ALL).title(title)
     }
 
+    pub fn border_style(&self) -> Style {
+        theme::style(match self {
+            Self::Normal => StyleName::VimNormal,
+            Self::Insert => StyleName::VimInsert,
+            Self::Visual => StyleName::VimVisual,
+            Self::Operator(_) => StyleName::VimOperator,
+            Self::Command(_) => StyleName::VimCommand,
+        })
+    }
+
+    // a plain block in normal mode, the mode's color otherwise
     pub fn cursor_style(&self) -> Style {
-        let color = match self {
-            Self::Normal => Color::Reset,
-            Self::Insert => Color::LightBlue,
-            Self::Visual => Color::LightYellow,
-            Self::Operator(_) => Color::LightGreen,
+        let style = match self {
+            Self::Normal => Style::default(),
+            _ => self.border_style(),
         };
-        Style::default().fg(color).add_modifier(Modifier::REVERSED)
+        style.add_modifier(Modifier::REVERSED)
     }
 }
 
@@ -42,343 +57,987 @@ impl fmt::Display for Mode {
             Self::Insert => write!(f, "INSERT"),
             Self::Visual => write!(f, "VISUAL"),
             Self::Operator(c) => write!(f, "OPERATOR({})", c),
+            Self::Command(_) => write!(f, "COMMAND"),
         }
     }
 }
 
 // How the Vim emulation state transitions
+#[derive(Debug, Clone, PartialEq)]
 pub enum Transition {
     Nop,
     Mode(Mode),
     Pending(Input),
+    // stop editing and keep the note
     Quit,
+    // :w, keep editing
+    Save,
+    // :q, drop what changed since the last save
+    Discard,
 }
 
+// the text and cursor to go back to with u
+type Snapshot = (Vec<String>, (usize, usize));
+
 // State of Vim emulation
 #[derive(Debug, Clone, Default)]
 pub struct Vim {
     pub mode: Mode,
     pub pending: Input, // Pending input to handle a sequence with two keys like gg
+    // typed before a command, like the 3 in 3dw
+    count: Option<usize>,
+    // typed before an operator, so 2d3w deletes six words
+    operator_count: usize,
+    // picked with "a for the next yank, delete or paste
+    register: Option<char>,
+    registers: HashMap<char, String>,
+    // what's been typed after / or :
+    pub command_line: String,
+    last_search: Option<String>,
+    // the keys of the command being typed and of the last one that changed the text, for .
+    keys: Vec<Input>,
+    last_change: Vec<Input>,
+    replaying: bool,
+    // the text before the command being typed
+    before: Option<Snapshot>,
+    undo_stack: Vec<Snapshot>,
+    redo_stack: Vec<Snapshot>,
 }
 
-impl Vim {
-    pub fn new(mode: Mode) -> Self {
-        Self {
-            mode,
-            pending: Input::default(),
+fn char_input(c: char) -> Input {
+    Input {
+        key: Key::Char(c),
+        ..Input::default()
+    }
+}
+
+fn snapshot(textarea: &TextArea<'_>) -> Snapshot {
+    (textarea.lines().to_vec(), textarea.cursor())
+}
+
+fn jump(textarea: &mut TextArea<'_>, (row, col): (usize, usize)) {
+    textarea.move_cursor(CursorMove::Jump(row as u16, col as u16));
+}
+
+// a new textarea has no history of its own, but the yank and search should survive undo
+fn restore(textarea: &mut TextArea<'_>, (lines, cursor): Snapshot) {
+    let yank = textarea.yank_text();
+    let search = textarea
+        .search_pattern()
+        .map(|pattern| pattern.as_str().to_string());
+    *textarea = TextArea::new(lines);
+    textarea.set_yank_text(yank);
+    if let Some(search) = search {
+        let _ = textarea.set_search_pattern(search);
+    }
+    jump(textarea, cursor);
+}
+
+#[derive(PartialEq)]
+enum CharClass {
+    Space,
+    Word,
+    Punctuation,
+}
+
+fn char_class(c: char, big_word: bool) -> CharClass {
+    match c {
+        c if c.is_whitespace() => CharClass::Space,
+        _ if big_word => CharClass::Word,
+        c if c.is_alphanumeric() || c == '_' => CharClass::Word,
+        _ => CharClass::Punctuation,
+    }
+}
+
+fn word_object(chars: &[char], col: usize, around: bool, big_word: bool) -> Option<(usize, usize)> {
+    let class = char_class(*chars.get(col)?, big_word);
+    let same = |c: &char| char_class(*c, big_word) == class;
+    let mut start = col - chars[..col].iter().rev().take_while(|c| same(c)).count();
+    let mut end = col + chars[col..].iter().take_while(|c| same(c)).count();
+    if around {
+        let is_space = |c: &&char| c.is_whitespace();
+        let trailing = chars[end..].iter().take_while(is_space).count();
+        match trailing {
+            0 => start -= chars[..start].iter().rev().take_while(is_space).count(),
+            _ => end += trailing,
         }
     }
+    Some((start, end))
+}
+
+fn quote_object(chars: &[char], col: usize, quote: char, around: bool) -> Option<(usize, usize)> {
+    let quotes: Vec<usize> = (0..chars.len()).filter(|i| chars[*i] == quote).collect();
+    // the pair around the cursor, or the next one along the line like vim
+    let (open, close) = quotes
+        .chunks_exact(2)
+        .map(|pair| (pair[0], pair[1]))
+        .find(|(_, close)| *close >= col)?;
+    match around {
+        true => Some((open, close + 1)),
+        false => Some((open + 1, close)),
+    }
+}
 
-    pub fn with_pending(self, pending: Input) -> Self {
-        Self {
-            mode: self.mode,
-            pending,
+fn bracket_object(
+    chars: &[char],
+    col: usize,
+    (open, close): (char, char),
+    around: bool,
+) -> Option<(usize, usize)> {
+    let mut depth = 0;
+    let start = (0..chars.len().min(col + 1))
+        .rev()
+        .find(|i| match chars[*i] {
+            c if c == close && *i != col => {
+                depth += 1;
+                false
+            }
+            c if c == open => match depth {
+                0 => true,
+                _ => {
+                    depth -= 1;
+                    false
+                }
+            },
+            _ => false,
+        })?;
+    let mut depth = 0;
+    let end = (start + 1..chars.len()).find(|i| match chars[*i] {
+        c if c == open => {
+            depth += 1;
+            false
         }
+        c if c == close => match depth {
+            0 => true,
+            _ => {
+                depth -= 1;
+                false
+            }
+        },
+        _ => false,
+    })?;
+    match around {
+        true => Some((start, end + 1)),
+        false => Some((start + 1, end)),
     }
+}
 
-    pub fn transition(&self, input: Input, textarea: &mut TextArea<'_>) -> Transition {
+/// The columns a text object like `iw` or `a"` covers on the cursor's line, end exclusive.
+/// Objects don't reach across lines.
+fn text_object(line: &str, col: usize, kind: char, object: char) -> Option<(usize, usize)> {
+    let chars: Vec<char> = line.chars().collect();
+    // the cursor can sit just past the end of the line
+    let col = col.min(chars.len().saturating_sub(1));
+    let around = kind == 'a';
+    match object {
+        'w' => word_object(&chars, col, around, false),
+        'W' => word_object(&chars, col, around, true),
+        '"' | '\'' | '`' => quote_object(&chars, col, object, around),
+        '(' | ')' | 'b' => bracket_object(&chars, col, ('(', ')'), around),
+        '[' | ']' => bracket_object(&chars, col, ('[', ']'), around),
+        '{' | '}' | 'B' => bracket_object(&chars, col, ('{', '}'), around),
+        '<' | '>' => bracket_object(&chars, col, ('<', '>'), around),
+        _ => None,
+    }
+}
+
+impl Vim {
+    /// Starts editing in insert mode, as if `i` had been typed so `.` can repeat it.
+    /// Registers and the last change carry over from earlier notes.
+    pub fn start_insert(&mut self, textarea: &TextArea<'_>) {
+        self.mode = Mode::Insert;
+        self.before = Some(snapshot(textarea));
+        self.keys = vec![char_input('i')];
+    }
+
+    /// Forgets the undo history, for when the textarea gets a different note
+    pub fn clear_history(&mut self) {
+        self.before = None;
+        self.keys.clear();
+        self.undo_stack.clear();
+        self.redo_stack.clear();
+    }
+
+    pub fn transition(&mut self, input: Input, textarea: &mut TextArea<'_>) -> Transition {
         if input.key == Key::Null {
             return Transition::Nop;
         }
 
-        match self.mode {
-            Mode::Normal | Mode::Visual | Mode::Operator(_) => {
-                match input {
-                    Input { key: Key::Esc, .. } => return Transition::Quit,
-                    Input {
-                        key: Key::Char('h'),
-                        ..
-                    }
-                    | Input { key: Key::Left, .. } => textarea.move_cursor(CursorMove::Back),
+        if self.before.is_none() {
+            self.before = Some(snapshot(textarea));
+            self.keys.clear();
+        }
+        self.keys.push(input.clone());
 
-                    Input {
-                        key: Key::Char('j'),
-                        ..
-                    }
-                    | Input { key: Key::Down, .. } => textarea.move_cursor(CursorMove::Down),
+        let transition = match self.mode {
+            Mode::Insert => self.insert(input, textarea),
+            Mode::Command(prompt) => self.command_line(prompt, input, textarea),
+            Mode::Normal | Mode::Visual | Mode::Operator(_) => self.normal(input, textarea),
+        };
 
-                    Input {
-                        key: Key::Char('k'),
-                        ..
-                    }
-                    | Input { key: Key::Up, .. } => textarea.move_cursor(CursorMove::Up),
+        if let Transition::Mode(mode) = transition {
+            if mode != self.mode {
+                self.count = None;
+                self.register = match mode {
+                    // the register is for the operator that was just typed
+                    Mode::Operator(_) | Mode::Visual => self.register,
+                    _ => None,
+                };
+            }
+            self.mode = mode;
+        }
+        self.pending = match &transition {
+            Transition::Pending(input) => input.clone(),
+            _ => Input::default(),
+        };
 
-                    Input {
-                        key: Key::Char('l'),
-                        ..
-                    }
-                    | Input {
-                        key: Key::Right, ..
-                    } => textarea.move_cursor(CursorMove::Forward),
-                    Input {
-                        key: Key::Char('w'),
-                        ..
-                    } => textarea.move_cursor(CursorMove::WordForward),
-                    Input {
-                        key: Key::Char('b'),
-                        ctrl: false,
-                        ..
-                    } => textarea.move_cursor(CursorMove::WordBack),
-                    Input {
-                        key: Key::Char('^'),
-                        ..
-                    } => textarea.move_cursor(CursorMove::Head),
-                    Input {
-                        key: Key::Char('$'),
-                        ..
-                    } => textarea.move_cursor(CursorMove::End),
-                    Input {
-                        key: Key::Char('D'),
-                        ..
-                    } => {
-                        textarea.delete_line_by_end();
-                        return Transition::Mode(Mode::Normal);
-                    }
-                    Input {
-                        key: Key::Char('C'),
-                        ..
-                    } => {
-                        textarea.delete_line_by_end();
-                        textarea.cancel_selection();
-                        return Transition::Mode(Mode::Insert);
-                    }
-                    Input {
-                        key: Key::Char('p'),
-                        ..
-                    } => {
-                        textarea.paste();
-                        return Transition::Mode(Mode::Normal);
-                    }
-                    Input {
-                        key: Key::Char('u'),
-                        ctrl: false,
-                        ..
-                    } => {
-                        textarea.undo();
-                        return Transition::Mode(Mode::Normal);
-                    }
-                    Input {
-                        key: Key::Char('r'),
-                        ctrl: true,
-                        ..
-                    } => {
-                        textarea.redo();
-                        return Transition::Mode(Mode::Normal);
-                    }
-                    Input {
-                        key: Key::Char('x'),
-                        ..
-                    } => {
-                        textarea.delete_next_char();
-                        return Transition::Mode(Mode::Normal);
-                    }
-                    Input {
-                        key: Key::Char('i'),
-                        ..
-                    } => {
-                        textarea.cancel_selection();
-                        return Transition::Mode(Mode::Insert);
-                    }
-                    Input {
-                        key: Key::Char('a'),
-                        ..
-                    } => {
-                        textarea.cancel_selection();
-                        textarea.move_cursor(CursorMove::Forward);
-                        return Transition::Mode(Mode::Insert);
-                    }
-                    Input {
-                        key: Key::Char('A'),
-                        ..
-                    } => {
-                        textarea.cancel_selection();
-                        textarea.move_cursor(CursorMove::End);
-                        return Transition::Mode(Mode::Insert);
-                    }
-                    Input {
-                        key: Key::Char('o'),
-                        ..
-                    } => {
-                        textarea.move_cursor(CursorMove::End);
-                        textarea.insert_newline();
-                        return Transition::Mode(Mode::Insert);
-                    }
-                    Input {
-                        key: Key::Char('O'),
-                        ..
-                    } => {
-                        textarea.move_cursor(CursorMove::Head);
-                        textarea.insert_newline();
-                        textarea.move_cursor(CursorMove::Up);
-                        return Transition::Mode(Mode::Insert);
-                    }
-                    Input {
-                        key: Key::Char('I'),
-                        ..
-                    } => {
-                        textarea.cancel_selection();
-                        textarea.move_cursor(CursorMove::Head);
-                        return Transition::Mode(Mode::Insert);
-                    }
-                    Input {
-                        key: Key::Char('q'),
-                        ..
-                    } => return Transition::Quit,
-                    Input {
-                        key: Key::Char('e'),
-                        ctrl: true,
-                        ..
-                    } => textarea.scroll((1, 0)),
-                    Input {
-                        key: Key::Char('y'),
-                        ctrl: true,
-                        ..
-                    } => textarea.scroll((-1, 0)),
-                    Input {
-                        key: Key::Char('d'),
-                        ctrl: true,
-                        ..
-                    } => textarea.scroll(Scrolling::HalfPageDown),
-                    Input {
-                        key: Key::Char('u'),
-                        ctrl: true,
-                        ..
-                    } => textarea.scroll(Scrolling::HalfPageUp),
-                    Input {
-                        key: Key::Char('f'),
-                        ctrl: true,
-                        ..
-                    } => textarea.scroll(Scrolling::PageDown),
-                    Input {
-                        key: Key::Char('b'),
-                        ctrl: true,
-                        ..
-                    } => textarea.scroll(Scrolling::PageUp),
-                    Input {
-                        key: Key::Char('v'),
-                        ctrl: false,
-                        ..
-                    } if self.mode == Mode::Normal => {
-                        textarea.start_selection();
-                        return Transition::Mode(Mode::Visual);
-                    }
-                    Input {
-                        key: Key::Char('V'),
-                        ctrl: false,
-                        ..
-                    } if self.mode == Mode::Normal => {
-                        textarea.move_cursor(CursorMove::Head);
-                        textarea.start_selection();
-                        textarea.move_cursor(CursorMove::End);
-                        return Transition::Mode(Mode::Visual);
-                    }
-                    // Input { key: Key::Esc, .. }
-                    Input {
-                        key: Key::Char('v'),
-                        ctrl: false,
-                        ..
-                    } if self.mode == Mode::Visual => {
-                        textarea.cancel_selection();
-                        return Transition::Mode(Mode::Normal);
-                    }
-                    Input {
-                        key: Key::Char('g'),
-                        ctrl: false,
-                        ..
-                    } if matches!(
-                        self.pending,
-                        Input {
-                            key: Key::Char('g'),
-                            ctrl: false,
-                            ..
-                        }
-                    ) =>
-                    {
-                        textarea.move_cursor(CursorMove::Top)
+        let is_done = self.mode == Mode::Normal
+            && self.pending.key == Key::Null
+            && self.count.is_none()
+            && self.register.is_none();
+        if is_done && !self.replaying {
+            self.finish_command(textarea);
+        }
+
+        transition
+    }
+
+    // a command that changed the text can be undone and repeated with .
+    fn finish_command(&mut self, textarea: &TextArea<'_>) {
+        if let Some(before) = self.before.take() {
+            if before.0 != textarea.lines() {
+                self.undo_stack.push(before);
+                self.redo_stack.clear();
+                self.last_change = std::mem::take(&mut self.keys);
+            }
+        }
+        self.keys.clear();
+    }
+
+    fn insert(&mut self, input: Input, textarea: &mut TextArea<'_>) -> Transition {
+        match input {
+            Input { key: Key::Esc, .. }
+            | Input {
+                key: Key::Char('c'),
+                ctrl: true,
+                ..
+            } => Transition::Mode(Mode::Normal),
+            input => {
+                textarea.input(input); // Use default key mappings in insert mode
+                Transition::Mode(Mode::Insert)
+            }
+        }
+    }
+
+    fn command_line(
+        &mut self,
+        prompt: char,
+        input: Input,
+        textarea: &mut TextArea<'_>,
+    ) -> Transition {
+        match input {
+            Input { key: Key::Esc, .. } => {
+                self.command_line.clear();
+                Transition::Mode(Mode::Normal)
+            }
+            Input {
+                key: Key::Enter, ..
+            } => {
+                let line = std::mem::take(&mut self.command_line);
+                match prompt {
+                    '/' => {
+                        self.search(textarea, line);
+                        Transition::Mode(Mode::Normal)
                     }
-                    Input {
-                        key: Key::Char('G'),
-                        ctrl: false,
-                        ..
-                    } => textarea.move_cursor(CursorMove::Bottom),
-                    Input {
-                        key: Key::Char(c),
-                        ctrl: false,
-                        ..
-                    } if self.mode == Mode::Operator(c) => {
-                        // Handle yy, dd, cc. (This is not strictly the same behavior as Vim)
-                        textarea.move_cursor(CursorMove::Head);
-                        textarea.start_selection();
-                        let cursor = textarea.cursor();
-                        textarea.move_cursor(CursorMove::Down);
-                        if cursor == textarea.cursor() {
-                            textarea.move_cursor(CursorMove::End); // At the last line, move to end of the line instead
+                    _ => {
+                        self.mode = Mode::Normal;
+                        match line.trim() {
+                            "w" => Transition::Save,
+                            "q" | "q!" => Transition::Discard,
+                            "wq" | "x" => Transition::Quit,
+                            _ => Transition::Mode(Mode::Normal),
                         }
                     }
-                    Input {
-                        key: Key::Char(op @ ('y' | 'd' | 'c')),
-                        ctrl: false,
-                        ..
-                    } if self.mode == Mode::Normal => {
-                        textarea.start_selection();
-                        return Transition::Mode(Mode::Operator(op));
-                    }
-                    Input {
-                        key: Key::Char('y'),
-                        ctrl: false,
-                        ..
-                    } if self.mode == Mode::Visual => {
-                        textarea.copy();
-                        return Transition::Mode(Mode::Normal);
-                    }
-                    Input {
-                        key: Key::Char('d'),
-                        ctrl: false,
-                        ..
-                    } if self.mode == Mode::Visual => {
-                        textarea.cut();
-                        return Transition::Mode(Mode::Normal);
-                    }
-                    Input {
-                        key: Key::Char('c'),
-                        ctrl: false,
-                        ..
-                    } if self.mode == Mode::Visual => {
-                        textarea.cut();
-                        return Transition::Mode(Mode::Insert);
-                    }
-                    input => return Transition::Pending(input),
                 }
+            }
+            Input {
+                key: Key::Backspace,
+                ..
+            } => match self.command_line.pop() {
+                Some(_) => Transition::Nop,
+                None => Transition::Mode(Mode::Normal),
+            },
+            Input {
+                key: Key::Char(c),
+                ctrl: false,
+                ..
+            } => {
+                self.command_line.push(c);
+                Transition::Nop
+            }
+            _ => Transition::Nop,
+        }
+    }
 
-                // Handle the pending operator
-                match self.mode {
-                    Mode::Operator('y') => {
-                        textarea.copy();
-                        Transition::Mode(Mode::Normal)
-                    }
-                    Mode::Operator('d') => {
-                        textarea.cut();
-                        Transition::Mode(Mode::Normal)
-                    }
-                    Mode::Operator('c') => {
-                        textarea.cut();
-                        Transition::Mode(Mode::Insert)
-                    }
-                    _ => Transition::Nop,
+    // an empty search goes to the next match of the last one, like vim
+    fn search(&mut self, textarea: &mut TextArea<'_>, pattern: String) {
+        let pattern = match pattern.is_empty() {
+            true => self.last_search.clone().unwrap_or_default(),
+            false => pattern,
+        };
+        if textarea.set_search_pattern(&pattern).is_ok() && !pattern.is_empty() {
+            textarea.search_forward(false);
+            self.last_search = Some(pattern);
+        }
+    }
+
+    // yanks and deletes go to the unnamed register too, and "+ or "* to the terminal's clipboard
+    fn store_yank(&mut self, textarea: &TextArea<'_>) {
+        let text = textarea.yank_text();
+        if let Some(register) = self.register.take() {
+            if matches!(register, '+' | '*') {
+                if let Err(error) = copy_to_clipboard(&text) {
+                    log::error!("Failed to copy to the clipboard: {error:?}");
                 }
             }
-            Mode::Insert => match input {
-                Input { key: Key::Esc, .. }
-                | Input {
-                    key: Key::Char('c'),
-                    ctrl: true,
+            self.registers.insert(register, text.clone());
+        }
+        self.registers.insert('"', text);
+    }
+
+    // the terminal's clipboard can only be written to, so "+p pastes what was last yanked into it here
+    fn paste(&mut self, textarea: &mut TextArea<'_>, count: usize) {
+        let register = self.register.take().unwrap_or('"');
+        let Some(text) = self.registers.get(&register) else {
+            if register == '"' {
+                (0..count).for_each(|_| {
+                    textarea.paste();
+                });
+            }
+            return;
+        };
+        textarea.set_yank_text(text.clone());
+        (0..count).for_each(|_| {
+            textarea.paste();
+        });
+    }
+
+    fn undo(&mut self, textarea: &mut TextArea<'_>, count: usize) {
+        for _ in 0..count {
+            let Some(previous) = self.undo_stack.pop() else {
+                break;
+            };
+            self.redo_stack.push(snapshot(textarea));
+            restore(textarea, previous);
+        }
+        // going back isn't a change of its own
+        self.before = None;
+    }
+
+    fn redo(&mut self, textarea: &mut TextArea<'_>, count: usize) {
+        for _ in 0..count {
+            let Some(next) = self.redo_stack.pop() else {
+                break;
+            };
+            self.undo_stack.push(snapshot(textarea));
+            restore(textarea, next);
+        }
+        self.before = None;
+    }
+
+    fn repeat_last_change(&mut self, textarea: &mut TextArea<'_>, count: usize) {
+        let keys = self.last_change.clone();
+        self.replaying = true;
+        for _ in 0..count {
+            for input in keys.iter().cloned() {
+                self.transition(input, textarea);
+            }
+        }
+        self.replaying = false;
+        // so the repeat is one undo step and . still repeats the original
+        self.keys = keys;
+    }
+
+    fn select_text_object(
+        &mut self,
+        kind: char,
+        object: char,
+        textarea: &mut TextArea<'_>,
+    ) -> bool {
+        let (row, col) = textarea.cursor();
+        let Some((start, end)) = text_object(&textarea.lines()[row], col, kind, object) else {
+            return false;
+        };
+        textarea.cancel_selection();
+        jump(textarea, (row, start));
+        textarea.start_selection();
+        jump(textarea, (row, end));
+        true
+    }
+
+    fn normal(&mut self, input: Input, textarea: &mut TextArea<'_>) -> Transition {
+        // the key after " names the register
+        if self.pending.key == Key::Char('"') {
+            if let Key::Char(register) = input.key {
+                self.register = Some(register);
+            }
+            return Transition::Nop;
+        }
+
+        // the key after i or a in operator mode is a text object, like the w in ciw
+        if let (Mode::Operator(_), Key::Char(kind @ ('i' | 'a')), Key::Char(object)) =
+            (self.mode, self.pending.key, input.key)
+        {
+            if !self.select_text_object(kind, object, textarea) {
+                textarea.cancel_selection();
+                return Transition::Mode(Mode::Normal);
+            }
+            return self.apply_operator(textarea);
+        }
+
+        // digits build up a count, except a leading 0 which goes to the start of the line
+        if let Input {
+            key: Key::Char(digit @ '0'..='9'),
+            ctrl: false,
+            ..
+        } = input
+        {
+            if digit != '0' || self.count.is_some() {
+                let digit = digit.to_digit(10).unwrap_or(0) as usize;
+                self.count = Some(self.count.unwrap_or(0).saturating_mul(10) + digit);
+                return Transition::Nop;
+            }
+        }
+
+        let typed_count = self.count.take();
+        let count = typed_count.unwrap_or(1)
+            * match self.mode {
+                Mode::Operator(_) => self.operator_count.max(1),
+                _ => 1,
+            };
+        let repeat = |textarea: &mut TextArea<'_>, m: CursorMove| {
+            (0..count).for_each(|_| textarea.move_cursor(m))
+        };
+
+        match input {
+            Input { key: Key::Esc, .. } if self.mode == Mode::Normal => return Transition::Quit,
+            Input { key: Key::Esc, .. } => {
+                textarea.cancel_selection();
+                return Transition::Mode(Mode::Normal);
+            }
+            Input {
+                key: Key::Char(kind @ ('i' | 'a')),
+                ctrl: false,
+                ..
+            } if matches!(self.mode, Mode::Operator(_)) => {
+                return Transition::Pending(char_input(kind))
+            }
+            Input {
+                key: Key::Char('"'),
+                ..
+            } => return Transition::Pending(input),
+            Input {
+                key: Key::Char('h'),
+                ..
+            }
+            | Input { key: Key::Left, .. } => repeat(textarea, CursorMove::Back),
+
+            Input {
+                key: Key::Char('j'),
+                ..
+            }
+            | Input { key: Key::Down, .. } => repeat(textarea, CursorMove::Down),
+
+            Input {
+                key: Key::Char('k'),
+                ..
+            }
+            | Input { key: Key::Up, .. } => repeat(textarea, CursorMove::Up),
+
+            Input {
+                key: Key::Char('l'),
+                ..
+            }
+            | Input {
+                key: Key::Right, ..
+            } => repeat(textarea, CursorMove::Forward),
+            Input {
+                key: Key::Char('w'),
+                ..
+            } => repeat(textarea, CursorMove::WordForward),
+            Input {
+                key: Key::Char('b'),
+                ctrl: false,
+                ..
+            } => repeat(textarea, CursorMove::WordBack),
+            Input {
+                key: Key::Char('^' | '0'),
+                ..
+            } => textarea.move_cursor(CursorMove::Head),
+            Input {
+                key: Key::Char('$'),
+                ..
+            } => textarea.move_cursor(CursorMove::End),
+            Input {
+                key: Key::Char('D'),
+                ..
+            } => {
+                textarea.delete_line_by_end();
+                return Transition::Mode(Mode::Normal);
+            }
+            Input {
+                key: Key::Char('C'),
+                ..
+            } => {
+                textarea.delete_line_by_end();
+                textarea.cancel_selection();
+                return Transition::Mode(Mode::Insert);
+            }
+            Input {
+                key: Key::Char('p'),
+                ..
+            } => {
+                self.paste(textarea, count);
+                return Transition::Mode(Mode::Normal);
+            }
+            Input {
+                key: Key::Char('u'),
+                ctrl: false,
+                ..
+            } => {
+                self.undo(textarea, count);
+                return Transition::Mode(Mode::Normal);
+            }
+            Input {
+                key: Key::Char('r'),
+                ctrl: true,
+                ..
+            } => {
+                self.redo(textarea, count);
+                return Transition::Mode(Mode::Normal);
+            }
+            Input {
+                key: Key::Char('.'),
+                ctrl: false,
+                ..
+            } if self.mode == Mode::Normal => {
+                self.repeat_last_change(textarea, count);
+                return Transition::Mode(Mode::Normal);
+            }
+            Input {
+                key: Key::Char('x'),
+                ..
+            } => {
+                (0..count).for_each(|_| {
+                    textarea.delete_next_char();
+                });
+                return Transition::Mode(Mode::Normal);
+            }
+            Input {
+                key: Key::Char('i'),
+                ..
+            } => {
+                textarea.cancel_selection();
+                return Transition::Mode(Mode::Insert);
+            }
+            Input {
+                key: Key::Char('a'),
+                ..
+            } => {
+                textarea.cancel_selection();
+                textarea.move_cursor(CursorMove::Forward);
+                return Transition::Mode(Mode::Insert);
+            }
+            Input {
+                key: Key::Char('A'),
+                ..
+            } => {
+                textarea.cancel_selection();
+                textarea.move_cursor(CursorMove::End);
+                return Transition::Mode(Mode::Insert);
+            }
+            Input {
+                key: Key::Char('o'),
+                ..
+            } => {
+                textarea.move_cursor(CursorMove::End);
+                textarea.insert_newline();
+                return Transition::Mode(Mode::Insert);
+            }
+            Input {
+                key: Key::Char('O'),
+                ..
+            } => {
+                textarea.move_cursor(CursorMove::Head);
+                textarea.insert_newline();
+                textarea.move_cursor(CursorMove::Up);
+                return Transition::Mode(Mode::Insert);
+            }
+            Input {
+                key: Key::Char('I'),
+                ..
+            } => {
+                textarea.cancel_selection();
+                textarea.move_cursor(CursorMove::Head);
+                return Transition::Mode(Mode::Insert);
+            }
+            Input {
+                key: Key::Char('q'),
+                ..
+            } => return Transition::Quit,
+            Input {
+                key: Key::Char(prompt @ ('/' | ':')),
+                ctrl: false,
+                ..
+            } if self.mode == Mode::Normal => {
+                self.command_line.clear();
+                return Transition::Mode(Mode::Command(prompt));
+            }
+            Input {
+                key: Key::Char('n'),
+                ctrl: false,
+                ..
+            } => (0..count).for_each(|_| {
+                textarea.search_forward(false);
+            }),
+            Input {
+                key: Key::Char('N'),
+                ctrl: false,
+                ..
+            } => (0..count).for_each(|_| {
+                textarea.search_back(false);
+            }),
+            Input {
+                key: Key::Char('e'),
+                ctrl: true,
+                ..
+            } => textarea.scroll((1, 0)),
+            Input {
+                key: Key::Char('y'),
+                ctrl: true,
+                ..
+            } => textarea.scroll((-1, 0)),
+            Input {
+                key: Key::Char('d'),
+                ctrl: true,
+                ..
+            } => textarea.scroll(Scrolling::HalfPageDown),
+            Input {
+                key: Key::Char('u'),
+                ctrl: true,
+                ..
+            } => textarea.scroll(Scrolling::HalfPageUp),
+            Input {
+                key: Key::Char('f'),
+                ctrl: true,
+                ..
+            } => textarea.scroll(Scrolling::PageDown),
+            Input {
+                key: Key::Char('b'),
+                ctrl: true,
+                ..
+            } => textarea.scroll(Scrolling::PageUp),
+            Input {
+                key: Key::Char('v'),
+                ctrl: false,
+                ..
+            } if self.mode == Mode::Normal => {
+                textarea.start_selection();
+                return Transition::Mode(Mode::Visual);
+            }
+            Input {
+                key: Key::Char('V'),
+                ctrl: false,
+                ..
+            } if self.mode == Mode::Normal => {
+                textarea.move_cursor(CursorMove::Head);
+                textarea.start_selection();
+                textarea.move_cursor(CursorMove::End);
+                return Transition::Mode(Mode::Visual);
+            }
+            // Input { key: Key::Esc, .. }
+            Input {
+                key: Key::Char('v'),
+                ctrl: false,
+                ..
+            } if self.mode == Mode::Visual => {
+                textarea.cancel_selection();
+                return Transition::Mode(Mode::Normal);
+            }
+            Input {
+                key: Key::Char('g'),
+                ctrl: false,
+                ..
+            } if matches!(
+                self.pending,
+                Input {
+                    key: Key::Char('g'),
+                    ctrl: false,
                     ..
-                } => Transition::Mode(Mode::Normal),
-                input => {
-                    textarea.input(input); // Use default key mappings in insert mode
-                    Transition::Mode(Mode::Insert)
                 }
+            ) =>
+            {
+                match typed_count {
+                    Some(line) => jump(textarea, (line.saturating_sub(1), 0)),
+                    None => textarea.move_cursor(CursorMove::Top),
+                }
+            }
+            Input {
+                key: Key::Char('G'),
+                ctrl: false,
+                ..
+            } => match typed_count {
+                Some(line) => jump(textarea, (line.saturating_sub(1), 0)),
+                None => textarea.move_cursor(CursorMove::Bottom),
             },
+            Input {
+                key: Key::Char(c),
+                ctrl: false,
+                ..
+            } if self.mode == Mode::Operator(c) => {
+                // Handle yy, dd, cc. (This is not strictly the same behavior as Vim)
+                textarea.move_cursor(CursorMove::Head);
+                textarea.start_selection();
+                let (row, _) = textarea.cursor();
+                repeat(textarea, CursorMove::Down);
+                if textarea.cursor().0 < row + count {
+                    textarea.move_cursor(CursorMove::End); // At the last line, move to end of the line instead
+                }
+            }
+            Input {
+                key: Key::Char(op @ ('y' | 'd' | 'c')),
+                ctrl: false,
+                ..
+            } if self.mode == Mode::Normal => {
+                self.operator_count = count;
+                textarea.start_selection();
+                return Transition::Mode(Mode::Operator(op));
+            }
+            Input {
+                key: Key::Char('y'),
+                ctrl: false,
+                ..
+            } if self.mode == Mode::Visual => {
+                textarea.copy();
+                self.store_yank(textarea);
+                return Transition::Mode(Mode::Normal);
+            }
+            Input {
+                key: Key::Char('d'),
+                ctrl: false,
+                ..
+            } if self.mode == Mode::Visual => {
+                textarea.cut();
+                self.store_yank(textarea);
+                return Transition::Mode(Mode::Normal);
+            }
+            Input {
+                key: Key::Char('c'),
+                ctrl: false,
+                ..
+            } if self.mode == Mode::Visual => {
+                textarea.cut();
+                self.store_yank(textarea);
+                return Transition::Mode(Mode::Insert);
+            }
+            // an operator followed by something that isn't a motion is cancelled
+            _ if matches!(self.mode, Mode::Operator(_)) => {
+                textarea.cancel_selection();
+                return Transition::Mode(Mode::Normal);
+            }
+            input => return Transition::Pending(input),
+        }
+
+        // Handle the pending operator
+        self.apply_operator(textarea)
+    }
+
+    fn apply_operator(&mut self, textarea: &mut TextArea<'_>) -> Transition {
+        match self.mode {
+            Mode::Operator('y') => {
+                textarea.copy();
+                self.store_yank(textarea);
+                Transition::Mode(Mode::Normal)
+            }
+            Mode::Operator('d') => {
+                textarea.cut();
+                self.store_yank(textarea);
+                Transition::Mode(Mode::Normal)
+            }
+            Mode::Operator('c') => {
+                textarea.cut();
+                self.store_yank(textarea);
+                Transition::Mode(Mode::Insert)
+            }
+            _ => Transition::Nop,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // plain characters, with <Esc>, <Enter>, <BS> and <C-r> for the special keys
+    fn inputs(keys: &str) -> Vec<Input> {
+        let mut inputs = Vec::new();
+        let mut rest = keys;
+        while let Some(c) = rest.chars().next() {
+            let special = [
+                ("<Esc>", Key::Esc, false),
+                ("<Enter>", Key::Enter, false),
+                ("<BS>", Key::Backspace, false),
+                ("<C-r>", Key::Char('r'), true),
+            ]
+            .into_iter()
+            .find(|(name, _, _)| rest.starts_with(name));
+            match special {
+                Some((name, key, ctrl)) => {
+                    inputs.push(Input {
+                        key,
+                        ctrl,
+                        ..Input::default()
+                    });
+                    rest = &rest[name.len()..];
+                }
+                None => {
+                    inputs.push(char_input(c));
+                    rest = &rest[c.len_utf8()..];
+                }
+            }
         }
+        inputs
+    }
+
+    fn type_keys(vim: &mut Vim, textarea: &mut TextArea<'_>, keys: &str) -> Transition {
+        inputs(keys)
+            .into_iter()
+            .map(|input| vim.transition(input, textarea))
+            .last()
+            .unwrap_or(Transition::Nop)
+    }
+
+    fn editor(text: &str) -> (Vim, TextArea<'static>) {
+        let textarea = TextArea::new(text.lines().map(ToString::to_string).collect());
+        (Vim::default(), textarea)
+    }
+
+    #[test]
+    fn counts_repeat_motions_and_operators() {
+        let (mut vim, mut textarea) = editor("one two three four five");
+        type_keys(&mut vim, &mut textarea, "2dw");
+        assert_eq!(textarea.lines(), ["three four five"]);
+        type_keys(&mut vim, &mut textarea, "d2w");
+        assert_eq!(textarea.lines(), ["five"]);
+        type_keys(&mut vim, &mut textarea, "3x");
+        assert_eq!(textarea.lines(), ["e"]);
+
+        let (mut vim, mut textarea) = editor("a\nb\nc\nd");
+        type_keys(&mut vim, &mut textarea, "2j");
+        assert_eq!(textarea.cursor(), (2, 0));
+        type_keys(&mut vim, &mut textarea, "1G2dd");
+        assert_eq!(textarea.lines(), ["c", "d"]);
+        assert_eq!(vim.mode, Mode::Normal);
+    }
+
+    #[test]
+    fn text_objects_change_and_delete() {
+        let (mut vim, mut textarea) = editor("fix the login bug");
+        type_keys(&mut vim, &mut textarea, "wwciwsignup<Esc>");
+        assert_eq!(textarea.lines(), ["fix the signup bug"]);
+        assert_eq!(vim.mode, Mode::Normal);
+
+        let (mut vim, mut textarea) = editor(r#"say "hello there" (to (all) of you)"#);
+        type_keys(&mut vim, &mut textarea, "wwdi\"");
+        assert_eq!(textarea.lines(), [r#"say "" (to (all) of you)"#]);
+        type_keys(&mut vim, &mut textarea, "$da(");
+        assert_eq!(textarea.lines(), [r#"say "" "#]);
+
+        let (mut vim, mut textarea) = editor("keep drop keep");
+        type_keys(&mut vim, &mut textarea, "wdaw");
+        assert_eq!(textarea.lines(), ["keep keep"]);
+    }
+
+    #[test]
+    fn dot_repeats_the_last_change() {
+        let (mut vim, mut textarea) = editor("a b c d e f");
+        type_keys(&mut vim, &mut textarea, "dw.");
+        assert_eq!(textarea.lines(), ["c d e f"]);
+        type_keys(&mut vim, &mut textarea, "2.");
+        assert_eq!(textarea.lines(), ["e f"]);
+
+        // moving around doesn't replace what . repeats
+        let (mut vim, mut textarea) = editor("x y");
+        type_keys(&mut vim, &mut textarea, "ciwz<Esc>w.");
+        assert_eq!(textarea.lines(), ["z z"]);
+    }
+
+    #[test]
+    fn registers_keep_their_own_text() {
+        let (mut vim, mut textarea) = editor("alpha beta");
+        type_keys(&mut vim, &mut textarea, "\"ayiwwyiw");
+        type_keys(&mut vim, &mut textarea, "$\"ap");
+        assert_eq!(textarea.lines(), ["alpha betaalpha"]);
+        type_keys(&mut vim, &mut textarea, "0p");
+        assert_eq!(textarea.lines(), ["betaalpha betaalpha"]);
+        // nothing was put in b
+        type_keys(&mut vim, &mut textarea, "\"bp");
+        assert_eq!(textarea.lines(), ["betaalpha betaalpha"]);
+    }
+
+    #[test]
+    fn search_jumps_between_matches() {
+        let (mut vim, mut textarea) = editor("#deploy\nreview\nmore #deploy");
+        assert_eq!(type_keys(&mut vim, &mut textarea, "/dep"), Transition::Nop);
+        assert_eq!(vim.mode, Mode::Command('/'));
+        type_keys(&mut vim, &mut textarea, "<Enter>");
+        assert_eq!(textarea.cursor(), (0, 1));
+        type_keys(&mut vim, &mut textarea, "n");
+        assert_eq!(textarea.cursor(), (2, 6));
+        type_keys(&mut vim, &mut textarea, "N");
+        assert_eq!(textarea.cursor(), (0, 1));
+        type_keys(&mut vim, &mut textarea, "/<Esc>");
+        assert_eq!(vim.mode, Mode::Normal);
+    }
+
+    #[test]
+    fn ex_commands_save_or_discard() {
+        let (mut vim, mut textarea) = editor("note");
+        assert_eq!(
+            type_keys(&mut vim, &mut textarea, ":w<Enter>"),
+            Transition::Save
+        );
+        assert_eq!(
+            type_keys(&mut vim, &mut textarea, ":q<Enter>"),
+            Transition::Discard
+        );
+        assert_eq!(
+            type_keys(&mut vim, &mut textarea, ":wq<Enter>"),
+            Transition::Quit
+        );
+        assert_eq!(
+            type_keys(&mut vim, &mut textarea, ":nope<Enter>"),
+            Transition::Mode(Mode::Normal)
+        );
+        assert_eq!(vim.mode, Mode::Normal);
+    }
+
+    #[test]
+    fn undo_takes_back_whole_commands() {
+        let (mut vim, mut textarea) = editor("first");
+        vim.start_insert(&textarea);
+        type_keys(&mut vim, &mut textarea, "new <Esc>");
+        type_keys(&mut vim, &mut textarea, "$ciwsecond<Esc>");
+        assert_eq!(textarea.lines(), ["new second"]);
+
+        type_keys(&mut vim, &mut textarea, "u");
+        assert_eq!(textarea.lines(), ["new first"]);
+        type_keys(&mut vim, &mut textarea, "u");
+        assert_eq!(textarea.lines(), ["first"]);
+        type_keys(&mut vim, &mut textarea, "2<C-r>");
+        assert_eq!(textarea.lines(), ["new second"]);
+        // undoing doesn't count as the change to repeat
+        type_keys(&mut vim, &mut textarea, "u0.");
+        assert_eq!(textarea.lines(), ["second first"]);
     }
 }
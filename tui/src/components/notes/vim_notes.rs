@@ -1,10 +1,10 @@
 use crate::api_client::ApiRequest::UpdateEntryNote;
 use crate::components::Component;
+use crate::theme::{self, StyleName};
 use crate::{action::Action, tui::Frame};
 use color_eyre::eyre::Result;
-use crossterm::event::{KeyCode, KeyEvent};
+use crossterm::event::KeyEvent;
 use ratatui::layout::Rect;
-use ratatui::style::{Color, Style};
 use ratatui::widgets::{block::Block, Borders};
 use tokio::sync::mpsc::UnboundedSender;
 use tui_textarea::TextArea;
@@ -16,7 +16,11 @@ pub struct VimnNotes<'a> {
     pub editor: TextArea<'a>,
     pub vim: Vim,
     pub is_edit_mode: bool,
+    // whatever EditNote is bound to, for the title
+    pub edit_key: String,
     entry_id: i32,
+    // what :q goes back to
+    saved_text: String,
     command_tx: Option<UnboundedSender<Action>>,
 }
 
@@ -26,7 +30,13 @@ impl VimnNotes<'_> {
     }
 
     pub fn set_text(&mut self, text: String) {
+        // a :w answer would otherwise reset the cursor and history mid-edit
+        if self.is_edit_mode {
+            return;
+        }
         self.editor = TextArea::new(text.lines().map(ToString::to_string).collect());
+        self.saved_text = text;
+        self.vim.clear_history();
     }
 
     pub fn set_id(&mut self, id: i32) {
@@ -36,6 +46,28 @@ impl VimnNotes<'_> {
     pub fn get_text(&self) -> String {
         self.editor.lines().join("\n")
     }
+
+    pub fn start_editing(&mut self) {
+        self.is_edit_mode = true;
+        self.vim.start_insert(&self.editor);
+    }
+
+    fn save(&mut self) -> Result<()> {
+        self.saved_text = self.get_text();
+        if let Some(tx) = &self.command_tx {
+            let update_note_action = UpdateEntryNote {
+                id: self.entry_id,
+                note: self.saved_text.clone(),
+            };
+            tx.send(Action::api_request_action(update_note_action))?;
+        }
+        Ok(())
+    }
+
+    fn stop_editing(&mut self) {
+        self.is_edit_mode = false;
+        let _ = self.editor.set_search_pattern("");
+    }
 }
 
 impl Component for VimnNotes<'_> {
@@ -43,29 +75,28 @@ impl Component for VimnNotes<'_> {
         // Determine title and border style based on whether editing is enabled
         let title = if self.is_edit_mode {
             match self.vim.mode {
-                Mode::Normal => format!("{} - 'i' to insert, 'v' to visual, Esc to quit", Mode::Normal),
+                Mode::Normal => format!(
+                    "{} - 'i' to insert, '/' to search, ':w' to save, ':q' to discard, Esc to quit",
+                    Mode::Normal
+                ),
                 Mode::Insert => format!("{} - Esc to normal", Mode::Insert),
                 Mode::Visual => format!("{} - 'y' to copy, 'd' to cut, Esc to normal", Mode::Visual),
                 Mode::Operator(_) => format!("{}", self.vim.mode),
+                Mode::Command(prompt) => format!("{}{}_", prompt, self.vim.command_line),
             }
         } else {
-            "Press '/' to start editing".to_string()
+            format!("Press '{}' to start editing", self.edit_key)
         };
 
-        let border_color = match self.is_edit_mode {
-            true => match self.vim.mode {
-                Mode::Normal => Color::LightGreen,
-                Mode::Insert => Color::LightBlue,
-                Mode::Visual => Color::LightYellow,
-                Mode::Operator(_) => Color::LightMagenta,
-            },
-            false => Color::Gray,
+        let border_style = match self.is_edit_mode {
+            true => self.vim.mode.border_style(),
+            false => theme::style(StyleName::Notes),
         };
 
         let block = Block::default()
             .borders(Borders::ALL)
             .title(title)
-            .border_style(Style::default().fg(border_color));
+            .border_style(border_style);
 
         // Set cursor style based on mode, if editing
         if self.is_edit_mode {
@@ -85,24 +116,17 @@ impl Component for VimnNotes<'_> {
             let input: tui_textarea::Input = key.into(); // Convert KeyEvent to Input for the TextArea
 
             match self.vim.transition(input, &mut self.editor) {
-                Transition::Mode(mode) => {
-                    self.vim = Vim::new(mode);
-                }
                 Transition::Quit => {
-                    if let Some(tx) = &self.command_tx {
-                        let update_note_action = UpdateEntryNote {
-                            id: self.entry_id,
-                            note: self.get_text(),
-                        };
-                        tx.send(Action::api_request_action(update_note_action))?;
-                    }
-                    self.is_edit_mode = false;
+                    self.save()?;
+                    self.stop_editing();
+                }
+                Transition::Save => self.save()?,
+                Transition::Discard => {
+                    self.stop_editing();
+                    self.set_text(self.saved_text.clone());
                 }
-                _ => {} // Handle other transitions or inputs
+                _ => {} // the vim state keeps track of modes and pending keys itself
             }
-        } else if key.code == KeyCode::Char('/') {
-            self.is_edit_mode = true;
-            self.vim = Vim::new(Mode::Insert);
         }
 
         Ok(None)
@@ -0,0 +1,174 @@
+use chrono::Local;
+use color_eyre::eyre::Result;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseEvent};
+use ratatui::{
+    prelude::*,
+    widgets::{Block, Borders, Clear, List, ListItem, ListState},
+};
+use shared_lib::models::operation::Operation;
+use tokio::sync::mpsc::UnboundedSender;
+
+use super::{component_utils::TooltipBar, Component, Frame};
+use crate::{
+    action::{Action, ApiAct, Command, TTAct},
+    api_client::{ApiRequest, ApiResponse},
+    theme::{self, StyleName},
+};
+
+/// A popup listing the changes kept on the server, newest first, with undone ones struck out
+#[derive(Default)]
+pub struct OperationHistory {
+    command_tx: Option<UnboundedSender<Action>>,
+    tooltip_bar: TooltipBar,
+    operations: Vec<Operation>,
+    list_state: ListState,
+    pub is_open: bool,
+}
+
+impl OperationHistory {
+    pub fn open(&mut self) {
+        self.is_open = true;
+        self.list_state.select(Some(0));
+        self.request_operations();
+    }
+
+    fn request_operations(&self) {
+        if let Some(tx) = &self.command_tx {
+            if let Err(error) = tx.send(Action::api_request_action(ApiRequest::GetOperations)) {
+                log::error!("Failed to send action: {error:?}");
+            }
+        }
+    }
+
+    fn send_command(&self, command: Command) {
+        if let Some(tx) = &self.command_tx {
+            if let Err(error) = tx.send(Action::TT(TTAct::Command(command))) {
+                log::error!("Failed to send action: {error:?}");
+            }
+        }
+    }
+
+    fn select(&mut self, offset: isize) {
+        if self.operations.is_empty() {
+            return;
+        }
+        let last = self.operations.len() as isize - 1;
+        let selected = self.list_state.selected().unwrap_or(0) as isize;
+        self.list_state
+            .select(Some((selected + offset).clamp(0, last) as usize));
+    }
+}
+
+impl Component for OperationHistory {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
+        self.command_tx = Some(tx);
+        Ok(())
+    }
+
+    fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        match action {
+            Action::Api(ApiAct::Response(ApiResponse::Operations(operations))) => {
+                self.operations = operations;
+                self.select(0);
+            }
+            // undoing from the popup marks an operation, so fetch the list again
+            Action::Api(ApiAct::Response(ApiResponse::Undone(_) | ApiResponse::Redone(_)))
+                if self.is_open =>
+            {
+                self.request_operations()
+            }
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn handle_key_events(&mut self, key: KeyEvent) -> Result<Option<Action>> {
+        match key.code {
+            KeyCode::Char('r') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.send_command(Command::Redo)
+            }
+            KeyCode::Char('u') => self.send_command(Command::Undo),
+            KeyCode::Char('j') | KeyCode::Down => self.select(1),
+            KeyCode::Char('k') | KeyCode::Up => self.select(-1),
+            KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('U') => self.is_open = false,
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn handle_mouse_events(&mut self, mouse: MouseEvent) -> Result<Option<Action>> {
+        match self.tooltip_bar.clicked_key(mouse) {
+            Some(key) => self.handle_key_events(key),
+            None => Ok(None),
+        }
+    }
+
+    fn is_capturing_input(&self) -> bool {
+        self.is_open
+    }
+
+    fn draw(&mut self, f: &mut Frame<'_>, area: Rect) -> Result<()> {
+        if !self.is_open {
+            return Ok(());
+        }
+
+        let modal_area = area.inner(&Margin {
+            horizontal: (area.width as f32 * 0.1) as u16,
+            vertical: (area.height as f32 * 0.1) as u16,
+        });
+        f.render_widget(Clear, modal_area);
+
+        let block = Block::default()
+            .title("History")
+            .borders(Borders::ALL)
+            .border_style(theme::style(StyleName::ModalBorder));
+        let inner_area = block.inner(modal_area);
+        f.render_widget(block, modal_area);
+
+        let list_area = Rect {
+            height: inner_area.height.saturating_sub(3),
+            ..inner_area
+        };
+        let items: Vec<ListItem> = match self.operations.is_empty() {
+            true => vec![ListItem::new("No changes yet")],
+            false => self
+                .operations
+                .iter()
+                .map(|operation| {
+                    let style = match operation.is_undone {
+                        true => theme::style(StyleName::Faint).add_modifier(Modifier::CROSSED_OUT),
+                        false => Style::default(),
+                    };
+                    ListItem::new(Line::from(vec![
+                        Span::styled(
+                            operation
+                                .created_at
+                                .with_timezone(&Local)
+                                .format("%a %H:%M:%S ")
+                                .to_string(),
+                            theme::style(StyleName::Hint),
+                        ),
+                        Span::styled(operation.description.clone(), style),
+                    ]))
+                })
+                .collect(),
+        };
+        let list = List::new(items)
+            .highlight_style(Style::default().add_modifier(Modifier::BOLD))
+            .highlight_symbol(">");
+        f.render_stateful_widget(list, list_area, &mut self.list_state);
+
+        let bottom_bar_area = Rect {
+            y: inner_area.y + list_area.height,
+            height: inner_area.height - list_area.height,
+            ..inner_area
+        };
+        self.tooltip_bar.draw(
+            f,
+            bottom_bar_area,
+            &["Undo [u]", "Redo [Ctrl-r]", "Close [Esc]"],
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,219 @@
+use color_eyre::eyre::Result;
+use crossterm::event::{KeyCode, KeyEvent, MouseEvent};
+use ratatui::{
+    prelude::*,
+    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph},
+};
+use shared_lib::{models::time_entry::TimeEntryVM, utils::note_tags::parse_tags};
+use tokio::sync::mpsc::UnboundedSender;
+
+use super::{component_utils::TooltipBar, Component, Frame};
+use crate::{
+    action::{Action, ApiAct, TTAct},
+    api_client::{ApiRequest, ApiResponse},
+    theme::{self, StyleName},
+};
+
+/// A popup that searches notes by `#tag`, ticket or word as you type and jumps to the picked entry
+#[derive(Default)]
+pub struct SearchPopup {
+    command_tx: Option<UnboundedSender<Action>>,
+    tooltip_bar: TooltipBar,
+    query: String,
+    results: Vec<TimeEntryVM>,
+    list_state: ListState,
+    pub is_open: bool,
+}
+
+impl SearchPopup {
+    pub fn open(&mut self) {
+        self.is_open = true;
+        self.query.clear();
+        self.results.clear();
+        self.list_state.select(None);
+    }
+
+    fn send(&self, action: Action) {
+        if let Some(tx) = &self.command_tx {
+            if let Err(error) = tx.send(action) {
+                log::error!("Failed to send action: {error:?}");
+            }
+        }
+    }
+
+    fn search(&mut self) {
+        let query = self.query.trim().to_string();
+        if query.is_empty() {
+            self.results.clear();
+            self.list_state.select(None);
+            return;
+        }
+        self.send(Action::api_request_action(ApiRequest::Search { query }));
+    }
+
+    fn select(&mut self, offset: isize) {
+        if self.results.is_empty() {
+            return;
+        }
+        let last = self.results.len() as isize - 1;
+        let selected = self.list_state.selected().unwrap_or(0) as isize;
+        self.list_state
+            .select(Some((selected + offset).clamp(0, last) as usize));
+    }
+
+    fn jump(&mut self) {
+        let Some(entry) = self
+            .list_state
+            .selected()
+            .and_then(|index| self.results.get(index))
+        else {
+            return;
+        };
+        self.send(Action::TT(TTAct::JumpToEntry(entry.day, entry.id)));
+        self.is_open = false;
+    }
+}
+
+// the first line of the note with its tags and tickets picked out
+fn note_line(note: &str) -> Line<'static> {
+    let first_line = note
+        .lines()
+        .find(|line| !line.trim().is_empty())
+        .unwrap_or("");
+    let spans: Vec<Span> = first_line
+        .split_inclusive(' ')
+        .map(|word| match parse_tags(word).is_empty() {
+            true => Span::raw(word.to_string()),
+            false => Span::styled(word.to_string(), theme::style(StyleName::Tag)),
+        })
+        .collect();
+    Line::from(spans)
+}
+
+impl Component for SearchPopup {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
+        self.command_tx = Some(tx);
+        Ok(())
+    }
+
+    fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        // answers to anything but the latest query are stale
+        if let Action::Api(ApiAct::Response(ApiResponse::SearchResults { query, entries })) = action
+        {
+            if self.is_open && query == self.query.trim() {
+                self.results = entries;
+                self.list_state
+                    .select((!self.results.is_empty()).then_some(0));
+            }
+        }
+        Ok(None)
+    }
+
+    fn handle_key_events(&mut self, key: KeyEvent) -> Result<Option<Action>> {
+        match key.code {
+            KeyCode::Esc => self.is_open = false,
+            KeyCode::Enter => self.jump(),
+            KeyCode::Down | KeyCode::Tab => self.select(1),
+            KeyCode::Up | KeyCode::BackTab => self.select(-1),
+            KeyCode::Backspace => {
+                self.query.pop();
+                self.search();
+            }
+            KeyCode::Char(c) => {
+                self.query.push(c);
+                self.search();
+            }
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn handle_mouse_events(&mut self, mouse: MouseEvent) -> Result<Option<Action>> {
+        match self.tooltip_bar.clicked_key(mouse) {
+            Some(key) => self.handle_key_events(key),
+            None => Ok(None),
+        }
+    }
+
+    fn is_capturing_input(&self) -> bool {
+        self.is_open
+    }
+
+    fn draw(&mut self, f: &mut Frame<'_>, area: Rect) -> Result<()> {
+        if !self.is_open {
+            return Ok(());
+        }
+
+        let modal_area = area.inner(&Margin {
+            horizontal: (area.width as f32 * 0.1) as u16,
+            vertical: (area.height as f32 * 0.1) as u16,
+        });
+        f.render_widget(Clear, modal_area);
+
+        let block = Block::default()
+            .title("Search notes")
+            .borders(Borders::ALL)
+            .border_style(theme::style(StyleName::ModalBorder));
+        let inner_area = block.inner(modal_area);
+        f.render_widget(block, modal_area);
+
+        let layout = Layout::new(
+            Direction::Vertical,
+            [
+                Constraint::Length(3),
+                Constraint::Min(0),
+                Constraint::Length(3),
+            ],
+        )
+        .split(inner_area);
+
+        let input = Paragraph::new(format!("{}_", self.query)).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(theme::style(StyleName::FocusedInput)),
+        );
+        f.render_widget(input, layout[0]);
+
+        let items: Vec<ListItem> = match (self.query.trim().is_empty(), self.results.is_empty()) {
+            (true, _) => vec![ListItem::new(Line::styled(
+                "like #deploy, ABC-123 or a word from the note",
+                theme::style(StyleName::Hint),
+            ))],
+            (false, true) => vec![ListItem::new("No matching entries")],
+            (false, false) => self
+                .results
+                .iter()
+                .map(|entry| {
+                    let alias = entry
+                        .charge_code
+                        .as_ref()
+                        .map_or("No charge code".to_string(), |code| code.alias.clone());
+                    let mut spans = vec![
+                        Span::styled(
+                            format!("{:<10}", format!("{:?}", entry.day)),
+                            theme::style(StyleName::Hint),
+                        ),
+                        Span::styled(
+                            format!("{} ", alias),
+                            Style::default().add_modifier(Modifier::BOLD),
+                        ),
+                    ];
+                    spans.extend(note_line(&entry.note).spans);
+                    ListItem::new(Line::from(spans))
+                })
+                .collect(),
+        };
+        let list = List::new(items)
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+            .highlight_symbol(">");
+        f.render_stateful_widget(list, layout[1], &mut self.list_state);
+
+        self.tooltip_bar.draw(
+            f,
+            layout[2],
+            &["Jump [Enter]", "Move [Up/Down]", "Close [Esc]"],
+        );
+
+        Ok(())
+    }
+}
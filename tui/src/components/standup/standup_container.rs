@@ -1,16 +1,33 @@
-use crate::{components::Component, tui::Frame};
+use crate::{
+    action::Command,
+    components::Component,
+    theme::{self, StyleName},
+    tui::Frame,
+};
 use color_eyre::eyre::Result;
 use ratatui::{
-    layout::{Constraint, Direction, Layout, Rect},
-    style::Style,
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
     widgets::{Block, Borders, Paragraph, Wrap},
 };
-use shared_lib::models::{charge_code::ChargeCode, time_entry::TimeEntryVM};
+use shared_lib::models::{
+    charge_code::ChargeCode, day::Day, full_state::FullState, time_entry::TimeEntryVM,
+};
 use std::collections::HashMap;
+use strum::Display;
 
+/// Yesterday's work and today's plan, one line per charge code with the notes under it
 #[derive(Default)]
 pub struct StandupContainer {
-    standup_entries: Vec<StandupEntry>,
+    sections: Vec<StandupSection>,
+    scroll: u16,
+    keys: HashMap<Command, String>,
+}
+
+pub struct StandupSection {
+    title: String,
+    entries: Vec<StandupEntry>,
 }
 
 pub struct StandupEntry {
@@ -19,43 +36,202 @@ pub struct StandupEntry {
     notes: String,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Display)]
+pub enum StandupFormat {
+    Markdown,
+    Slack,
+}
+
 impl StandupContainer {
-    pub fn aggregate_time_entries(&mut self, entries: &[TimeEntryVM], charge_codes: &[ChargeCode]) {
-        let mut aggregation: HashMap<i32, Vec<&TimeEntryVM>> = HashMap::new();
+    pub fn set_state(&mut self, full_state: &FullState, day: Day) {
+        // last week's entries stay until the cleanup, they only ever count as last week
+        let entries_for = |day: Day, last_week: bool| {
+            full_state
+                .get_vms_for_day(day)
+                .map_or(&[][..], Vec::as_slice)
+                .iter()
+                .filter(|entry| full_state.earlier_week_entries.contains(&entry.id) == last_week)
+                .cloned()
+                .collect::<Vec<_>>()
+        };
 
-        for entry in entries {
-            let key = entry.charge_code.as_ref().map_or(-1, |code| code.id);
-            aggregation.entry(key).or_default().push(entry);
+        let mut sections = Vec::new();
+        match previous_working_day(full_state, day) {
+            Some((previous_day, last_week)) => sections.push(StandupSection {
+                title: if last_week {
+                    format!("Yesterday (last {:?})", previous_day)
+                } else {
+                    format!("Yesterday ({:?})", previous_day)
+                },
+                entries: aggregate_time_entries(
+                    &entries_for(previous_day, last_week),
+                    &full_state.charge_codes,
+                ),
+            }),
+            None => sections.push(StandupSection {
+                title: "Yesterday".to_string(),
+                entries: Vec::new(),
+            }),
         }
+        sections.push(StandupSection {
+            title: format!("Today ({:?})", day),
+            entries: aggregate_time_entries(&entries_for(day, false), &full_state.charge_codes),
+        });
 
-        let mut standup_entries: Vec<StandupEntry> = Vec::default();
+        self.sections = sections;
+    }
 
-        for (k, v) in aggregation {
-            let notes = v
-                .iter()
-                .map(|entry| &entry.note)
-                .filter(|note| !note.is_empty())
-                .cloned()
-                .collect::<Vec<String>>()
-                .join("\n");
-
-            standup_entries.push(StandupEntry {
-                charge_code: get_code_from_id(charge_codes, k),
-                rounded_minutes: sum_to_nearest_quarter_hour(v.as_slice()),
-                notes,
-            });
+    pub fn set_keys(&mut self, keys: HashMap<Command, String>) {
+        self.keys = keys;
+    }
+
+    pub fn handle_command(&mut self, command: Command) {
+        match command {
+            Command::NextEntry => self.scroll = self.scroll.saturating_add(1),
+            Command::PreviousEntry => self.scroll = self.scroll.saturating_sub(1),
+            _ => {}
         }
+    }
+
+    pub fn summary(&self, format: StandupFormat) -> String {
+        standup_summary(&self.sections, format)
+    }
+
+    fn lines(&self) -> Vec<Line<'static>> {
+        let mut lines = Vec::new();
+        for section in &self.sections {
+            if !lines.is_empty() {
+                lines.push(Line::raw(""));
+            }
+            lines.push(Line::styled(
+                section.title.clone(),
+                theme::style(StyleName::Heading),
+            ));
+            if section.entries.is_empty() {
+                lines.push(Line::styled(
+                    "  nothing tracked",
+                    theme::style(StyleName::Faint),
+                ));
+            }
+            for entry in &section.entries {
+                lines.push(Line::from(vec![
+                    Span::styled(
+                        format!("  {}", entry.label()),
+                        Style::default().add_modifier(Modifier::BOLD),
+                    ),
+                    Span::styled(
+                        format!(" - {:.2} hrs", entry.hours()),
+                        theme::style(StyleName::Hint),
+                    ),
+                ]));
+                for note in entry.notes.lines().filter(|note| !note.trim().is_empty()) {
+                    lines.push(Line::raw(format!("    {}", note.trim())));
+                }
+            }
+        }
+        lines
+    }
+}
+
+impl StandupEntry {
+    fn label(&self) -> String {
+        match &self.charge_code {
+            Some(code) => format!("{} ({})", code.alias, code.code),
+            None => "No charge code".to_string(),
+        }
+    }
+
+    fn hours(&self) -> f64 {
+        self.rounded_minutes as f64 / 60.0
+    }
+}
+
+/// The closest earlier day this week that was worked, skipping days off. With none, like
+/// on a monday, it's the last day with entries left from last week, flagged as last week.
+fn previous_working_day(full_state: &FullState, day: Day) -> Option<(Day, bool)> {
+    let this_week = (0..i16::from(day)).rev().map(Day::from).find(|earlier| {
+        full_state.get_day_off(*earlier).is_none()
+            || full_state.day_progress(*earlier).worked_millis > 0
+    });
+    if let Some(earlier) = this_week {
+        return Some((earlier, false));
+    }
+
+    (0..=i16::from(Day::Friday))
+        .rev()
+        .map(Day::from)
+        .find(|earlier| {
+            full_state.get_vms_for_day(*earlier).is_some_and(|entries| {
+                entries
+                    .iter()
+                    .any(|entry| full_state.earlier_week_entries.contains(&entry.id))
+            })
+        })
+        .map(|earlier| (earlier, true))
+}
 
-        // make the output deterministic
-        standup_entries
-            .sort_by_key(|entry| entry.charge_code.as_ref().map_or(i32::MAX, |code| code.id));
+fn aggregate_time_entries(
+    entries: &[TimeEntryVM],
+    charge_codes: &[ChargeCode],
+) -> Vec<StandupEntry> {
+    let mut aggregation: HashMap<i32, Vec<&TimeEntryVM>> = HashMap::new();
 
-        self.standup_entries = standup_entries;
+    for entry in entries {
+        let key = entry.charge_code.as_ref().map_or(-1, |code| code.id);
+        aggregation.entry(key).or_default().push(entry);
     }
 
-    pub fn clear_entries(&mut self) {
-        self.standup_entries.clear();
+    let mut standup_entries: Vec<StandupEntry> = Vec::default();
+
+    for (k, v) in aggregation {
+        let notes = v
+            .iter()
+            .map(|entry| &entry.note)
+            .filter(|note| !note.is_empty())
+            .cloned()
+            .collect::<Vec<String>>()
+            .join("\n");
+
+        standup_entries.push(StandupEntry {
+            charge_code: get_code_from_id(charge_codes, k),
+            rounded_minutes: sum_to_nearest_quarter_hour(v.as_slice()),
+            notes,
+        });
+    }
+
+    // make the output deterministic
+    standup_entries
+        .sort_by_key(|entry| entry.charge_code.as_ref().map_or(i32::MAX, |code| code.id));
+
+    standup_entries
+}
+
+// slack's mrkdwn bolds with single stars and has no nested lists
+fn standup_summary(sections: &[StandupSection], format: StandupFormat) -> String {
+    let (bold, bullet, note_bullet) = match format {
+        StandupFormat::Markdown => ("**", "-", "  -"),
+        StandupFormat::Slack => ("*", "•", "    ◦"),
+    };
+
+    let mut lines = Vec::new();
+    for section in sections {
+        lines.push(format!("{bold}{}{bold}", section.title));
+        if section.entries.is_empty() {
+            lines.push(format!("{bullet} nothing tracked"));
+        }
+        for entry in &section.entries {
+            lines.push(format!(
+                "{bullet} {} - {:.2} hrs",
+                entry.label(),
+                entry.hours()
+            ));
+            for note in entry.notes.lines().filter(|note| !note.trim().is_empty()) {
+                lines.push(format!("{note_bullet} {}", note.trim()));
+            }
+        }
+        lines.push(String::new());
     }
+    lines.join("\n").trim_end().to_string() + "\n"
 }
 
 fn get_code_from_id(codes: &[ChargeCode], id: i32) -> Option<ChargeCode> {
@@ -74,32 +250,132 @@ fn sum_to_nearest_quarter_hour(entries: &[&TimeEntryVM]) -> u16 {
 
 impl Component for StandupContainer {
     fn draw(&mut self, f: &mut Frame<'_>, rect: Rect) -> Result<()> {
-        let chunks = Layout::default()
-            .direction(Direction::Vertical)
-            .constraints(
-                self.standup_entries
-                    .iter()
-                    .map(|_| Constraint::Length(rect.height / self.standup_entries.len() as u16))
-                    .collect::<Vec<_>>(),
-            )
-            .split(rect);
-
-        for (i, entry) in self.standup_entries.iter().enumerate() {
-            let title = format!(
-                "{} ({}) - {:.2} hrs",
-                entry.charge_code.as_ref().map_or("", |cc| &cc.alias),
-                entry.charge_code.as_ref().map_or("", |cc| &cc.code),
-                entry.rounded_minutes as f64 / 60.0 // Convert minutes to hours
-            );
-
-            let paragraph = Paragraph::new(entry.notes.as_str())
-                .block(Block::default().title(title).borders(Borders::ALL))
-                .wrap(Wrap { trim: true })
-                .style(Style::default());
-
-            f.render_widget(paragraph, chunks[i]);
-        }
+        let hints: Vec<String> = [
+            ("Copy", Command::CopyStandup),
+            ("Slack", Command::CopyStandupSlack),
+            ("Save", Command::SaveStandup),
+        ]
+        .into_iter()
+        .filter_map(|(label, command)| Some(format!("{} [{}]", label, self.keys.get(&command)?)))
+        .collect();
+        let block = Block::default()
+            .title("Standup")
+            .title_bottom(Line::from(hints.join("  ")).right_aligned())
+            .borders(Borders::ALL);
+        let lines = self.lines();
+
+        // stop scrolling once the last line is at the top
+        let max_scroll = lines.len().saturating_sub(1) as u16;
+        self.scroll = self.scroll.min(max_scroll);
+
+        let paragraph = Paragraph::new(lines)
+            .block(block)
+            .wrap(Wrap { trim: false })
+            .scroll((self.scroll, 0));
+        f.render_widget(paragraph, rect);
 
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared_lib::models::charge_code::ChargeCodeVM;
+
+    fn entry(id: i32, code_id: Option<i32>, minutes: i64, note: &str) -> TimeEntryVM {
+        TimeEntryVM {
+            id,
+            total_time: minutes * 60_000,
+            note: note.to_string(),
+            day: Day::Monday,
+            charge_code: code_id.map(|id| ChargeCodeVM {
+                id,
+                alias: format!("alias {id}"),
+            }),
+            ..Default::default()
+        }
+    }
+
+    fn code(id: i32) -> ChargeCode {
+        ChargeCode {
+            id,
+            alias: format!("Project {id}"),
+            code: format!("P-{id}"),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn monday_looks_back_to_last_week() {
+        let on = |day: Day, entry: TimeEntryVM| TimeEntryVM { day, ..entry };
+        let state = FullState {
+            time_entries: HashMap::from([
+                (
+                    Day::Monday,
+                    vec![
+                        entry(1, Some(1), 60, "last monday"),
+                        entry(4, Some(2), 30, "planning"),
+                    ],
+                ),
+                (
+                    Day::Thursday,
+                    vec![on(Day::Thursday, entry(2, Some(1), 60, "last thursday"))],
+                ),
+                (
+                    Day::Friday,
+                    vec![on(Day::Friday, entry(3, Some(1), 90, "last friday"))],
+                ),
+            ]),
+            charge_codes: vec![code(1), code(2)],
+            earlier_week_entries: vec![1, 2, 3],
+            ..Default::default()
+        };
+
+        let mut standup = StandupContainer::default();
+        standup.set_state(&state, Day::Monday);
+
+        assert_eq!(standup.sections[0].title, "Yesterday (last Friday)");
+        assert_eq!(standup.sections[0].entries.len(), 1);
+        assert_eq!(standup.sections[0].entries[0].notes, "last friday");
+        assert_eq!(standup.sections[1].entries.len(), 1);
+        assert_eq!(standup.sections[1].entries[0].notes, "planning");
+    }
+
+    #[test]
+    fn summaries_come_as_markdown_or_slack() {
+        let codes = [code(1), code(2)];
+        let sections = vec![
+            StandupSection {
+                title: "Yesterday (Tuesday)".to_string(),
+                entries: aggregate_time_entries(
+                    &[
+                        entry(1, Some(1), 50, "fixed login"),
+                        entry(2, Some(1), 40, "reviews\n"),
+                    ],
+                    &codes,
+                ),
+            },
+            StandupSection {
+                title: "Today (Wednesday)".to_string(),
+                entries: Vec::new(),
+            },
+        ];
+
+        assert_eq!(
+            standup_summary(&sections, StandupFormat::Markdown),
+            "**Yesterday (Tuesday)**\n\
+             - Project 1 (P-1) - 1.50 hrs\n  \
+               - fixed login\n  \
+               - reviews\n\
+             \n\
+             **Today (Wednesday)**\n\
+             - nothing tracked\n"
+        );
+        assert!(
+            standup_summary(&sections, StandupFormat::Slack).starts_with(
+                "*Yesterday (Tuesday)*\n• Project 1 (P-1) - 1.50 hrs\n    ◦ fixed login\n"
+            )
+        );
+    }
+}
@@ -0,0 +1,244 @@
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Local};
+use color_eyre::eyre::Result;
+use crossterm::event::{KeyEvent, MouseEvent};
+use ratatui::{
+    prelude::*,
+    widgets::{Block, Borders, Clear, List, ListItem, Paragraph},
+};
+
+use super::{
+    component_utils::{draw_tooltip_bar, is_left_click},
+    Component, Frame,
+};
+use crate::{
+    action::{Action, ApiAct, UIAct},
+    api_client::{offline::ConnectionStatus, ApiResponse},
+    theme::{self, StyleName},
+};
+
+// how long a message stays on the status line
+const MESSAGE_DISPLAY_TIME: Duration = Duration::from_secs(5);
+const ERROR_DISPLAY_TIME: Duration = Duration::from_secs(30);
+// older errors fall off the history
+const MAX_HISTORY: usize = 100;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum StatusMessage {
+    Saved,
+    Info(String),
+    Error(String),
+}
+
+/// One line at the bottom with the last message and the connection, plus a popup with past errors
+pub struct StatusBar {
+    connection: ConnectionStatus,
+    message: Option<(StatusMessage, Instant)>,
+    history: Vec<(DateTime<Local>, String)>,
+    history_key: String,
+    pub is_history_open: bool,
+}
+
+impl Default for StatusBar {
+    fn default() -> Self {
+        Self {
+            connection: ConnectionStatus::Online,
+            message: None,
+            history: Vec::new(),
+            history_key: String::new(),
+            is_history_open: false,
+        }
+    }
+}
+
+impl StatusBar {
+    pub fn set_connection(&mut self, connection: ConnectionStatus) {
+        self.connection = connection;
+    }
+
+    pub fn set_history_key(&mut self, key: String) {
+        self.history_key = key;
+    }
+
+    pub fn toggle_history(&mut self) {
+        self.is_history_open = !self.is_history_open;
+    }
+
+    pub fn push_error(&mut self, error: String) {
+        log::error!("{error}");
+        self.history.push((Local::now(), error.clone()));
+        if self.history.len() > MAX_HISTORY {
+            self.history.remove(0);
+        }
+        self.message = Some((StatusMessage::Error(error), Instant::now()));
+    }
+
+    fn push_saved(&mut self) {
+        // don't hide an error behind a save
+        if !matches!(self.message, Some((StatusMessage::Error(_), _))) {
+            self.message = Some((StatusMessage::Saved, Instant::now()));
+        }
+    }
+
+    pub fn push_info(&mut self, info: String) {
+        self.message = Some((StatusMessage::Info(info), Instant::now()));
+    }
+
+    fn expire_message(&mut self) {
+        let expired = match &self.message {
+            Some((StatusMessage::Saved | StatusMessage::Info(_), shown)) => {
+                shown.elapsed() > MESSAGE_DISPLAY_TIME
+            }
+            Some((StatusMessage::Error(_), shown)) => shown.elapsed() > ERROR_DISPLAY_TIME,
+            None => false,
+        };
+        if expired {
+            self.message = None;
+        }
+    }
+
+    fn connection_span(&self) -> Span<'static> {
+        match self.connection {
+            ConnectionStatus::Online => Span::styled("online", theme::style(StyleName::Success)),
+            ConnectionStatus::Offline { queued } => Span::styled(
+                format!("offline, {} queued", queued),
+                theme::style(StyleName::Offline),
+            ),
+            ConnectionStatus::Syncing { queued } => Span::styled(
+                format!("syncing {} changes", queued),
+                theme::style(StyleName::Syncing),
+            ),
+        }
+    }
+
+    fn draw_history(&self, f: &mut Frame<'_>, area: Rect) {
+        let modal_area = area.inner(&Margin {
+            horizontal: (area.width as f32 * 0.15) as u16,
+            vertical: (area.height as f32 * 0.15) as u16,
+        });
+        f.render_widget(Clear, modal_area);
+
+        let block = Block::default()
+            .title("Errors")
+            .borders(Borders::ALL)
+            .border_style(theme::style(StyleName::Error));
+        let inner_area = block.inner(modal_area);
+        f.render_widget(block, modal_area);
+
+        let list_area = Rect {
+            height: inner_area.height.saturating_sub(3),
+            ..inner_area
+        };
+        let items: Vec<ListItem> = match self.history.is_empty() {
+            true => vec![ListItem::new("No errors")],
+            // newest first
+            false => self
+                .history
+                .iter()
+                .rev()
+                .map(|(time, error)| {
+                    ListItem::new(Line::from(vec![
+                        Span::styled(
+                            time.format("%a %H:%M:%S ").to_string(),
+                            theme::style(StyleName::Hint),
+                        ),
+                        Span::raw(error.clone()),
+                    ]))
+                })
+                .collect(),
+        };
+        f.render_widget(List::new(items), list_area);
+
+        let bottom_bar_area = Rect {
+            y: inner_area.y + list_area.height,
+            height: inner_area.height - list_area.height,
+            ..inner_area
+        };
+        draw_tooltip_bar(f, bottom_bar_area, &["Close [any key]"]);
+    }
+}
+
+impl Component for StatusBar {
+    fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        match action {
+            Action::UI(UIAct::Tick) => self.expire_message(),
+            Action::UI(UIAct::Error(error)) => self.push_error(error),
+            Action::Api(ApiAct::Connection(connection)) => self.set_connection(connection),
+            Action::Api(ApiAct::Conflict(conflict)) => self.push_error(conflict),
+            Action::Api(ApiAct::Response(
+                ApiResponse::DayEntriesUpdate(_)
+                | ApiResponse::TimeEntryUpdate(_)
+                | ApiResponse::Split(_),
+            )) => self.push_saved(),
+            Action::Api(ApiAct::Response(ApiResponse::Undone(operation))) => self.push_info(
+                operation.map_or("Nothing to undo".to_string(), |operation| {
+                    format!("Undid {}", operation.description)
+                }),
+            ),
+            Action::Api(ApiAct::Response(ApiResponse::Redone(operation))) => self.push_info(
+                operation.map_or("Nothing to redo".to_string(), |operation| {
+                    format!("Redid {}", operation.description)
+                }),
+            ),
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn handle_key_events(&mut self, _key: KeyEvent) -> Result<Option<Action>> {
+        self.is_history_open = false;
+        Ok(None)
+    }
+
+    // like a key press, any click closes the history
+    fn handle_mouse_events(&mut self, mouse: MouseEvent) -> Result<Option<Action>> {
+        if is_left_click(mouse) {
+            self.is_history_open = false;
+        }
+        Ok(None)
+    }
+
+    fn is_capturing_input(&self) -> bool {
+        self.is_history_open
+    }
+
+    fn draw(&mut self, f: &mut Frame<'_>, area: Rect) -> Result<()> {
+        let layout = Layout::new(
+            Direction::Horizontal,
+            [Constraint::Min(0), Constraint::Length(40)],
+        )
+        .split(area);
+
+        let message = match &self.message {
+            Some((StatusMessage::Saved, _)) => {
+                Span::styled("Saved", theme::style(StyleName::Success))
+            }
+            Some((StatusMessage::Info(info), _)) => Span::raw(info.clone()),
+            Some((StatusMessage::Error(error), _)) => {
+                Span::styled(error.clone(), theme::style(StyleName::Error))
+            }
+            None => Span::raw(""),
+        };
+        f.render_widget(Paragraph::new(Line::from(message)), layout[0]);
+
+        let mut spans = Vec::new();
+        if !self.history.is_empty() && !self.history_key.is_empty() {
+            spans.push(Span::styled(
+                format!("errors ({}) [{}] ", self.history.len(), self.history_key),
+                theme::style(StyleName::Hint),
+            ));
+        }
+        spans.push(self.connection_span());
+        f.render_widget(
+            Paragraph::new(Line::from(spans)).alignment(Alignment::Right),
+            layout[1],
+        );
+
+        if self.is_history_open {
+            self.draw_history(f, f.size());
+        }
+
+        Ok(())
+    }
+}
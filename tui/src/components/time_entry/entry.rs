@@ -3,9 +3,10 @@ use shared_lib::models::time_entry::TimeEntryVM as ApiTimeEntry;
 use crate::{
     action::{Action, UIAct},
     components::Component,
+    theme::{self, StyleName},
     tui::Frame,
 };
-use chrono::{Duration, NaiveDateTime, TimeZone, Utc};
+use chrono::{DateTime, Duration, Utc};
 use color_eyre::eyre::Result;
 use ratatui::{prelude::*, widgets::*};
 use std::time::SystemTime;
@@ -18,6 +19,8 @@ pub struct TimeEntry {
     pub elapsed_time: Duration,
     pub is_active: bool,
     pub is_selected: bool,
+    // its charge code gets an entry every day
+    pub is_pinned: bool,
     pub start_time: Option<Instant>,
     delta_time: Option<Duration>,
 }
@@ -31,16 +34,16 @@ impl From<&ApiTimeEntry> for TimeEntry {
             elapsed_time: Duration::milliseconds(value.total_time as i64),
             is_active: value.is_active,
             is_selected: false,
+            is_pinned: false,
             start_time: convert_ndt_to_instant(&value.start_time),
             delta_time: None,
         }
     }
 }
 
-fn convert_ndt_to_instant(date: &Option<NaiveDateTime>) -> Option<Instant> {
+fn convert_ndt_to_instant(date: &Option<DateTime<Utc>>) -> Option<Instant> {
     match date {
-        Some(date) => {
-            let datetime = Utc.from_utc_datetime(date);
+        Some(datetime) => {
             let duration_since_epoch = Duration::seconds(datetime.timestamp());
 
             let now_duration_since_epoch = SystemTime::now()
@@ -70,6 +73,7 @@ impl Default for TimeEntry {
             elapsed_time: Duration::zero(),
             is_active: false,
             is_selected: false,
+            is_pinned: false,
             start_time: None,
             delta_time: None,
         }
@@ -86,9 +90,10 @@ impl TimeEntry {
     }
 
     fn get_border_style(&self) -> Style {
-        match self.is_selected {
-            true => Style::default().fg(Color::Yellow),
-            false => Style::default().fg(Color::DarkGray),
+        match (self.is_selected, self.is_active) {
+            (true, _) => theme::style(StyleName::SelectedEntry),
+            (false, true) => theme::style(StyleName::ActiveEntry),
+            (false, false) => theme::style(StyleName::Entry),
         }
     }
 
@@ -163,12 +168,21 @@ impl Component for TimeEntry {
         f.render_widget(Paragraph::new(button_text), chunks[0]);
 
         // Render elapsed time
-        let time_text = Text::styled(elapsed_time_str, Style::default());
+        let time_style = match self.is_active {
+            true => theme::style(StyleName::RunningTimer),
+            false => Style::default(),
+        };
+        let time_text = Text::styled(elapsed_time_str, time_style);
         f.render_widget(Paragraph::new(time_text), chunks[1]);
 
         // Render charge code
-        let charge_code_text = Text::styled(self.charge_code_string(), Style::default());
-        f.render_widget(Paragraph::new(charge_code_text), chunks[2]);
+        let mut charge_code_line = Line::from(self.charge_code_string());
+        if self.is_pinned {
+            charge_code_line
+                .spans
+                .push(Span::styled("  pinned", theme::style(StyleName::Favorite)));
+        }
+        f.render_widget(Paragraph::new(charge_code_line), chunks[2]);
 
         Ok(())
     }
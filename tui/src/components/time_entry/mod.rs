@@ -1,3 +1,4 @@
 pub mod entry;
 pub mod time_entry_container;
 pub mod time_utils;
+pub mod timeline;
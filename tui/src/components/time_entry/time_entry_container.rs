@@ -1,14 +1,28 @@
-use super::{entry::TimeEntry, time_utils::format_millis};
+use super::{
+    entry::TimeEntry,
+    time_utils::{format_hours_minutes, format_millis, progress_bar},
+};
 use crate::{
-    action::{Action, EditTimeAction, TTAct, UIAct},
+    action::{Action, Command, EditTimeAction, TTAct, UIAct},
     api_client::ApiRequest::*,
-    components::Component,
+    components::{
+        component_utils::{is_left_click, is_within},
+        Component,
+    },
+    theme::{self, StyleName},
     tui::Frame,
 };
+use chrono::Utc;
 use color_eyre::eyre::Result;
-use crossterm::event::{KeyCode, KeyEvent};
+use crossterm::event::{MouseEvent, MouseEventKind};
 use ratatui::{prelude::*, widgets::*};
-use shared_lib::models::day::Day;
+use shared_lib::{
+    models::{
+        day::Day,
+        targets::{TargetProgress, WorkTargets},
+    },
+    utils::time_zone::user_time_zone,
+};
 use tokio::sync::mpsc::UnboundedSender;
 
 pub struct TimeEntryContainer {
@@ -18,6 +32,12 @@ pub struct TimeEntryContainer {
     scroll_position: usize,
     rect: Rect,
     current_day: Day,
+    targets: WorkTargets,
+    // time worked this week on days other than `current_day`
+    other_days_millis: i64,
+    // time on `current_day` that isn't worked this week, from a holiday or PTO or from
+    // last week's entries the cleanup hasn't removed yet
+    uncounted_millis: i64,
 }
 
 impl TimeEntryContainer {
@@ -29,6 +49,9 @@ impl TimeEntryContainer {
             scroll_position: 0,
             rect: Rect::default(),
             current_day,
+            targets: WorkTargets::default(),
+            other_days_millis: 0,
+            uncounted_millis: 0,
         }
     }
 
@@ -42,23 +65,127 @@ impl TimeEntryContainer {
 
     pub fn set_index(&mut self, index: usize) {
         self.selected_index = index;
+        self.adjust_scroll_position(self.calculate_num_visible_entries());
+    }
+
+    pub fn selected_index(&self) -> usize {
+        self.selected_index
     }
 
     pub fn set_day(&mut self, day: Day) {
         self.current_day = day;
     }
 
+    pub fn set_targets(
+        &mut self,
+        targets: WorkTargets,
+        other_days_millis: i64,
+        uncounted_millis: i64,
+    ) {
+        self.targets = targets;
+        self.other_days_millis = other_days_millis;
+        self.uncounted_millis = uncounted_millis;
+    }
+
+    fn calculate_worked_millis(&self) -> i64 {
+        self.calculate_total_millis() - self.uncounted_millis
+    }
+
     pub fn get_selected_entry(&self) -> Option<TimeEntry> {
         self.entries.get(self.selected_index).cloned()
     }
 
+    /// Deletes the entry with this id, which isn't always the selected one by the time a
+    /// delete is confirmed
+    pub fn delete_entry(&mut self, id: i32) -> Result<()> {
+        let Some(tx) = &self.command_tx else {
+            return Ok(());
+        };
+        tx.send(Action::api_request_action(DeleteEntry { id }))?;
+
+        // state will get updated after this request is processed, but
+        // removing for now so we can keep track of the selected index
+        let Some(index) = self.entries.iter().position(|entry| entry.id == id) else {
+            return Ok(());
+        };
+        self.entries.remove(index);
+        if index < self.selected_index {
+            self.selected_index -= 1;
+        }
+
+        // update selected index, if we're out of bounds after the deletion
+        if self.selected_index >= self.entries.len() && !self.entries.is_empty() {
+            self.selected_index = self.entries.len() - 1;
+        } else if self.entries.is_empty() {
+            self.selected_index = 0;
+        }
+        Ok(())
+    }
+
     fn calculate_total_millis(&self) -> i64 {
         self.entries.iter().map(|e| e.total_milliseconds()).sum()
     }
 
+    fn day_progress(&self) -> TargetProgress {
+        TargetProgress::new(
+            self.calculate_worked_millis(),
+            self.targets.minutes_for_day(self.current_day),
+        )
+    }
+
     fn total_elapsed_time_string(&self) -> String {
-        let time_string = format_millis(&self.calculate_total_millis());
-        format!("Total Time: {}", time_string)
+        let total_millis = self.calculate_total_millis();
+        let time_string = format_millis(&total_millis);
+        let progress = self.day_progress();
+
+        if progress.target_millis <= 0 {
+            return format!("Total Time: {}", time_string);
+        }
+
+        let status = match progress.remaining_millis() {
+            0 => format!("{} over", format_hours_minutes(&progress.balance_millis())),
+            remaining => {
+                let left = format!("{} left", format_hours_minutes(&remaining));
+                // a finish time only makes sense for the day that's being worked
+                match progress.projected_finish(Utc::now()) {
+                    Some(finish) if self.current_day == Day::get_current_day() => format!(
+                        "{}, done ~{}",
+                        left,
+                        finish.with_timezone(&user_time_zone()).format("%H:%M")
+                    ),
+                    _ => left,
+                }
+            }
+        };
+
+        format!(
+            "Total Time: {} {} {:.0}% | {}",
+            time_string,
+            progress_bar(progress.ratio(), 10),
+            progress.ratio() * 100.0,
+            status
+        )
+    }
+
+    fn week_progress_string(&self) -> String {
+        let progress = TargetProgress::new(
+            self.other_days_millis + self.calculate_worked_millis(),
+            self.targets.adjusted_weekly_minutes(),
+        );
+        let carried_millis = self.targets.carried_balance_minutes * 60_000;
+        let carried = if carried_millis > 0 {
+            format!("+{}", format_hours_minutes(&carried_millis))
+        } else {
+            format_hours_minutes(&carried_millis)
+        };
+
+        format!(
+            "Week: {} / {} | {} left | carried {}",
+            format_hours_minutes(&progress.worked_millis),
+            format_hours_minutes(&progress.target_millis),
+            format_hours_minutes(&progress.remaining_millis()),
+            carried
+        )
     }
 
     pub fn send_index_action(&mut self) {
@@ -83,37 +210,52 @@ impl TimeEntryContainer {
         };
     }
 
+    // the entry drawn under a point, if there is one
+    fn entry_at(&self, mouse: MouseEvent) -> Option<usize> {
+        let inner_area = self.rect.inner(&Margin {
+            vertical: 1,
+            horizontal: 1,
+        });
+        if !is_within(inner_area, mouse) {
+            return None;
+        }
+        let entry_height = 3;
+        let slot = (mouse.row - inner_area.y) as usize / entry_height;
+        // the rows left under the last whole entry are empty
+        if slot >= inner_area.height as usize / entry_height {
+            return None;
+        }
+        let index = self.scroll_position + slot;
+        (index < self.entries.len()).then_some(index)
+    }
+
     fn calculate_num_visible_entries(&self) -> usize {
         let entry_height = 3; // Assuming each entry takes 3 lines
         self.rect.height as usize / entry_height
     }
-}
 
-impl Component for TimeEntryContainer {
-    fn update(&mut self, action: Action) -> Result<Option<Action>> {
-        match action {
-            Action::UI(UIAct::Tick) => {
-                for entry in &mut self.entries {
-                    entry.update(action.clone())?;
-                }
-            }
-            _ => { /* ... */ }
+    // swapped here straight away and the selection goes with it, the server's order follows
+    fn move_selected_entry(&mut self, offset: i32) -> Result<()> {
+        let target = self.selected_index as i64 + offset as i64;
+        if !(0..self.entries.len() as i64).contains(&target) {
+            return Ok(());
         }
-        Ok(None)
-    }
-
-    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
-        self.command_tx = Some(tx.clone());
-
+        let target = target as usize;
+        let id = self.entries[self.selected_index].id;
+        if let Some(tx) = &self.command_tx {
+            tx.send(Action::api_request_action(MoveEntry { id, offset }))?;
+        }
+        self.entries.swap(self.selected_index, target);
+        self.set_index(target);
         Ok(())
     }
 
-    fn handle_key_events(&mut self, key: KeyEvent) -> Result<Option<Action>> {
+    pub fn handle_command(&mut self, command: Command) -> Result<()> {
         let num_visible_entries = self.calculate_num_visible_entries();
         let num_entries = self.entries.len();
 
-        match key.code {
-            KeyCode::Up | KeyCode::Char('k') => {
+        match command {
+            Command::PreviousEntry => {
                 if !self.entries.is_empty() {
                     self.selected_index = if self.selected_index == 0 {
                         num_entries - 1 // Wrap to the end
@@ -124,14 +266,14 @@ impl Component for TimeEntryContainer {
                     self.send_index_action();
                 }
             }
-            KeyCode::Down | KeyCode::Char('j') => {
+            Command::NextEntry => {
                 if !self.entries.is_empty() {
                     self.selected_index = (self.selected_index + 1) % num_entries; // Wrap to the beginning
                     self.adjust_scroll_position(num_visible_entries);
                     self.send_index_action();
                 }
             }
-            KeyCode::Char(' ') => {
+            Command::ToggleTimer => {
                 // Toggle the timer for the selected entry
                 if let Some(entry) = self.entries.get_mut(self.selected_index) {
                     entry.is_active = !entry.is_active;
@@ -139,14 +281,12 @@ impl Component for TimeEntryContainer {
                         // went from pause to play
                         true => {
                             if let Some(tx) = &self.command_tx {
-                                tx.send(Action::TT(TTAct::SaveState))?;
                                 tx.send(Action::api_request_action(PlayEntry { id: entry.id }))?;
                             }
                         }
                         // went from play to pause
                         false => {
                             if let Some(tx) = &self.command_tx {
-                                tx.send(Action::TT(TTAct::SaveState))?;
                                 tx.send(Action::api_request_action(PauseEntry { id: entry.id }))?;
                             }
                         }
@@ -161,15 +301,14 @@ impl Component for TimeEntryContainer {
                     }
                 }
             }
-            KeyCode::Char('a') => {
+            Command::AddEntry => {
                 if let Some(tx) = &self.command_tx {
-                    tx.send(Action::TT(TTAct::SaveState))?;
                     tx.send(Action::api_request_action(CreateTimeEntry {
                         day: self.current_day.into(),
                     }))?;
                 }
             }
-            KeyCode::Char('t') => {
+            Command::EditTime => {
                 if let (Some(tx), Some(entry)) = (&self.command_tx, self.get_selected_entry()) {
                     let edit_time_action = EditTimeAction {
                         id: entry.id,
@@ -178,29 +317,24 @@ impl Component for TimeEntryContainer {
                     tx.send(Action::edit_time_action(edit_time_action))?;
                 }
             }
-            KeyCode::Char('d') => {
-                if let (Some(tx), Some(entry)) = (&self.command_tx, self.get_selected_entry()) {
-                    tx.send(Action::TT(TTAct::SaveState))?;
-                    tx.send(Action::api_request_action(DeleteEntry { id: entry.id }))?;
-
-                    // state will get updated after this request is processed, but
-                    // removing for now so we can keep track of the selected index
-                    self.entries.remove(self.selected_index);
-
-                    // update selected index, if we're out of bounds after the deletion
-                    if self.selected_index >= self.entries.len() && !self.entries.is_empty() {
-                        self.selected_index = self.entries.len() - 1;
-                    } else if self.entries.is_empty() {
-                        self.selected_index = 0;
-                    }
+            Command::DeleteEntry => {
+                if let Some(entry) = self.get_selected_entry() {
+                    self.delete_entry(entry.id)?;
                 }
             }
-            KeyCode::Char('c') => {
+            Command::MoveEntryUp | Command::MoveEntryDown => {
+                let offset = match command {
+                    Command::MoveEntryUp => -1,
+                    _ => 1,
+                };
+                self.move_selected_entry(offset)?;
+            }
+            Command::EditChargeCode => {
                 if let (Some(tx), Some(entry)) = (&self.command_tx, self.get_selected_entry()) {
                     tx.send(Action::TT(TTAct::EditChargeCode(entry.id)))?;
                 }
             }
-            KeyCode::Char('s') => {
+            Command::SwapTime => {
                 if let (Some(tx), Some(entry)) = (&self.command_tx, self.get_selected_entry()) {
                     tx.send(Action::TT(TTAct::SwapTime(entry.id)))?;
                 }
@@ -208,14 +342,63 @@ impl Component for TimeEntryContainer {
             _ => {}
         }
 
+        Ok(())
+    }
+}
+
+impl Component for TimeEntryContainer {
+    fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        match action {
+            Action::UI(UIAct::Tick) => {
+                for entry in &mut self.entries {
+                    entry.update(action.clone())?;
+                }
+            }
+            _ => { /* ... */ }
+        }
+        Ok(None)
+    }
+
+    // a click selects an entry and the wheel steps through them without wrapping around
+    fn handle_mouse_events(&mut self, mouse: MouseEvent) -> Result<Option<Action>> {
+        if !is_within(self.rect, mouse) {
+            return Ok(None);
+        }
+        match mouse.kind {
+            MouseEventKind::ScrollDown if self.selected_index + 1 < self.entries.len() => {
+                self.handle_command(Command::NextEntry)?
+            }
+            MouseEventKind::ScrollUp if self.selected_index > 0 => {
+                self.handle_command(Command::PreviousEntry)?
+            }
+            _ if is_left_click(mouse) => {
+                if let Some(index) = self.entry_at(mouse) {
+                    self.set_index(index);
+                    self.send_index_action();
+                }
+            }
+            _ => {}
+        }
         Ok(None)
     }
 
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
+        self.command_tx = Some(tx.clone());
+
+        Ok(())
+    }
+
     fn draw(&mut self, f: &mut Frame<'_>, rect: Rect) -> Result<()> {
         self.rect = rect;
 
+        let progress = self.day_progress();
+        let title_style = match progress.target_millis > 0 && progress.remaining_millis() == 0 {
+            true => theme::style(StyleName::OverTarget),
+            false => Style::default(),
+        };
         let block = Block::default()
-            .title(self.total_elapsed_time_string())
+            .title(Line::styled(self.total_elapsed_time_string(), title_style))
+            .title_bottom(self.week_progress_string())
             .borders(Borders::ALL);
         f.render_widget(block, rect);
 
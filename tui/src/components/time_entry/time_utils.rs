@@ -9,3 +9,20 @@ pub fn format_millis(millis: &i64) -> String {
         hours, minutes, seconds, milliseconds
     )
 }
+
+pub fn format_hours_minutes(millis: &i64) -> String {
+    let sign = if *millis < 0 { "-" } else { "" };
+    let minutes = millis.abs() / 60_000;
+    format!("{}{}h{:02}m", sign, minutes / 60, minutes % 60)
+}
+
+// hours rounded to the nearest quarter, the way Costpoint wants them
+pub fn quarter_hours(millis: &i64) -> f64 {
+    (*millis as f64 / 900_000.0).round() / 4.0
+}
+
+// filled/empty blocks for the share of a target that has been worked
+pub fn progress_bar(ratio: f64, width: usize) -> String {
+    let filled = (ratio.clamp(0.0, 1.0) * width as f64).round() as usize;
+    format!("{}{}", "█".repeat(filled), "░".repeat(width - filled))
+}
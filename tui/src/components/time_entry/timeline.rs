@@ -0,0 +1,282 @@
+use crate::components::{time_entry::time_utils::format_hours_minutes, Component};
+use crate::theme::{self, StyleName};
+use crate::tui::Frame;
+use chrono::{DateTime, TimeZone, Timelike, Utc};
+use color_eyre::eyre::Result;
+use ratatui::{prelude::*, widgets::*};
+use shared_lib::{
+    models::{time_entry::TimeEntryVM, time_segment::TimeSegment},
+    utils::time_zone::user_time_zone,
+};
+
+// rows can stand for any of these, the smallest that fits the day is used
+const SLOT_MINUTES: [i64; 6] = [5, 10, 15, 20, 30, 60];
+const MINUTES_IN_DAY: i64 = 24 * 60;
+
+/// When an entry was worked, in minutes after local midnight
+#[derive(Debug, Clone, PartialEq)]
+struct TimelineSpan {
+    entry_index: usize,
+    start: i64,
+    end: i64,
+}
+
+#[derive(Debug, Default, PartialEq)]
+struct Coverage {
+    gap_minutes: i64,
+    overlap_minutes: i64,
+}
+
+/// The day's segments top to bottom, with the gaps between them and any overlaps called out
+#[derive(Default)]
+pub struct Timeline {
+    entries: Vec<TimeEntryVM>,
+    segments: Vec<TimeSegment>,
+}
+
+fn minute_of_day<T: TimeZone>(time: DateTime<Utc>, tz: &T) -> i64 {
+    let local = time.with_timezone(tz);
+    (local.hour() * 60 + local.minute()) as i64
+}
+
+fn timeline_spans<T: TimeZone>(
+    entries: &[TimeEntryVM],
+    segments: &[TimeSegment],
+    now: DateTime<Utc>,
+    tz: &T,
+) -> Vec<TimelineSpan> {
+    let running = entries
+        .iter()
+        .filter_map(|entry| entry.start_time.map(|start| (entry.id, start, now)));
+    let stopped = segments
+        .iter()
+        .map(|segment| (segment.time_entry_id, segment.start_time, segment.end_time));
+
+    stopped
+        .chain(running)
+        .filter_map(|(entry_id, start, end)| {
+            let entry_index = entries.iter().position(|entry| entry.id == entry_id)?;
+            let start_minute = minute_of_day(start, tz);
+            // anything that runs past midnight is cut off there
+            let is_same_day =
+                start.with_timezone(tz).date_naive() == end.with_timezone(tz).date_naive();
+            let end_minute = match is_same_day {
+                true => minute_of_day(end, tz),
+                false => MINUTES_IN_DAY,
+            };
+            Some(TimelineSpan {
+                entry_index,
+                start: start_minute,
+                end: end_minute.max(start_minute + 1),
+            })
+        })
+        .collect()
+}
+
+// unaccounted and double counted minutes between the first start and the last end
+fn coverage(spans: &[TimelineSpan]) -> Coverage {
+    let (Some(first), Some(last)) = (
+        spans.iter().map(|span| span.start).min(),
+        spans.iter().map(|span| span.end).max(),
+    ) else {
+        return Coverage::default();
+    };
+
+    let mut coverage = Coverage::default();
+    for minute in first..last {
+        match spans_at(spans, minute, minute + 1).len() {
+            0 => coverage.gap_minutes += 1,
+            1 => {}
+            _ => coverage.overlap_minutes += 1,
+        }
+    }
+    coverage
+}
+
+fn spans_at(spans: &[TimelineSpan], from: i64, to: i64) -> Vec<&TimelineSpan> {
+    spans
+        .iter()
+        .filter(|span| span.start < to && span.end > from)
+        .collect()
+}
+
+// the hours the timeline covers and how long each row is
+fn layout_slots(spans: &[TimelineSpan], rows: i64) -> (i64, i64, i64) {
+    let first = spans.iter().map(|span| span.start).min().unwrap_or(9 * 60);
+    let last = spans.iter().map(|span| span.end).max().unwrap_or(17 * 60);
+    let first = first / 60 * 60;
+    let last = ((last + 59) / 60 * 60).max(first + 60);
+
+    let slot = SLOT_MINUTES
+        .into_iter()
+        .find(|slot| (last - first + slot - 1) / slot <= rows.max(1))
+        .unwrap_or(60);
+    (first, last, slot)
+}
+
+impl Timeline {
+    pub fn set_day(&mut self, entries: &[TimeEntryVM], segments: &[TimeSegment]) {
+        self.entries = entries.to_vec();
+        let entry_ids: Vec<i32> = entries.iter().map(|entry| entry.id).collect();
+        self.segments = segments
+            .iter()
+            .filter(|segment| entry_ids.contains(&segment.time_entry_id))
+            .cloned()
+            .collect();
+    }
+
+    fn label(&self, entry_index: usize) -> String {
+        let entry = &self.entries[entry_index];
+        match (&entry.charge_code, entry.note.lines().next()) {
+            (Some(code), _) => code.alias.clone(),
+            (None, Some(note)) if !note.is_empty() => note.to_string(),
+            _ => format!("#{}", entry.id),
+        }
+    }
+}
+
+impl Component for Timeline {
+    fn draw(&mut self, f: &mut Frame<'_>, rect: Rect) -> Result<()> {
+        let block = Block::default().title("Timeline").borders(Borders::ALL);
+        let inner = block.inner(rect);
+        f.render_widget(block, rect);
+        if inner.height < 2 {
+            return Ok(());
+        }
+
+        let spans = timeline_spans(&self.entries, &self.segments, Utc::now(), &user_time_zone());
+        let worked_from = spans.iter().map(|span| span.start).min();
+        let worked_to = spans.iter().map(|span| span.end).max();
+        let (first, last, slot) = layout_slots(&spans, inner.height as i64 - 1);
+
+        let mut lines = Vec::new();
+        let mut previous: Vec<usize> = Vec::new();
+        for slot_start in (first..last).step_by(slot as usize) {
+            let time = match slot_start % 60 == 0 || slot_start == first {
+                true => format!("{:02}:{:02} ", slot_start / 60, slot_start % 60),
+                false => "      ".to_string(),
+            };
+            let mut line = vec![Span::styled(time, theme::style(StyleName::Hint))];
+
+            let mut distinct: Vec<usize> = spans_at(&spans, slot_start, slot_start + slot)
+                .iter()
+                .map(|span| span.entry_index)
+                .collect();
+            distinct.sort();
+            distinct.dedup();
+            let is_new_run = distinct != previous;
+
+            match distinct.as_slice() {
+                [] => {
+                    let is_gap = worked_from.is_some_and(|from| slot_start >= from)
+                        && worked_to.is_some_and(|to| slot_start < to);
+                    let text = match is_new_run {
+                        true => "┊ unaccounted",
+                        false => "┊",
+                    };
+                    if is_gap {
+                        line.push(Span::styled(text, theme::style(StyleName::Faint)));
+                    }
+                }
+                [entry_index] => {
+                    let style = theme::palette_color(*entry_index)
+                        .map_or(Style::default(), |color| Style::default().fg(color));
+                    line.push(Span::styled("█ ", style));
+                    if is_new_run {
+                        line.push(Span::raw(self.label(*entry_index)));
+                    }
+                }
+                _ => {
+                    line.push(Span::styled("▓ ", theme::style(StyleName::Error)));
+                    if is_new_run {
+                        let labels: Vec<String> =
+                            distinct.iter().map(|index| self.label(*index)).collect();
+                        line.push(Span::styled(
+                            format!("overlap: {}", labels.join(", ")),
+                            theme::style(StyleName::Error),
+                        ));
+                    }
+                }
+            }
+            previous = distinct;
+            lines.push(Line::from(line));
+        }
+
+        let list_area = Rect {
+            height: inner.height - 1,
+            ..inner
+        };
+        f.render_widget(Paragraph::new(lines), list_area);
+
+        let coverage = coverage(&spans);
+        let summary = Line::from(vec![
+            Span::styled(
+                format!(
+                    "gaps {}",
+                    format_hours_minutes(&(coverage.gap_minutes * 60_000))
+                ),
+                theme::style(StyleName::Hint),
+            ),
+            Span::styled(
+                format!(
+                    "  overlap {}",
+                    format_hours_minutes(&(coverage.overlap_minutes * 60_000))
+                ),
+                match coverage.overlap_minutes {
+                    0 => theme::style(StyleName::Hint),
+                    _ => theme::style(StyleName::Error),
+                },
+            ),
+        ]);
+        let summary_area = Rect {
+            y: inner.y + inner.height - 1,
+            height: 1,
+            ..inner
+        };
+        f.render_widget(Paragraph::new(summary), summary_area);
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn span(entry_index: usize, start: i64, end: i64) -> TimelineSpan {
+        TimelineSpan {
+            entry_index,
+            start,
+            end,
+        }
+    }
+
+    #[test]
+    fn counts_gaps_and_overlaps() {
+        // 9:00-10:30, 10:00-11:00, then nothing until 12:00-12:30
+        let spans = vec![
+            span(0, 9 * 60, 10 * 60 + 30),
+            span(1, 10 * 60, 11 * 60),
+            span(2, 12 * 60, 12 * 60 + 30),
+        ];
+
+        assert_eq!(
+            coverage(&spans),
+            Coverage {
+                gap_minutes: 60,
+                overlap_minutes: 30,
+            }
+        );
+        assert_eq!(coverage(&[]), Coverage::default());
+    }
+
+    #[test]
+    fn rows_get_longer_to_fit_the_day() {
+        let spans = vec![span(0, 8 * 60 + 10, 16 * 60 + 50)];
+
+        // 8:00 to 17:00 is 36 quarter hours
+        assert_eq!(layout_slots(&spans, 40), (8 * 60, 17 * 60, 15));
+        assert_eq!(layout_slots(&spans, 20), (8 * 60, 17 * 60, 30));
+        assert_eq!(layout_slots(&[], 100), (9 * 60, 17 * 60, 5));
+    }
+}
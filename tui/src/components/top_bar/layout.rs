@@ -1,12 +1,16 @@
 use super::{mode_selector::ModeSelector, weekday_selector::WeekdaySelector};
-use crate::{action::Action, components::Component};
+use crate::{
+    action::{Action, Command},
+    components::Component,
+};
 use color_eyre::eyre::Result;
-use crossterm::event::{KeyCode, KeyEvent};
+use crossterm::event::MouseEvent;
 use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
     Frame,
 };
 use shared_lib::models::day::Day;
+use std::collections::HashMap;
 use tokio::sync::mpsc::UnboundedSender;
 
 #[derive(Debug, Clone, Default)]
@@ -16,23 +20,34 @@ pub struct TopBar {
 }
 
 impl TopBar {
-    fn is_mode_selector_event(&self, key: &KeyEvent) -> bool {
-        matches!(key.code, KeyCode::Char('0') | KeyCode::Char('9'))
-    }
-
     pub fn new(selected_day: Day) -> Self {
         Self {
             weekday_selector: WeekdaySelector::new(selected_day),
             mode_selector: ModeSelector::default(),
         }
     }
+
+    pub fn set_keys(&mut self, keys: HashMap<Command, String>) {
+        self.weekday_selector.set_keys(keys.clone());
+        self.mode_selector.set_keys(keys);
+    }
+
+    pub fn select_day(&mut self, day: Day) -> Result<()> {
+        self.weekday_selector.select_day(day)
+    }
+
+    pub fn handle_command(&mut self, command: Command) -> Result<()> {
+        self.weekday_selector.handle_command(command)?;
+        self.mode_selector.handle_command(command);
+        Ok(())
+    }
 }
 
 impl Component for TopBar {
     fn draw(&mut self, f: &mut Frame<'_>, area: Rect) -> Result<()> {
         let layout = Layout::new(
             Direction::Horizontal,
-            vec![Constraint::Percentage(75), Constraint::Percentage(25)],
+            vec![Constraint::Percentage(65), Constraint::Percentage(35)],
         )
         .split(area);
 
@@ -49,13 +64,10 @@ impl Component for TopBar {
         Ok(())
     }
 
-    fn handle_key_events(&mut self, key: KeyEvent) -> Result<Option<Action>> {
-        self.weekday_selector.handle_key_events(key)?;
-
-        if self.is_mode_selector_event(&key) {
-            self.mode_selector.handle_key_events(key)?;
+    fn handle_mouse_events(&mut self, mouse: MouseEvent) -> Result<Option<Action>> {
+        match self.weekday_selector.handle_mouse_events(mouse)? {
+            Some(action) => Ok(Some(action)),
+            None => self.mode_selector.handle_mouse_events(mouse),
         }
-
-        Ok(None)
     }
 }
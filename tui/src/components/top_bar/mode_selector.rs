@@ -1,13 +1,29 @@
-use crate::action::TTAct::UpdateMode;
-use crate::{action::Action, components::Component, mode::Mode, tui::Frame};
+use crate::action::TTAct::{self, UpdateMode};
+use crate::{
+    action::{Action, Command},
+    components::{
+        component_utils::{is_left_click, is_within, span_areas},
+        Component,
+    },
+    mode::Mode,
+    theme::{self, StyleName},
+    tui::Frame,
+};
 use color_eyre::eyre::Result;
-use crossterm::event::{KeyCode, KeyEvent};
+use crossterm::event::MouseEvent;
 use ratatui::{prelude::*, widgets::*};
+use std::collections::HashMap;
 use tokio::sync::mpsc::UnboundedSender;
+
+const MODES: [Command; 3] = [Command::CrudMode, Command::StandupMode, Command::WeekMode];
+
 #[derive(Debug, Clone, Default)]
 pub struct ModeSelector {
     selected_mode: Mode,
     command_tx: Option<UnboundedSender<Action>>,
+    keys: HashMap<Command, String>,
+    // where each mode was last drawn, for clicks
+    mode_areas: Vec<Rect>,
 }
 
 impl ModeSelector {
@@ -22,27 +38,35 @@ impl ModeSelector {
         }
     }
 
-    fn style_by_is_mode_selected(&mut self, mode: Mode) -> Style {
-        match self.selected_mode == mode {
-            true => Style::default()
-                .dim()
-                .fg(Color::White)
-                .add_modifier(Modifier::UNDERLINED),
-            false => Style::default().dim().fg(Color::Gray),
+    pub fn set_keys(&mut self, keys: HashMap<Command, String>) {
+        self.keys = keys;
+    }
+
+    fn label(&self, name: &str, command: Command) -> String {
+        match self.keys.get(&command) {
+            Some(key) => format!("{} ({})", name, key),
+            None => name.to_string(),
         }
     }
-}
 
-impl Component for ModeSelector {
-    fn handle_key_events(&mut self, key: KeyEvent) -> Result<Option<Action>> {
-        match key.code {
-            KeyCode::Char('9') => self.select_mode(Mode::Crud),
-            KeyCode::Char('0') => self.select_mode(Mode::Standup),
+    pub fn handle_command(&mut self, command: Command) {
+        match command {
+            Command::CrudMode => self.select_mode(Mode::Crud),
+            Command::StandupMode => self.select_mode(Mode::Standup),
+            Command::WeekMode => self.select_mode(Mode::Week),
             _ => {}
         };
-        Ok(None)
     }
 
+    fn style_by_is_mode_selected(&mut self, mode: Mode) -> Style {
+        match self.selected_mode == mode {
+            true => theme::style(StyleName::SelectedTab),
+            false => theme::style(StyleName::Tab),
+        }
+    }
+}
+
+impl Component for ModeSelector {
     fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
         self.command_tx = Some(tx.clone());
 
@@ -51,11 +75,29 @@ impl Component for ModeSelector {
 
     fn draw(&mut self, f: &mut Frame<'_>, rect: Rect) -> Result<()> {
         let line = Line::from(vec![
-            Span::styled("CRUD (9)", self.style_by_is_mode_selected(Mode::Crud)),
-            Span::styled(" | ", Style::default().dim().fg(Color::Gray)),
-            Span::styled("Standup (0)", self.style_by_is_mode_selected(Mode::Standup)),
+            Span::styled(
+                self.label("CRUD", Command::CrudMode),
+                self.style_by_is_mode_selected(Mode::Crud),
+            ),
+            Span::styled(" | ", theme::style(StyleName::Tab)),
+            Span::styled(
+                self.label("Standup", Command::StandupMode),
+                self.style_by_is_mode_selected(Mode::Standup),
+            ),
+            Span::styled(" | ", theme::style(StyleName::Tab)),
+            Span::styled(
+                self.label("Week", Command::WeekMode),
+                self.style_by_is_mode_selected(Mode::Week),
+            ),
         ]);
 
+        // the line is right aligned inside the border
+        let x = rect.right().saturating_sub(1 + line.width() as u16);
+        self.mode_areas = span_areas(&line, x.max(rect.x + 1), rect.y + 1)
+            .into_iter()
+            .step_by(2)
+            .collect();
+
         let paragraph = Paragraph::new(vec![line])
             .block(
                 Block::default()
@@ -70,4 +112,15 @@ impl Component for ModeSelector {
 
         Ok(())
     }
+
+    fn handle_mouse_events(&mut self, mouse: MouseEvent) -> Result<Option<Action>> {
+        if !is_left_click(mouse) {
+            return Ok(None);
+        }
+        let clicked = self
+            .mode_areas
+            .iter()
+            .position(|area| is_within(*area, mouse));
+        Ok(clicked.map(|i| Action::TT(TTAct::Command(MODES[i]))))
+    }
 }
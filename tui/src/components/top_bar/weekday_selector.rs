@@ -1,18 +1,37 @@
 use crate::{
-    action::{Action, TTAct::ChangeDay},
-    components::Component,
+    action::{
+        Action, Command,
+        TTAct::{self, ChangeDay},
+    },
+    components::{
+        component_utils::{is_left_click, is_within, span_areas},
+        Component,
+    },
+    theme::{self, StyleName},
     tui::Frame,
 };
 use color_eyre::eyre::Result;
-use crossterm::event::{KeyCode, KeyEvent};
+use crossterm::event::MouseEvent;
 use ratatui::{prelude::*, widgets::*};
 use shared_lib::models::day::Day;
+use std::collections::HashMap;
 use tokio::sync::mpsc::UnboundedSender;
 
+const DAYS: [(&str, Command); 5] = [
+    ("Mon", Command::Monday),
+    ("Tue", Command::Tuesday),
+    ("Wed", Command::Wednesday),
+    ("Thu", Command::Thursday),
+    ("Fri", Command::Friday),
+];
+
 #[derive(Debug, Clone, Default)]
 pub struct WeekdaySelector {
     command_tx: Option<UnboundedSender<Action>>,
     selected_day: Day,
+    keys: HashMap<Command, String>,
+    // where each day was last drawn, for clicks
+    day_areas: Vec<Rect>,
 }
 
 impl WeekdaySelector {
@@ -20,10 +39,16 @@ impl WeekdaySelector {
         Self {
             selected_day,
             command_tx: None,
+            keys: HashMap::new(),
+            day_areas: Vec::new(),
         }
     }
 
-    fn select_day(&mut self, day: Day) -> Result<()> {
+    pub fn set_keys(&mut self, keys: HashMap<Command, String>) {
+        self.keys = keys;
+    }
+
+    pub fn select_day(&mut self, day: Day) -> Result<()> {
         self.selected_day = day;
         if let Some(tx) = &self.command_tx {
             tx.send(Action::TT(ChangeDay(day)))?;
@@ -48,71 +73,79 @@ impl WeekdaySelector {
         }
         Ok(())
     }
+
+    pub fn handle_command(&mut self, command: Command) -> Result<()> {
+        match command {
+            Command::Monday => self.select_day(Day::Monday)?,
+            Command::Tuesday => self.select_day(Day::Tuesday)?,
+            Command::Wednesday => self.select_day(Day::Wednesday)?,
+            Command::Thursday => self.select_day(Day::Thursday)?,
+            Command::Friday => self.select_day(Day::Friday)?,
+            Command::PreviousDay => self.move_day_left()?,
+            Command::NextDay => self.move_day_right()?,
+            _ => {}
+        };
+        Ok(())
+    }
 }
 
 impl Component for WeekdaySelector {
     fn draw(&mut self, f: &mut Frame<'_>, rect: Rect) -> Result<()> {
-        let days = ["Mon", "Tue", "Wed", "Thu", "Fri"];
-
         let mut spans: Vec<Span> = Vec::new();
 
-        for (i, day) in days.iter().enumerate() {
+        for (i, (day, command)) in DAYS.iter().enumerate() {
             if i > 0 {
-                spans.push(Span::styled(" | ", Style::default().dim().fg(Color::Gray)));
+                spans.push(Span::styled(" | ", theme::style(StyleName::Tab)));
             }
 
-            let day_str = format!(" {} ({}) ", day, i + 1);
+            let day_str = match self.keys.get(command) {
+                Some(key) => format!(" {} ({}) ", day, key),
+                None => format!(" {} ", day),
+            };
 
             if Day::from(i as i16) == self.selected_day {
-                spans.push(Span::styled(
-                    day_str,
-                    Style::new()
-                        .dim()
-                        .fg(Color::White)
-                        .add_modifier(Modifier::UNDERLINED),
-                ));
+                spans.push(Span::styled(day_str, theme::style(StyleName::SelectedTab)));
             } else {
-                spans.push(Span::styled(
-                    day_str,
-                    Style::default().dim().fg(Color::Gray),
-                ));
+                spans.push(Span::styled(day_str, theme::style(StyleName::Tab)));
             }
         }
 
         let line = Line::from(spans);
+        let block = Block::default()
+            .title("Weekday Selector")
+            .title_alignment(Alignment::Left)
+            .borders(Borders::ALL);
+        let inner = block.inner(rect);
+        // the days are every other span, between the separators
+        self.day_areas = span_areas(&line, inner.x, inner.y)
+            .into_iter()
+            .step_by(2)
+            .collect();
 
         let paragraph = Paragraph::new(vec![line])
-            .block(
-                Block::default()
-                    .title("Weekday Selector")
-                    .title_alignment(Alignment::Left)
-                    .borders(Borders::ALL),
-            )
+            .block(block)
             .alignment(Alignment::Left)
-            .wrap(Wrap { trim: true });
+            .wrap(Wrap { trim: false });
 
         f.render_widget(paragraph, rect);
 
         Ok(())
     }
 
-    fn handle_key_events(&mut self, key: KeyEvent) -> Result<Option<Action>> {
-        match key.code {
-            KeyCode::Char('1') => self.select_day(Day::Monday)?,
-            KeyCode::Char('2') => self.select_day(Day::Tuesday)?,
-            KeyCode::Char('3') => self.select_day(Day::Wednesday)?,
-            KeyCode::Char('4') => self.select_day(Day::Thursday)?,
-            KeyCode::Char('5') => self.select_day(Day::Friday)?,
-            KeyCode::Left | KeyCode::Char('h') => self.move_day_left()?,
-            KeyCode::Right | KeyCode::Char('l') => self.move_day_right()?,
-            _ => {}
-        };
-        Ok(None)
-    }
-
     fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
         self.command_tx = Some(tx.clone());
 
         Ok(())
     }
+
+    fn handle_mouse_events(&mut self, mouse: MouseEvent) -> Result<Option<Action>> {
+        if !is_left_click(mouse) {
+            return Ok(None);
+        }
+        let clicked = self
+            .day_areas
+            .iter()
+            .position(|area| is_within(*area, mouse));
+        Ok(clicked.map(|i| Action::TT(TTAct::Command(DAYS[i].1))))
+    }
 }
@@ -0,0 +1,177 @@
+use chrono::Local;
+use color_eyre::eyre::Result;
+use crossterm::event::{KeyCode, KeyEvent, MouseEvent};
+use ratatui::{
+    prelude::*,
+    widgets::{Block, Borders, Clear, List, ListItem, ListState},
+};
+use shared_lib::models::trash::TrashedEntry;
+use tokio::sync::mpsc::UnboundedSender;
+
+use super::{
+    component_utils::TooltipBar, time_entry::time_utils::format_hours_minutes, Component, Frame,
+};
+use crate::{
+    action::{Action, ApiAct},
+    api_client::{ApiRequest, ApiResponse},
+    theme::{self, StyleName},
+};
+
+/// A popup listing deleted entries, most recent first, that can be put back on their day
+#[derive(Default)]
+pub struct TrashPopup {
+    command_tx: Option<UnboundedSender<Action>>,
+    tooltip_bar: TooltipBar,
+    entries: Vec<TrashedEntry>,
+    list_state: ListState,
+    pub is_open: bool,
+}
+
+impl TrashPopup {
+    pub fn open(&mut self) {
+        self.is_open = true;
+        self.entries.clear();
+        self.list_state.select(Some(0));
+        self.send_request(ApiRequest::GetTrash);
+    }
+
+    fn send_request(&self, request: ApiRequest) {
+        if let Some(tx) = &self.command_tx {
+            if let Err(error) = tx.send(Action::api_request_action(request)) {
+                log::error!("Failed to send action: {error:?}");
+            }
+        }
+    }
+
+    fn restore_selected(&mut self) {
+        let Some(index) = self
+            .list_state
+            .selected()
+            .filter(|index| *index < self.entries.len())
+        else {
+            return;
+        };
+        let restored = self.entries.remove(index);
+        self.send_request(ApiRequest::RestoreEntry {
+            id: restored.entry.id,
+        });
+        self.select(0);
+    }
+
+    fn select(&mut self, offset: isize) {
+        if self.entries.is_empty() {
+            return;
+        }
+        let last = self.entries.len() as isize - 1;
+        let selected = self.list_state.selected().unwrap_or(0) as isize;
+        self.list_state
+            .select(Some((selected + offset).clamp(0, last) as usize));
+    }
+}
+
+fn trash_item(trashed: &TrashedEntry) -> ListItem<'static> {
+    let entry = &trashed.entry;
+    let alias = entry
+        .charge_code
+        .as_ref()
+        .map_or("(no charge code)".to_string(), |code| code.alias.clone());
+    let mut spans = vec![
+        Span::styled(
+            trashed
+                .deleted_at
+                .with_timezone(&Local)
+                .format("%a %H:%M ")
+                .to_string(),
+            theme::style(StyleName::Hint),
+        ),
+        Span::raw(format!("{:<10}", format!("{:?}", entry.day))),
+        Span::raw(format!("{:>7}  ", format_hours_minutes(&entry.total_time))),
+        Span::raw(alias),
+    ];
+    if let Some(line) = entry.note.lines().find(|line| !line.trim().is_empty()) {
+        spans.push(Span::styled(
+            format!("  {}", line.trim()),
+            theme::style(StyleName::Faint),
+        ));
+    }
+    ListItem::new(Line::from(spans))
+}
+
+impl Component for TrashPopup {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
+        self.command_tx = Some(tx);
+        Ok(())
+    }
+
+    fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        if let Action::Api(ApiAct::Response(ApiResponse::Trash(entries))) = action {
+            self.entries = entries;
+            self.select(0);
+        }
+        Ok(None)
+    }
+
+    fn handle_key_events(&mut self, key: KeyEvent) -> Result<Option<Action>> {
+        match key.code {
+            KeyCode::Enter | KeyCode::Char('r') => self.restore_selected(),
+            KeyCode::Char('j') | KeyCode::Down => self.select(1),
+            KeyCode::Char('k') | KeyCode::Up => self.select(-1),
+            KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('D') => self.is_open = false,
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn handle_mouse_events(&mut self, mouse: MouseEvent) -> Result<Option<Action>> {
+        match self.tooltip_bar.clicked_key(mouse) {
+            Some(key) => self.handle_key_events(key),
+            None => Ok(None),
+        }
+    }
+
+    fn is_capturing_input(&self) -> bool {
+        self.is_open
+    }
+
+    fn draw(&mut self, f: &mut Frame<'_>, area: Rect) -> Result<()> {
+        if !self.is_open {
+            return Ok(());
+        }
+
+        let modal_area = area.inner(&Margin {
+            horizontal: (area.width as f32 * 0.1) as u16,
+            vertical: (area.height as f32 * 0.1) as u16,
+        });
+        f.render_widget(Clear, modal_area);
+
+        let block = Block::default()
+            .title("Trash")
+            .borders(Borders::ALL)
+            .border_style(theme::style(StyleName::ModalBorder));
+        let inner_area = block.inner(modal_area);
+        f.render_widget(block, modal_area);
+
+        let list_area = Rect {
+            height: inner_area.height.saturating_sub(3),
+            ..inner_area
+        };
+        let items: Vec<ListItem> = match self.entries.is_empty() {
+            true => vec![ListItem::new("The trash is empty")],
+            false => self.entries.iter().map(trash_item).collect(),
+        };
+        let list = List::new(items)
+            .highlight_style(theme::style(StyleName::ListHighlight))
+            .highlight_symbol(">");
+        f.render_stateful_widget(list, list_area, &mut self.list_state);
+
+        let bottom_bar_area = Rect {
+            y: inner_area.y + list_area.height,
+            height: inner_area.height - list_area.height,
+            ..inner_area
+        };
+        self.tooltip_bar
+            .draw(f, bottom_bar_area, &["Restore [Enter]", "Close [Esc]"]);
+
+        Ok(())
+    }
+}
@@ -0,0 +1 @@
+pub mod week_container;
@@ -0,0 +1,300 @@
+use crate::{
+    action::Command,
+    components::{time_entry::time_utils::quarter_hours, Component},
+    theme::{self, StyleName},
+    tui::Frame,
+};
+use color_eyre::eyre::Result;
+use ratatui::{prelude::*, widgets::*};
+use shared_lib::models::{charge_code::ChargeCodeVM, day::Day, full_state::FullState};
+
+const DAYS: [Day; 5] = [
+    Day::Monday,
+    Day::Tuesday,
+    Day::Wednesday,
+    Day::Thursday,
+    Day::Friday,
+];
+
+#[derive(Debug, Clone, PartialEq)]
+struct WeekRow {
+    charge_code: Option<ChargeCodeVM>,
+    // monday through friday
+    day_millis: [i64; 5],
+}
+
+/// Charge codes down the side and weekdays across the top, laid out like the Costpoint timesheet
+#[derive(Default)]
+pub struct WeekContainer {
+    rows: Vec<WeekRow>,
+    // time worked and expected each day, leaving out holidays and PTO
+    day_worked_millis: [i64; 5],
+    day_target_millis: [i64; 5],
+    selected_row: usize,
+    current_day: Day,
+}
+
+fn day_index(day: Day) -> usize {
+    i16::from(day) as usize
+}
+
+// one row per charge code used this week, entries without a code last. Days off and last
+// week's entries are left out, the same as the worked time the days are highlighted by.
+fn week_rows(full_state: &FullState) -> Vec<WeekRow> {
+    let mut rows: Vec<WeekRow> = Vec::new();
+    for day in DAYS {
+        let entries = full_state
+            .time_entries
+            .get(&day)
+            .into_iter()
+            .flatten()
+            .filter(|entry| full_state.counts_as_worked(entry.id));
+        for entry in entries {
+            let code_id = entry.charge_code.as_ref().map(|code| code.id);
+            let row = match rows
+                .iter()
+                .position(|row| row.charge_code.as_ref().map(|code| code.id) == code_id)
+            {
+                Some(position) => &mut rows[position],
+                None => {
+                    rows.push(WeekRow {
+                        charge_code: entry.charge_code.clone(),
+                        day_millis: [0; 5],
+                    });
+                    rows.last_mut().unwrap()
+                }
+            };
+            row.day_millis[day_index(day)] += entry.real_total_time();
+        }
+    }
+
+    rows.sort_by_key(|row| {
+        row.charge_code
+            .as_ref()
+            .map_or((1, String::new()), |code| (0, code.alias.to_lowercase()))
+    });
+    rows
+}
+
+// each cell is rounded on its own, so totals add up the way the timesheet will
+fn rounded_hours(day_millis: &[i64; 5]) -> [f64; 5] {
+    day_millis.map(|millis| quarter_hours(&millis))
+}
+
+fn hours_cell(hours: f64) -> Cell<'static> {
+    let text = match hours == 0.0 {
+        true => String::new(),
+        false => format!("{:.2}", hours),
+    };
+    Cell::from(Line::from(text).alignment(Alignment::Right))
+}
+
+impl WeekContainer {
+    pub fn set_state(&mut self, full_state: &FullState) {
+        self.rows = week_rows(full_state);
+        for day in DAYS {
+            let progress = full_state.day_progress(day);
+            self.day_worked_millis[day_index(day)] = progress.worked_millis;
+            self.day_target_millis[day_index(day)] = progress.target_millis;
+        }
+        self.selected_row = self.selected_row.min(self.rows.len().saturating_sub(1));
+    }
+
+    pub fn set_day(&mut self, day: Day) {
+        self.current_day = day;
+    }
+
+    /// The charge code on the selected row, `None` when it's the row for entries without one
+    pub fn selected_code_id(&self) -> Option<i32> {
+        self.rows
+            .get(self.selected_row)
+            .and_then(|row| row.charge_code.as_ref())
+            .map(|code| code.id)
+    }
+
+    pub fn handle_command(&mut self, command: Command) {
+        if self.rows.is_empty() {
+            return;
+        }
+        match command {
+            Command::PreviousEntry => {
+                self.selected_row = match self.selected_row {
+                    0 => self.rows.len() - 1,
+                    row => row - 1,
+                }
+            }
+            Command::NextEntry => self.selected_row = (self.selected_row + 1) % self.rows.len(),
+            _ => {}
+        }
+    }
+
+    // past days short of their target are red, today is yellow until it's done
+    fn day_style(&self, day: Day) -> Style {
+        let index = day_index(day);
+        let is_under = self.day_worked_millis[index] < self.day_target_millis[index];
+        let today = Day::get_current_day();
+        match (is_under, index.cmp(&day_index(today))) {
+            (true, std::cmp::Ordering::Less) => theme::style(StyleName::UnderTarget),
+            (true, std::cmp::Ordering::Equal) => theme::style(StyleName::TargetToday),
+            _ => Style::default(),
+        }
+    }
+}
+
+impl Component for WeekContainer {
+    fn draw(&mut self, f: &mut Frame<'_>, rect: Rect) -> Result<()> {
+        let current_column = day_index(self.current_day);
+        let selected_style = Style::default().add_modifier(Modifier::REVERSED);
+
+        let header = Row::new(
+            std::iter::once(Cell::from("Charge code"))
+                .chain(DAYS.iter().enumerate().map(|(index, day)| {
+                    let style = match index == current_column {
+                        true => self.day_style(*day).add_modifier(Modifier::UNDERLINED),
+                        false => self.day_style(*day),
+                    };
+                    Cell::from(
+                        Line::from(format!("{:?}", day)[..3].to_string())
+                            .alignment(Alignment::Right),
+                    )
+                    .style(style)
+                }))
+                .chain(std::iter::once(Cell::from(
+                    Line::from("Total").alignment(Alignment::Right),
+                ))),
+        )
+        .style(Style::default().add_modifier(Modifier::BOLD));
+
+        let row_hours: Vec<[f64; 5]> = self
+            .rows
+            .iter()
+            .map(|row| rounded_hours(&row.day_millis))
+            .collect();
+
+        let mut rows: Vec<Row> = self
+            .rows
+            .iter()
+            .zip(&row_hours)
+            .enumerate()
+            .map(|(row_index, (row, hours))| {
+                let is_selected_row = row_index == self.selected_row;
+                let alias = row
+                    .charge_code
+                    .as_ref()
+                    .map_or("(no code)".to_string(), |code| code.alias.clone());
+                let alias_style = match is_selected_row {
+                    true => Style::default().add_modifier(Modifier::BOLD),
+                    false => Style::default(),
+                };
+
+                Row::new(
+                    std::iter::once(Cell::from(alias).style(alias_style))
+                        .chain(hours.iter().enumerate().map(|(index, hours)| {
+                            let cell = hours_cell(*hours);
+                            match is_selected_row && index == current_column {
+                                true => cell.style(selected_style),
+                                false => cell,
+                            }
+                        }))
+                        .chain(std::iter::once(hours_cell(hours.iter().sum()))),
+                )
+            })
+            .collect();
+
+        let day_totals: Vec<f64> = (0..DAYS.len())
+            .map(|index| row_hours.iter().map(|hours| hours[index]).sum())
+            .collect();
+        rows.push(
+            Row::new(
+                std::iter::once(Cell::from("Total"))
+                    .chain(
+                        DAYS.iter()
+                            .zip(&day_totals)
+                            .map(|(day, hours)| hours_cell(*hours).style(self.day_style(*day))),
+                    )
+                    .chain(std::iter::once(hours_cell(day_totals.iter().sum()))),
+            )
+            .style(Style::default().add_modifier(Modifier::BOLD))
+            .top_margin(1),
+        );
+        let target_hours = self
+            .day_target_millis
+            .map(|millis| millis as f64 / 3_600_000.0);
+        rows.push(
+            Row::new(
+                std::iter::once(Cell::from("Target"))
+                    .chain(target_hours.iter().map(|hours| hours_cell(*hours)))
+                    .chain(std::iter::once(hours_cell(target_hours.iter().sum()))),
+            )
+            .style(theme::style(StyleName::Hint)),
+        );
+
+        let widths = std::iter::once(Constraint::Min(20))
+            .chain(std::iter::repeat_n(Constraint::Length(7), DAYS.len() + 1));
+        let table = Table::new(rows, widths)
+            .header(header)
+            .block(Block::default().title("Week").borders(Borders::ALL));
+
+        f.render_widget(table, rect);
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared_lib::models::time_entry::TimeEntryVM;
+    use std::collections::HashMap;
+
+    fn entry(id: i32, day: Day, code: Option<(i32, &str)>, total_time: i64) -> TimeEntryVM {
+        TimeEntryVM {
+            id,
+            total_time,
+            day,
+            charge_code: code.map(|(id, alias)| ChargeCodeVM {
+                id,
+                alias: alias.to_string(),
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn rows_are_one_per_charge_code() {
+        let hour = 60 * 60 * 1000;
+        let time_entries = HashMap::from([
+            (
+                Day::Monday,
+                vec![
+                    entry(1, Day::Monday, Some((2, "Ops")), hour),
+                    entry(2, Day::Monday, None, hour),
+                    entry(3, Day::Monday, Some((2, "Ops")), hour),
+                ],
+            ),
+            (
+                Day::Wednesday,
+                vec![
+                    entry(4, Day::Wednesday, Some((1, "Design")), 2 * hour),
+                    entry(5, Day::Wednesday, Some((3, "Last week")), hour),
+                ],
+            ),
+        ]);
+
+        let full_state = FullState {
+            time_entries,
+            earlier_week_entries: vec![5],
+            ..Default::default()
+        };
+
+        let rows = week_rows(&full_state);
+        let aliases: Vec<Option<&str>> = rows
+            .iter()
+            .map(|row| row.charge_code.as_ref().map(|code| code.alias.as_str()))
+            .collect();
+        assert_eq!(aliases, vec![Some("Design"), Some("Ops"), None]);
+        assert_eq!(rows[0].day_millis, [0, 0, 2 * hour, 0, 0]);
+        assert_eq!(rows[1].day_millis, [2 * hour, 0, 0, 0, 0]);
+        assert_eq!(rows[2].day_millis, [hour, 0, 0, 0, 0]);
+    }
+}
@@ -1,14 +1,19 @@
-use std::{collections::HashMap, path::PathBuf};
+use std::{collections::HashMap, path::PathBuf, str::FromStr};
 
 use color_eyre::eyre::Result;
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use derive_deref::{Deref, DerefMut};
 use ratatui::style::{Color, Modifier, Style};
-use serde::{de::Deserializer, Deserialize};
+use serde::{
+    de::{self, Deserializer},
+    Deserialize,
+};
+use shared_lib::models::time_entry::TimeEntryVM;
 
 use crate::{
-    action::{Action, UIAct},
+    action::{Action, Command, TTAct, UIAct},
     mode::Mode,
+    theme::ThemeName,
 };
 
 const CONFIG: &str = include_str!("../.config/config.json5");
@@ -34,6 +39,39 @@ impl Default for VimModeConfig {
     }
 }
 
+// what counts as an edit when a note comes back from $EDITOR
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct ExternalEditorConfig {
+    // save the note even when the file wasn't changed
+    pub save_unchanged: bool,
+    // an emptied file clears the note instead of being ignored
+    pub save_empty: bool,
+}
+
+// which deletes ask first, they can come back out of the trash either way
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConfirmDelete {
+    Never,
+    // entries with time on them or a note
+    #[default]
+    WithContent,
+    Always,
+}
+
+impl ConfirmDelete {
+    pub fn asks_for(&self, entry: &TimeEntryVM) -> bool {
+        match self {
+            ConfirmDelete::Never => false,
+            ConfirmDelete::WithContent => {
+                entry.real_total_time() > 0 || !entry.note.trim().is_empty()
+            }
+            ConfirmDelete::Always => true,
+        }
+    }
+}
+
 #[derive(Clone, Debug, Default, Deserialize)]
 pub struct Config {
     #[serde(default, flatten)]
@@ -41,9 +79,15 @@ pub struct Config {
     #[serde(default)]
     pub keybindings: KeyBindings,
     #[serde(default)]
+    pub theme: ThemeName,
+    #[serde(default)]
     pub styles: Styles,
     #[serde(default)]
     pub vim_mode: VimModeConfig,
+    #[serde(default)]
+    pub external_editor: ExternalEditorConfig,
+    #[serde(default)]
+    pub confirm_delete: ConfirmDelete,
 }
 
 impl Config {
@@ -87,13 +131,8 @@ impl Config {
                     .or_insert_with(|| cmd.clone());
             }
         }
-        for (mode, default_styles) in default_config.styles.iter() {
-            let user_styles = cfg.styles.entry(*mode).or_default();
-            for (style_key, style) in default_styles.iter() {
-                user_styles
-                    .entry(style_key.clone())
-                    .or_insert_with(|| *style);
-            }
+        for (name, style) in default_config.styles.iter() {
+            cfg.styles.entry(name.clone()).or_insert(*style);
         }
 
         Ok(cfg)
@@ -117,10 +156,12 @@ impl<'de> Deserialize<'de> for KeyBindings {
                     .into_iter()
                     .map(|(key_str, action_str)| {
                         // Parse the key sequence
-                        let keys = parse_key_sequence(&key_str).unwrap();
+                        let keys = parse_key_sequence(&key_str).map_err(de::Error::custom)?;
 
                         // Parse the action string to Action enum
-                        let action = parse_action_string(&action_str);
+                        let action = parse_action_string(&action_str).map_err(|e| {
+                            de::Error::custom(format!("{e} (bound to `{key_str}` in {mode:?})"))
+                        })?;
 
                         Ok((keys, action))
                     })
@@ -134,21 +175,44 @@ impl<'de> Deserialize<'de> for KeyBindings {
     }
 }
 
-// Helper function to parse action strings
-fn parse_action_string(action_str: &str) -> Action {
-    match action_str {
+impl KeyBindings {
+    /// The key sequences bound to `action` in `mode`, shortest first
+    pub fn keys_for(&self, mode: Mode, action: &Action) -> Vec<String> {
+        let mut keys: Vec<String> = self
+            .get(&mode)
+            .map(|bindings| {
+                bindings
+                    .iter()
+                    .filter(|(_, bound)| *bound == action)
+                    .map(|(sequence, _)| sequence.iter().map(key_event_to_string).collect())
+                    .collect()
+            })
+            .unwrap_or_default();
+        keys.sort_by(|a, b| a.len().cmp(&b.len()).then_with(|| a.cmp(b)));
+        keys
+    }
+}
+
+// Helper function to parse action strings, UI actions first and then the named commands
+fn parse_action_string(action_str: &str) -> Result<Action, String> {
+    let action = match action_str {
         "Tick" => Action::UI(UIAct::Tick),
-        "Render" => Action::UI(UIAct::Tick),
+        "Render" => Action::UI(UIAct::Render),
         "Suspend" => Action::UI(UIAct::Suspend),
         "Resume" => Action::UI(UIAct::Resume),
         "Refresh" => Action::UI(UIAct::Refresh),
         "Help" => Action::UI(UIAct::Help),
         "Quit" => Action::UI(UIAct::Quit),
-        _ => Action::UI(UIAct::Quit),
-    }
+        _ => {
+            let command = Command::from_str(action_str)
+                .map_err(|_| format!("Unknown action `{action_str}`"))?;
+            Action::TT(TTAct::Command(command))
+        }
+    };
+    Ok(action)
 }
 
-fn parse_key_event(raw: &str) -> Result<KeyEvent, String> {
+pub fn parse_key_event(raw: &str) -> Result<KeyEvent, String> {
     let raw_lower = raw.to_ascii_lowercase();
     let (remaining, modifiers) = extract_modifiers(&raw_lower);
     parse_key_code_with_modifiers(remaining, modifiers)
@@ -319,24 +383,30 @@ pub fn parse_key_sequence(raw: &str) -> Result<Vec<KeyEvent>, String> {
     sequences.into_iter().map(parse_key_event).collect()
 }
 
+// named styles laid over the theme, see `theme::StyleName`
 #[derive(Clone, Debug, Default, Deref, DerefMut)]
-pub struct Styles(pub HashMap<Mode, HashMap<String, Style>>);
+pub struct Styles(pub HashMap<String, Style>);
 
 impl<'de> Deserialize<'de> for Styles {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
         D: Deserializer<'de>,
     {
-        let parsed_map = HashMap::<Mode, HashMap<String, String>>::deserialize(deserializer)?;
+        let parsed_map = HashMap::<String, StyleEntry>::deserialize(deserializer)?;
 
         let styles = parsed_map
             .into_iter()
-            .map(|(mode, inner_map)| {
-                let converted_inner_map = inner_map
-                    .into_iter()
-                    .map(|(str, style)| (str, parse_style(&style)))
-                    .collect();
-                (mode, converted_inner_map)
+            .filter_map(|(name, entry)| match entry {
+                StyleEntry::Style(style) => Some((name, parse_style(&style))),
+                StyleEntry::PerMode(mode_styles) => {
+                    let names: Vec<_> = mode_styles.into_keys().collect();
+                    log::warn!(
+                        "Ignoring the `{name}` styles ({}): styles are no longer set per mode, \
+                         see `theme::StyleName` for the names they take now",
+                        names.join(", ")
+                    );
+                    None
+                }
             })
             .collect();
 
@@ -344,6 +414,14 @@ impl<'de> Deserialize<'de> for Styles {
     }
 }
 
+// older configs nested styles under a mode, those are read and left out
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum StyleEntry {
+    Style(String),
+    PerMode(HashMap<String, String>),
+}
+
 pub fn parse_style(line: &str) -> Style {
     let (foreground, background) =
         line.split_at(line.to_lowercase().find("on ").unwrap_or(line.len()));
@@ -366,6 +444,7 @@ fn process_color_string(color_str: &str) -> (String, Modifier) {
         .replace("grey", "gray")
         .replace("bright ", "")
         .replace("bold ", "")
+        .replace("dim ", "")
         .replace("underline ", "")
         .replace("inverse ", "");
 
@@ -376,6 +455,9 @@ fn process_color_string(color_str: &str) -> (String, Modifier) {
     if color_str.contains("bold") {
         modifiers |= Modifier::BOLD;
     }
+    if color_str.contains("dim") {
+        modifiers |= Modifier::DIM;
+    }
     if color_str.contains("inverse") {
         modifiers |= Modifier::REVERSED;
     }
@@ -483,10 +565,11 @@ mod tests {
 
     #[test]
     fn test_process_color_string() {
-        let (color, modifiers) = process_color_string("underline bold inverse gray");
+        let (color, modifiers) = process_color_string("underline bold dim inverse gray");
         assert_eq!(color, "gray");
         assert!(modifiers.contains(Modifier::UNDERLINED));
         assert!(modifiers.contains(Modifier::BOLD));
+        assert!(modifiers.contains(Modifier::DIM));
         assert!(modifiers.contains(Modifier::REVERSED));
     }
 
@@ -519,6 +602,84 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_every_command_has_a_default_binding() {
+        use strum::IntoEnumIterator;
+
+        let c: Config = json5::from_str(CONFIG).unwrap();
+        for command in Command::iter() {
+            let action = Action::TT(TTAct::Command(command));
+            assert!(
+                !c.keybindings.keys_for(Mode::Crud, &action).is_empty(),
+                "{command} is not bound"
+            );
+        }
+    }
+
+    #[test]
+    fn test_remapped_command() {
+        let bindings: KeyBindings =
+            json5::from_str(r#"{ "Standup": { "<x>": "AddEntry", "<ctrl-n>": "NextDay" } }"#)
+                .unwrap();
+        let standup = bindings.get(&Mode::Standup).unwrap();
+
+        assert_eq!(
+            standup.get(&parse_key_sequence("<x>").unwrap()),
+            Some(&Action::TT(TTAct::Command(Command::AddEntry)))
+        );
+        assert_eq!(
+            bindings.keys_for(Mode::Standup, &Action::TT(TTAct::Command(Command::NextDay))),
+            vec!["ctrl-n".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_confirm_delete() {
+        let mut entry = TimeEntryVM {
+            id: 1,
+            note: "  ".to_string(),
+            ..Default::default()
+        };
+        let c: Config = json5::from_str(CONFIG).unwrap();
+        assert_eq!(c.confirm_delete, ConfirmDelete::WithContent);
+        assert!(!c.confirm_delete.asks_for(&entry));
+        assert!(ConfirmDelete::Always.asks_for(&entry));
+
+        entry.note = "patching".to_string();
+        assert!(c.confirm_delete.asks_for(&entry));
+        entry.note.clear();
+        entry.total_time = 60_000;
+        assert!(c.confirm_delete.asks_for(&entry));
+        assert!(!ConfirmDelete::Never.asks_for(&entry));
+    }
+
+    #[test]
+    fn test_unknown_action_is_an_error() {
+        let err = json5::from_str::<KeyBindings>(r#"{ "Crud": { "<x>": "Explode" } }"#)
+            .unwrap_err()
+            .to_string();
+
+        assert!(err.contains("Unknown action `Explode`"), "{err}");
+    }
+
+    #[test]
+    fn test_per_mode_styles_are_ignored() {
+        let styles =
+            json5::from_str::<Styles>(r#"{ "Crud": { "default": "white" }, "hint": "gray" }"#)
+                .unwrap();
+
+        assert_eq!(styles.len(), 1);
+        assert_eq!(styles["hint"], parse_style("gray"));
+    }
+
+    #[test]
+    fn test_keys_for_shortest_first() {
+        let c: Config = json5::from_str(CONFIG).unwrap();
+        let quit = c.keybindings.keys_for(Mode::Crud, &Action::UI(UIAct::Quit));
+
+        assert_eq!(quit, vec!["q", "ctrl-c", "ctrl-d"]);
+    }
+
     #[test]
     fn test_simple_keys() {
         assert_eq!(
@@ -1,5 +1,4 @@
 pub mod action;
-pub mod action_history;
 pub mod api_client;
 pub mod app;
 pub mod cli;
@@ -7,6 +6,8 @@ pub mod components;
 pub mod config;
 pub mod mode;
 pub mod shared;
+pub mod subcommands;
+pub mod theme;
 pub mod tui;
 
 use clap::Parser;
@@ -30,6 +31,15 @@ async fn tokio_main() -> Result<()> {
         std::env::var("API_BASE_URL").unwrap_or_else(|_| "http://localhost:8001".to_string());
     let api_client = ApiClient::new(api_base_url);
 
+    if let Some(command) = args.command {
+        // scripts want a one line error and an exit code, not a backtrace
+        if let Err(e) = subcommands::run(command, args.json, &api_client).await {
+            eprintln!("error: {e}");
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
     let mut app = App::new(args.tick_rate, args.frame_rate, &api_client).await?;
     app.run().await?;
 
@@ -0,0 +1,65 @@
+use color_eyre::eyre::{eyre, Result};
+use std::io::Write;
+use tokio::process::Command;
+
+use crate::config::ExternalEditorConfig;
+
+/// What to save once the editor closes, if anything. Editors add a final newline,
+/// so trailing whitespace doesn't count as a change.
+pub fn edited_note(original: &str, edited: &str, config: &ExternalEditorConfig) -> Option<String> {
+    let edited = edited.trim_end();
+    match (edited.is_empty(), edited == original.trim_end()) {
+        (true, _) if !config.save_empty => None,
+        (_, true) if !config.save_unchanged => None,
+        _ => Some(edited.to_string()),
+    }
+}
+
+/// Writes the note to a temp file, waits for `$EDITOR` to close it and returns what's in it.
+/// `$EDITOR` can carry arguments, like `code --wait`.
+pub async fn edit_in_editor(entry_id: i32, note: &str) -> Result<String> {
+    let editor = std::env::var("EDITOR").unwrap_or_else(|_| "vi".to_string());
+    let mut words = editor.split_whitespace();
+    let program = words.next().ok_or_else(|| eyre!("$EDITOR is empty"))?;
+
+    // a fresh file each time, so nothing else in the temp dir can be edited in its place
+    let mut file = tempfile::Builder::new()
+        .prefix(&format!("rustic-note-{entry_id}-"))
+        .suffix(".md")
+        .tempfile()?;
+    file.write_all(note.as_bytes())?;
+    let status = Command::new(program)
+        .args(words)
+        .arg(file.path())
+        .status()
+        .await;
+    let edited = std::fs::read_to_string(file.path());
+
+    match status? {
+        status if status.success() => Ok(edited?),
+        status => Err(eyre!("{editor} exited with {status}")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_real_changes_are_saved_by_default() {
+        let config = ExternalEditorConfig::default();
+        assert_eq!(edited_note("a", "a\n", &config), None);
+        assert_eq!(edited_note("a", "\n", &config), None);
+        assert_eq!(
+            edited_note("a", "a\nb\n\n", &config),
+            Some("a\nb".to_string())
+        );
+
+        let config = ExternalEditorConfig {
+            save_unchanged: true,
+            save_empty: true,
+        };
+        assert_eq!(edited_note("a", "a\n", &config), Some("a".to_string()));
+        assert_eq!(edited_note("a", "", &config), Some("".to_string()));
+    }
+}
@@ -1,3 +1,4 @@
+pub mod clipboard;
+pub mod editor;
 pub mod utils;
 pub mod error;
-
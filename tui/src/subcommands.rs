@@ -0,0 +1,554 @@
+use chrono::{DateTime, Duration, Utc};
+use color_eyre::eyre::{eyre, Result};
+use serde::{Deserialize, Serialize};
+use shared_lib::{
+    api_client::ApiClient,
+    models::{
+        charge_code::ChargeCode,
+        costpoint_entry::CostpointEntryVM,
+        day::Day,
+        full_state::{DayTimeEntries, FullState},
+        time_entry::TimeEntryVM,
+        timer_status::TimerStatus,
+    },
+    utils::{duration::parse_duration, quick_add::parse_quick_entry, time_zone::user_time_zone},
+};
+
+use crate::{
+    cli::Commands, components::time_entry::time_utils::format_hours_minutes,
+    shared::utils::get_data_dir,
+};
+
+const STATUS_CACHE_FILE: &str = "status_cache.json";
+// prompts run `status --format` every second, the server only needs asking every few
+const STATUS_CACHE_TIME: Duration = Duration::seconds(5);
+
+const DAYS: [Day; 5] = [
+    Day::Monday,
+    Day::Tuesday,
+    Day::Wednesday,
+    Day::Thursday,
+    Day::Friday,
+];
+
+#[derive(Serialize, Debug)]
+struct EntryOutput {
+    id: i32,
+    day: String,
+    alias: Option<String>,
+    note: String,
+    total_millis: i64,
+    is_active: bool,
+}
+
+impl From<&TimeEntryVM> for EntryOutput {
+    fn from(entry: &TimeEntryVM) -> Self {
+        Self {
+            id: entry.id,
+            day: format!("{:?}", entry.day),
+            alias: entry.charge_code.as_ref().map(|code| code.alias.clone()),
+            note: entry.note.clone(),
+            total_millis: entry.real_total_time(),
+            is_active: entry.is_active,
+        }
+    }
+}
+
+impl EntryOutput {
+    fn line(&self) -> String {
+        format!(
+            "{:>5} {} {:<16} {:>7}  {}",
+            self.id,
+            if self.is_active { "*" } else { " " },
+            self.alias.as_deref().unwrap_or("-"),
+            format_hours_minutes(&self.total_millis),
+            self.note.lines().next().unwrap_or_default()
+        )
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+struct CachedStatus {
+    // local time, so a server clock that's off doesn't matter
+    fetched_at: DateTime<Utc>,
+    status: TimerStatus,
+}
+
+#[derive(Serialize, Debug)]
+struct ReportRow {
+    alias: String,
+    // monday through friday
+    day_millis: [i64; 5],
+    total_millis: i64,
+}
+
+#[derive(Serialize, Debug)]
+struct ReportOutput {
+    rows: Vec<ReportRow>,
+    day_millis: [i64; 5],
+    total_millis: i64,
+    target_millis: i64,
+}
+
+/// Runs one command against the server and prints the result, for use from scripts
+pub async fn run(command: Commands, json: bool, api_client: &ApiClient) -> Result<()> {
+    if !matches!(command, Commands::Status { .. }) {
+        clear_status_cache();
+    }
+
+    match command {
+        Commands::Start { alias, note } => {
+            let entry = start(api_client, &alias, note.join(" ")).await?;
+            print_entries(&[entry], json, "Started")
+        }
+        Commands::Stop => {
+            let stopped = stop(api_client).await?;
+            match stopped.is_empty() && !json {
+                true => println!("No timer running"),
+                false => print_entries(&stopped, json, "Stopped")?,
+            }
+            Ok(())
+        }
+        Commands::Status {
+            format: Some(format),
+            idle_format,
+        } => {
+            let status = cached_status(api_client).await?;
+            let template = match status.active {
+                Some(_) => format,
+                None => idle_format.unwrap_or_default(),
+            };
+            println!("{}", format_status(&template, &status));
+            Ok(())
+        }
+        Commands::Status { format: None, .. } => {
+            let status = api_client.get_status().await?;
+            print_status(&status, json)
+        }
+        Commands::Add {
+            alias,
+            duration,
+            day,
+        } => {
+            let millis = parse_duration(&duration).map_err(|e| eyre!(e))?;
+            let day = day.unwrap_or_else(Day::get_current_day);
+            let state = api_client.get_full_state().await?;
+            let code = find_charge_code(&state.charge_codes, &alias)?;
+            let entry = entry_for_code(api_client, &state, day, code).await?;
+            let entry = api_client.add_time_to_entry(entry.id, millis).await?;
+            print_entries(&[entry], json, "Added to")
+        }
+        Commands::Quick { text } => {
+            let state = api_client.get_full_state().await?;
+            let entry = parse_quick_entry(
+                &quick_line(&text),
+                &state.charge_codes,
+                user_time_zone(),
+                Utc::now(),
+            )
+            .map_err(|e| eyre!(e))?;
+            let day_entries = api_client.create_quick_entry(&entry).await?;
+            let created = created_entry(day_entries)?;
+            print_entries(&[created], json, "Added")
+        }
+        Commands::Note { id, text } => {
+            let entry = api_client.update_entry_note(id, text.join(" ")).await?;
+            print_entries(&[entry], json, "Updated")
+        }
+        Commands::Ls { day } => {
+            let day = day.unwrap_or_else(Day::get_current_day);
+            let state = api_client.get_full_state().await?;
+            let mut entries = state.time_entries.get(&day).cloned().unwrap_or_default();
+            entries.sort_by_key(|entry| entry.id);
+            print_entries(&entries, json, "")
+        }
+        Commands::Report => {
+            let state = api_client.get_full_state().await?;
+            print_report(&report(&state), json)
+        }
+        Commands::Costpoint => {
+            let entries = api_client.get_costpoint_entries().await?;
+            print_costpoint(&entries, json)
+        }
+    }
+}
+
+// the shell has already taken the quotes off, so put them back around notes with spaces
+fn quick_line(args: &[String]) -> String {
+    args.iter()
+        .map(|arg| match arg.contains(char::is_whitespace) {
+            true => format!("\"{}\"", arg),
+            false => arg.clone(),
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Matches an alias or code exactly, ignoring case, then falls back to a unique partial match
+fn find_charge_code<'a>(codes: &'a [ChargeCode], alias: &str) -> Result<&'a ChargeCode> {
+    let alias = alias.to_lowercase();
+    if let Some(code) = codes
+        .iter()
+        .find(|code| code.alias.to_lowercase() == alias || code.code.to_lowercase() == alias)
+    {
+        return Ok(code);
+    }
+
+    let matches: Vec<&ChargeCode> = codes
+        .iter()
+        .filter(|code| code.alias.to_lowercase().contains(&alias))
+        .collect();
+    match matches.as_slice() {
+        [code] => Ok(code),
+        [] => Err(eyre!("No charge code matches '{}'", alias)),
+        _ => Err(eyre!(
+            "'{}' matches more than one charge code: {}",
+            alias,
+            matches
+                .iter()
+                .map(|code| code.alias.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        )),
+    }
+}
+
+// the day's entry for a code, or a new one when there isn't one yet
+async fn entry_for_code(
+    api_client: &ApiClient,
+    state: &FullState,
+    day: Day,
+    code: &ChargeCode,
+) -> Result<TimeEntryVM> {
+    let existing = state.time_entries.get(&day).and_then(|entries| {
+        entries.iter().find(|entry| {
+            entry
+                .charge_code
+                .as_ref()
+                .is_some_and(|entry_code| entry_code.id == code.id)
+        })
+    });
+    if let Some(entry) = existing {
+        return Ok(entry.clone());
+    }
+
+    let day_entries = api_client.create_time_entry(day.into()).await?;
+    let created = created_entry(day_entries)?;
+    Ok(api_client
+        .update_time_entry_charge_code(created.id, code.id)
+        .await?)
+}
+
+fn created_entry(day_entries: DayTimeEntries) -> Result<TimeEntryVM> {
+    let created_id = day_entries
+        .created_id
+        .ok_or_else(|| eyre!("The server didn't say which entry it made"))?;
+    day_entries
+        .entries
+        .into_iter()
+        .find(|entry| entry.id == created_id)
+        .ok_or_else(|| eyre!("The server didn't return the new entry"))
+}
+
+async fn start(api_client: &ApiClient, alias: &str, note: String) -> Result<TimeEntryVM> {
+    let state = api_client.get_full_state().await?;
+    let code = find_charge_code(&state.charge_codes, alias)?;
+    let mut entry = entry_for_code(api_client, &state, Day::get_current_day(), code).await?;
+
+    if !note.is_empty() && !entry.note.lines().any(|line| line == note) {
+        let note = match entry.note.is_empty() {
+            true => note,
+            false => format!("{}\n{}", entry.note, note),
+        };
+        entry = api_client.update_entry_note(entry.id, note).await?;
+    }
+
+    if entry.is_active {
+        return Ok(entry);
+    }
+    let day_entries = api_client.play_entry(entry.id).await?;
+    day_entries
+        .entries
+        .into_iter()
+        .find(|played| played.id == entry.id)
+        .ok_or_else(|| eyre!("The server didn't return the started entry"))
+}
+
+async fn stop(api_client: &ApiClient) -> Result<Vec<TimeEntryVM>> {
+    let state = api_client.get_full_state().await?;
+    let mut stopped = Vec::new();
+    for entry in state.time_entries.values().flatten() {
+        if entry.is_active {
+            let day_entries = api_client.pause_entry(entry.id).await?;
+            stopped.extend(
+                day_entries
+                    .entries
+                    .into_iter()
+                    .filter(|paused| paused.id == entry.id),
+            );
+        }
+    }
+    Ok(stopped)
+}
+
+async fn cached_status(api_client: &ApiClient) -> Result<TimerStatus> {
+    let path = get_data_dir().join(STATUS_CACHE_FILE);
+    let now = Utc::now();
+    let cached = std::fs::read_to_string(&path)
+        .ok()
+        .and_then(|json| serde_json::from_str::<CachedStatus>(&json).ok());
+    if let Some(cached) = cached {
+        let age = now.signed_duration_since(cached.fetched_at);
+        if age >= Duration::zero() && age < STATUS_CACHE_TIME {
+            return Ok(cached.status.at(cached.status.as_of + age));
+        }
+    }
+
+    let status = api_client.get_status().await?;
+    let cached = CachedStatus {
+        fetched_at: now,
+        status: status.clone(),
+    };
+    // a cache that can't be written just means asking the server next time
+    if let Err(error) = std::fs::create_dir_all(get_data_dir())
+        .and_then(|_| std::fs::write(&path, serde_json::to_string(&cached)?))
+    {
+        log::error!("Failed to write {STATUS_CACHE_FILE}: {error}");
+    }
+    Ok(status)
+}
+
+// anything that changes entries makes the cached status wrong
+fn clear_status_cache() {
+    let _ = std::fs::remove_file(get_data_dir().join(STATUS_CACHE_FILE));
+}
+
+/// Fills in `{alias}`, `{note}`, `{elapsed}`, `{today}` and `{id}`
+fn format_status(template: &str, status: &TimerStatus) -> String {
+    let active = status.active.as_ref();
+    template
+        .replace(
+            "{alias}",
+            active
+                .and_then(|active| active.alias.as_deref())
+                .unwrap_or(""),
+        )
+        .replace(
+            "{note}",
+            active
+                .and_then(|active| active.note.lines().next())
+                .unwrap_or(""),
+        )
+        .replace(
+            "{elapsed}",
+            &active
+                .map(|active| format_hours_minutes(&active.elapsed))
+                .unwrap_or_default(),
+        )
+        .replace("{today}", &format_hours_minutes(&status.today_total))
+        .replace(
+            "{id}",
+            &active
+                .map(|active| active.id.to_string())
+                .unwrap_or_default(),
+        )
+}
+
+fn report(state: &FullState) -> ReportOutput {
+    let mut rows: Vec<ReportRow> = Vec::new();
+    for (index, day) in DAYS.iter().enumerate() {
+        // last week's entries wait on the cleanup under the same weekdays
+        let entries = state
+            .time_entries
+            .get(day)
+            .into_iter()
+            .flatten()
+            .filter(|entry| !state.earlier_week_entries.contains(&entry.id));
+        for entry in entries {
+            let alias = entry
+                .charge_code
+                .as_ref()
+                .map_or("(no code)".to_string(), |code| code.alias.clone());
+            let row = match rows.iter().position(|row| row.alias == alias) {
+                Some(position) => &mut rows[position],
+                None => {
+                    rows.push(ReportRow {
+                        alias,
+                        day_millis: [0; 5],
+                        total_millis: 0,
+                    });
+                    rows.last_mut().unwrap()
+                }
+            };
+            let millis = entry.real_total_time();
+            row.day_millis[index] += millis;
+            row.total_millis += millis;
+        }
+    }
+    rows.sort_by_key(|row| std::cmp::Reverse(row.total_millis));
+
+    let mut day_millis = [0; 5];
+    for row in &rows {
+        for (total, millis) in day_millis.iter_mut().zip(row.day_millis) {
+            *total += millis;
+        }
+    }
+
+    ReportOutput {
+        total_millis: day_millis.iter().sum(),
+        day_millis,
+        target_millis: state.week_progress().target_millis,
+        rows,
+    }
+}
+
+fn print_json<T: Serialize>(value: &T) -> Result<()> {
+    println!("{}", serde_json::to_string_pretty(value)?);
+    Ok(())
+}
+
+fn print_entries(entries: &[TimeEntryVM], json: bool, verb: &str) -> Result<()> {
+    let entries: Vec<EntryOutput> = entries.iter().map(EntryOutput::from).collect();
+    if json {
+        return print_json(&entries);
+    }
+    for entry in entries {
+        match verb.is_empty() {
+            true => println!("{}", entry.line()),
+            false => println!("{} {}", verb, entry.line().trim_start()),
+        }
+    }
+    Ok(())
+}
+
+fn print_status(status: &TimerStatus, json: bool) -> Result<()> {
+    if json {
+        return print_json(status);
+    }
+    let today = format_hours_minutes(&status.today_total);
+    match &status.active {
+        Some(active) => println!(
+            "{} {} (today {})",
+            active.alias.as_deref().unwrap_or("-"),
+            format_hours_minutes(&active.elapsed),
+            today
+        ),
+        None => println!("No timer running (today {})", today),
+    }
+    Ok(())
+}
+
+fn print_report(report: &ReportOutput, json: bool) -> Result<()> {
+    if json {
+        return print_json(report);
+    }
+    let columns = |millis: &[i64; 5], total: i64| {
+        millis
+            .iter()
+            .chain(std::iter::once(&total))
+            .map(|millis| format!("{:>7}", format_hours_minutes(millis)))
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+
+    println!(
+        "{:<16} {:>7} {:>7} {:>7} {:>7} {:>7} {:>7}",
+        "", "Mon", "Tue", "Wed", "Thu", "Fri", "Total"
+    );
+    for row in &report.rows {
+        println!(
+            "{:<16} {}",
+            row.alias,
+            columns(&row.day_millis, row.total_millis)
+        );
+    }
+    println!(
+        "{:<16} {}",
+        "Total",
+        columns(&report.day_millis, report.total_millis)
+    );
+    println!(
+        "Target {}, {} to go",
+        format_hours_minutes(&report.target_millis),
+        format_hours_minutes(&(report.target_millis - report.total_millis).max(0))
+    );
+    Ok(())
+}
+
+fn print_costpoint(entries: &[CostpointEntryVM], json: bool) -> Result<()> {
+    if json {
+        return print_json(&entries);
+    }
+    for entry in entries {
+        println!(
+            "{}\t{}\t{}\t{}",
+            entry.date,
+            entry.charge_code,
+            entry.hours,
+            entry.notes.lines().collect::<Vec<_>>().join("; ")
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared_lib::models::timer_status::ActiveTimer;
+
+    fn code(id: i32, alias: &str) -> ChargeCode {
+        ChargeCode {
+            id,
+            alias: alias.to_string(),
+            code: format!("CODE.{}", id),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn finds_charge_codes_by_alias() {
+        let codes = vec![code(1, "Ops"), code(2, "Design"), code(3, "Design Review")];
+
+        assert_eq!(find_charge_code(&codes, "ops").unwrap().id, 1);
+        assert_eq!(find_charge_code(&codes, "code.2").unwrap().id, 2);
+        assert_eq!(find_charge_code(&codes, "design").unwrap().id, 2);
+        assert_eq!(find_charge_code(&codes, "rev").unwrap().id, 3);
+        assert!(find_charge_code(&codes, "sig").is_err());
+        assert!(find_charge_code(&codes, "e").is_err());
+    }
+
+    #[test]
+    fn quick_lines_keep_quoted_notes_together() {
+        let args = ["1h15", "ops", "patching servers", "yesterday"].map(String::from);
+        assert_eq!(
+            quick_line(&args),
+            r#"1h15 ops "patching servers" yesterday"#
+        );
+    }
+
+    #[test]
+    fn fills_in_status_templates() {
+        let now = Utc::now();
+        let mut status = TimerStatus {
+            active: Some(ActiveTimer {
+                id: 7,
+                alias: Some("Ops".to_string()),
+                note: "fixing the build\nand more".to_string(),
+                day: Day::Monday,
+                started: now,
+                elapsed: 65 * 60 * 1000,
+            }),
+            today: Day::Monday,
+            today_total: 3 * 60 * 60 * 1000,
+            as_of: now,
+        };
+
+        assert_eq!(
+            format_status("{alias} {elapsed} ({today}) #{id} {note}", &status),
+            "Ops 1h05m (3h00m) #7 fixing the build"
+        );
+
+        status.active = None;
+        assert_eq!(format_status("{alias}{elapsed} {today}", &status), " 3h00m");
+    }
+}
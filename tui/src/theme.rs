@@ -0,0 +1,211 @@
+use std::{collections::HashMap, str::FromStr, sync::OnceLock};
+
+use ratatui::style::{Color, Style};
+use serde::Deserialize;
+use strum::{Display, EnumIter, EnumString};
+
+use crate::config::{parse_style, Config};
+
+const DARK: &str = include_str!("../.config/themes/dark.json5");
+const LIGHT: &str = include_str!("../.config/themes/light.json5");
+const HIGH_CONTRAST: &str = include_str!("../.config/themes/high_contrast.json5");
+const NO_COLOR: &str = include_str!("../.config/themes/no_color.json5");
+
+static THEME: OnceLock<Theme> = OnceLock::new();
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ThemeName {
+    #[default]
+    Dark,
+    Light,
+    HighContrast,
+}
+
+/// The styles components draw with, named in the config as `snake_case`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Display, EnumIter, EnumString)]
+#[strum(serialize_all = "snake_case")]
+pub enum StyleName {
+    Entry,
+    SelectedEntry,
+    // the entry whose timer is running
+    ActiveEntry,
+    RunningTimer,
+    OverTarget,
+    // a past day that fell short of its target
+    UnderTarget,
+    TargetToday,
+    ModalBorder,
+    Panel,
+    ActivePanel,
+    Input,
+    FocusedInput,
+    Digit,
+    ActiveDigit,
+    ListItem,
+    ListHighlight,
+    Hint,
+    Faint,
+    Error,
+    Success,
+    Offline,
+    Syncing,
+    Tooltip,
+    Tag,
+    Heading,
+    Favorite,
+    NonChargeable,
+    Tab,
+    SelectedTab,
+    Notes,
+    NotesEditing,
+    VimNormal,
+    VimInsert,
+    VimVisual,
+    VimOperator,
+    VimCommand,
+}
+
+#[derive(Deserialize)]
+struct ThemeFile {
+    palette: Vec<String>,
+    styles: HashMap<String, String>,
+}
+
+#[derive(Debug, Default)]
+pub struct Theme {
+    styles: HashMap<StyleName, Style>,
+    // tells entries apart in the timeline
+    palette: Vec<Color>,
+}
+
+impl Theme {
+    fn parse(source: &str) -> Self {
+        let file: ThemeFile = json5::from_str(source).expect("built-in themes are valid json5");
+        let mut theme = Theme {
+            palette: file
+                .palette
+                .iter()
+                .filter_map(|color| parse_style(color).fg)
+                .collect(),
+            ..Theme::default()
+        };
+        for (name, style) in file.styles {
+            theme.set(&name, parse_style(&style));
+        }
+        theme
+    }
+
+    pub fn builtin(name: ThemeName) -> Self {
+        Self::parse(match name {
+            ThemeName::Dark => DARK,
+            ThemeName::Light => LIGHT,
+            ThemeName::HighContrast => HIGH_CONTRAST,
+        })
+    }
+
+    /// The configured theme with the `styles` section laid over it. With `no_color` the
+    /// built-in fallback is used instead and the overrides only keep their modifiers.
+    pub fn from_config(config: &Config, no_color: bool) -> Self {
+        let mut theme = match no_color {
+            true => Self::parse(NO_COLOR),
+            false => Self::builtin(config.theme),
+        };
+        for (name, style) in config.styles.iter() {
+            let style = match no_color {
+                true => without_colors(*style),
+                false => *style,
+            };
+            theme.set(name, style);
+        }
+        theme
+    }
+
+    fn set(&mut self, name: &str, style: Style) {
+        match StyleName::from_str(name) {
+            Ok(name) => {
+                self.styles.insert(name, style);
+            }
+            Err(_) => log::warn!("Ignoring unknown style {name}"),
+        }
+    }
+
+    pub fn style(&self, name: StyleName) -> Style {
+        self.styles.get(&name).copied().unwrap_or_default()
+    }
+}
+
+fn without_colors(style: Style) -> Style {
+    Style::default()
+        .add_modifier(style.add_modifier)
+        .remove_modifier(style.sub_modifier)
+}
+
+/// Picks the theme for the rest of the run. Follows https://no-color.org: any non-empty
+/// `NO_COLOR` wins over the config.
+pub fn init(config: &Config) {
+    let no_color = std::env::var("NO_COLOR").is_ok_and(|value| !value.is_empty());
+    if THEME.set(Theme::from_config(config, no_color)).is_err() {
+        log::warn!("The theme was already set");
+    }
+}
+
+fn current() -> &'static Theme {
+    THEME.get_or_init(|| Theme::builtin(ThemeName::default()))
+}
+
+pub fn style(name: StyleName) -> Style {
+    current().style(name)
+}
+
+/// The color for the nth item of a list, or none without colors
+pub fn palette_color(index: usize) -> Option<Color> {
+    let palette = &current().palette;
+    palette.get(index % palette.len().max(1)).copied()
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use ratatui::style::Modifier;
+    use strum::IntoEnumIterator;
+
+    use super::*;
+
+    #[test]
+    fn builtin_themes_name_every_style() {
+        for source in [DARK, LIGHT, HIGH_CONTRAST, NO_COLOR] {
+            let file: ThemeFile = json5::from_str(source).unwrap();
+            for name in StyleName::iter() {
+                assert!(file.styles.contains_key(&name.to_string()), "{name}");
+            }
+            assert_eq!(file.styles.len(), StyleName::iter().count());
+        }
+    }
+
+    #[test]
+    fn config_styles_override_the_theme() {
+        let mut config = Config {
+            theme: ThemeName::Light,
+            ..Config::default()
+        };
+        config
+            .styles
+            .insert("selected_entry".to_string(), parse_style("bold cyan"));
+
+        let theme = Theme::from_config(&config, false);
+        assert_eq!(
+            theme.style(StyleName::SelectedEntry),
+            parse_style("bold cyan")
+        );
+        assert_eq!(theme.style(StyleName::Error), parse_style("red"));
+
+        let theme = Theme::from_config(&config, true);
+        assert_eq!(
+            theme.style(StyleName::SelectedEntry),
+            Style::default().add_modifier(Modifier::BOLD)
+        );
+        assert_eq!(theme.style(StyleName::Error).fg, None);
+        assert!(theme.palette.is_empty());
+    }
+}